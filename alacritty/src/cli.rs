@@ -1,15 +1,21 @@
 use std::cmp::max;
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use alacritty_config::SerdeReplace;
+use clap::builder::{PossibleValuesParser, TypedValueParser};
 use clap::{ArgAction, Args, Parser, Subcommand, ValueHint};
 use log::{error, LevelFilter};
 use serde::{Deserialize, Serialize};
 use toml::Value;
 
+use alacritty_terminal::term::export::{ExportFormat, ExportRange};
+#[cfg(unix)]
+use alacritty_terminal::tty::serial::SerialOptions;
+#[cfg(unix)]
+use alacritty_terminal::tty::stream::Target;
 use alacritty_terminal::tty::Options as PtyOptions;
 
 use crate::config::ui_config::Program;
@@ -160,6 +166,20 @@ pub struct TerminalOptions {
     #[clap(long)]
     pub hold: bool,
 
+    /// Connect to a serial port instead of spawning a shell [example: /dev/ttyUSB0:115200,8N1].
+    #[cfg(unix)]
+    #[clap(long, value_name = "DEVICE[:BAUD[,FORMAT][,FLOW]]", value_hint = ValueHint::FilePath)]
+    pub serial: Option<SerialOptions>,
+
+    /// Attach to an inherited file descriptor or Unix socket instead of spawning a shell.
+    #[cfg(unix)]
+    #[clap(long, value_name = "FD|SOCKET", conflicts_with = "serial")]
+    pub attach: Option<Target>,
+
+    /// Write the terminal output to a log file in the specified directory.
+    #[clap(long, value_name = "DIR", value_hint = ValueHint::DirPath)]
+    pub log_session: Option<PathBuf>,
+
     /// Command and args to execute (must be last argument).
     #[clap(short = 'e', long, allow_hyphen_values = true, num_args = 1..)]
     command: Vec<String>,
@@ -172,6 +192,21 @@ impl TerminalOptions {
         Some(Program::WithArgs { program: program.clone(), args: args.to_vec() })
     }
 
+    /// Serial port to connect to, falling back to the config unless a command was passed.
+    #[cfg(unix)]
+    pub fn serial(&self, config: &UiConfig) -> Option<SerialOptions> {
+        if self.serial.is_some() || self.attach.is_some() || !self.command.is_empty() {
+            return self.serial.clone();
+        }
+
+        config.terminal.serial.as_ref().map(|serial| serial.0.clone())
+    }
+
+    /// Directory for session logs, falling back to the config.
+    pub fn log_session<'a>(&'a self, config: &'a UiConfig) -> Option<&'a Path> {
+        self.log_session.as_deref().or(config.logging.session.directory.as_deref())
+    }
+
     /// Override the [`PtyOptions`]'s fields with the [`TerminalOptions`].
     pub fn override_pty_config(&self, pty_config: &mut PtyOptions) {
         if let Some(working_directory) = &self.working_directory {
@@ -226,6 +261,7 @@ impl WindowIdentity {
 }
 
 /// Available CLI subcommands.
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 pub enum Subcommands {
     #[cfg(unix)]
@@ -255,6 +291,12 @@ pub enum SocketMessage {
 
     /// Update the Alacritty configuration.
     Config(IpcConfig),
+
+    /// Write the terminal content to a file.
+    Export(IpcExport),
+
+    /// Print the value of a user variable.
+    GetUserVar(IpcGetUserVar),
 }
 
 /// Migrate the configuration file.
@@ -328,6 +370,68 @@ pub struct IpcConfig {
     pub reset: bool,
 }
 
+/// Parameters to the `export` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IpcExport {
+    /// Path of the exported file.
+    #[clap(value_hint = ValueHint::FilePath)]
+    pub path: PathBuf,
+
+    /// Format of the exported content.
+    #[clap(
+        short,
+        long,
+        default_value = "plain",
+        value_parser = PossibleValuesParser::new(["plain", "ansi", "html"]).map(parse_export_format),
+    )]
+    pub format: ExportFormat,
+
+    /// Terminal content which should be exported.
+    #[clap(
+        short,
+        long,
+        default_value = "scrollback",
+        value_parser = PossibleValuesParser::new(["viewport", "selection", "scrollback"])
+            .map(parse_export_range),
+    )]
+    pub range: ExportRange,
+
+    /// Window ID of the exported terminal.
+    #[clap(short, long, env = "ALACRITTY_WINDOW_ID")]
+    pub window_id: u64,
+}
+
+/// Parameters to the `get-user-var` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IpcGetUserVar {
+    /// Name of the user variable.
+    pub name: String,
+
+    /// Window ID of the terminal.
+    #[clap(short, long, env = "ALACRITTY_WINDOW_ID")]
+    pub window_id: u64,
+}
+
+#[cfg(unix)]
+fn parse_export_format(format: String) -> ExportFormat {
+    match format.as_str() {
+        "ansi" => ExportFormat::Ansi,
+        "html" => ExportFormat::Html,
+        _ => ExportFormat::Plain,
+    }
+}
+
+#[cfg(unix)]
+fn parse_export_range(range: String) -> ExportRange {
+    match range.as_str() {
+        "viewport" => ExportRange::Viewport,
+        "selection" => ExportRange::Selection,
+        _ => ExportRange::Scrollback,
+    }
+}
+
 /// Parsed CLI config overrides.
 #[derive(Debug, Default)]
 pub struct ParsedOptions {
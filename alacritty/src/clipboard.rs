@@ -1,3 +1,10 @@
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
 use log::{debug, warn};
 use raw_window_handle::RawDisplayHandle;
 
@@ -13,9 +20,17 @@ use copypasta::x11_clipboard::{Primary as X11SelectionClipboard, X11ClipboardCon
 use copypasta::ClipboardContext;
 use copypasta::ClipboardProvider;
 
+use crate::config::clipboard::ClipboardConfig;
+
+/// File name of the persisted clipboard history.
+const HISTORY_FILE: &str = "clipboard_history.json";
+
 pub struct Clipboard {
     clipboard: Box<dyn ClipboardProvider>,
     selection: Option<Box<dyn ClipboardProvider>>,
+
+    /// Recently copied text.
+    pub history: ClipboardHistory,
 }
 
 impl Clipboard {
@@ -25,7 +40,11 @@ impl Clipboard {
             RawDisplayHandle::Wayland(display) => {
                 let (selection, clipboard) =
                     wayland_clipboard::create_clipboards_from_external(display.display);
-                Self { clipboard: Box::new(clipboard), selection: Some(Box::new(selection)) }
+                Self {
+                    clipboard: Box::new(clipboard),
+                    selection: Some(Box::new(selection)),
+                    history: Default::default(),
+                }
             },
             _ => Self::default(),
         }
@@ -35,19 +54,28 @@ impl Clipboard {
     /// feature.
     #[cfg(any(test, not(any(feature = "x11", target_os = "macos", windows))))]
     pub fn new_nop() -> Self {
-        Self { clipboard: Box::new(NopClipboardContext::new().unwrap()), selection: None }
+        Self {
+            clipboard: Box::new(NopClipboardContext::new().unwrap()),
+            selection: None,
+            history: Default::default(),
+        }
     }
 }
 
 impl Default for Clipboard {
     fn default() -> Self {
         #[cfg(any(target_os = "macos", windows))]
-        return Self { clipboard: Box::new(ClipboardContext::new().unwrap()), selection: None };
+        return Self {
+            clipboard: Box::new(ClipboardContext::new().unwrap()),
+            selection: None,
+            history: Default::default(),
+        };
 
         #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
         return Self {
             clipboard: Box::new(ClipboardContext::new().unwrap()),
             selection: Some(Box::new(X11ClipboardContext::<X11SelectionClipboard>::new().unwrap())),
+            history: Default::default(),
         };
 
         #[cfg(not(any(feature = "x11", target_os = "macos", windows)))]
@@ -83,3 +111,173 @@ impl Clipboard {
         }
     }
 }
+
+/// Ring of recently copied text, ordered from newest to oldest.
+#[derive(Default, Debug)]
+pub struct ClipboardHistory {
+    entries: VecDeque<String>,
+
+    /// Whether the persisted history was read already.
+    loaded: bool,
+
+    /// Whether there are entries which have not been written to disk yet.
+    dirty: bool,
+}
+
+impl ClipboardHistory {
+    /// Add a new entry to the history.
+    ///
+    /// Entries which are already present are moved to the front. Persisted entries are only
+    /// written to disk once [`ClipboardHistory::save`] is called.
+    pub fn record(&mut self, config: &ClipboardConfig, text: &str) {
+        if config.history_size == 0 || text.is_empty() {
+            return;
+        }
+
+        self.load(config);
+
+        self.push(text, config.history_size);
+
+        self.dirty |= config.persist_history;
+    }
+
+    /// All entries, starting with the most recent one.
+    pub fn entries(&mut self, config: &ClipboardConfig) -> Vec<String> {
+        self.load(config);
+        self.entries.iter().take(config.history_size).cloned().collect()
+    }
+
+    /// Insert an entry at the front, removing duplicates.
+    fn push(&mut self, text: &str, max_size: usize) {
+        self.entries.retain(|entry| entry != text);
+        self.entries.push_front(text.to_owned());
+        self.entries.truncate(max_size);
+    }
+
+    /// Merge the persisted history with entries copied since startup.
+    fn load(&mut self, config: &ClipboardConfig) {
+        if self.loaded || !config.persist_history {
+            return;
+        }
+        self.loaded = true;
+
+        let path = match history_path() {
+            Some(path) if path.exists() => path,
+            _ => return,
+        };
+
+        match read_history(&path) {
+            Ok(entries) => {
+                for entry in entries {
+                    if !self.entries.contains(&entry) {
+                        self.entries.push_back(entry);
+                    }
+                }
+                self.entries.truncate(config.history_size);
+            },
+            Err(err) => warn!("Unable to read clipboard history {path:?}: {err}"),
+        }
+    }
+
+    /// Write the history to disk, if it changed since the last write.
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        self.dirty = false;
+
+        let path = match history_path() {
+            Some(path) => path,
+            None => return,
+        };
+
+        if let Err(err) = write_history(&path, &self.entries) {
+            warn!("Unable to write clipboard history {path:?}: {err}");
+        }
+    }
+}
+
+/// Location of the persisted clipboard history.
+#[cfg(not(windows))]
+fn history_path() -> Option<PathBuf> {
+    xdg::BaseDirectories::with_prefix("alacritty").ok()?.place_state_file(HISTORY_FILE).ok()
+}
+
+/// Location of the persisted clipboard history.
+#[cfg(windows)]
+fn history_path() -> Option<PathBuf> {
+    let dir = dirs::data_local_dir()?.join("alacritty");
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(HISTORY_FILE))
+}
+
+fn read_history(path: &Path) -> io::Result<Vec<String>> {
+    let json = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Write the history, making sure it is only readable by the current user.
+///
+/// The history is written to a temporary file first, so the old history is kept intact when
+/// writing fails.
+fn write_history(path: &Path, entries: &VecDeque<String>) -> io::Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(&tmp_path)?;
+
+    // The mode is only applied to new files, so fix up leftovers from previous writes.
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+
+    file.write_all(serde_json::to_string(entries)?.as_bytes())?;
+    file.sync_all()?;
+
+    fs::rename(&tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{env, process};
+
+    #[test]
+    fn history_ring() {
+        let config = ClipboardConfig { history_size: 3, ..Default::default() };
+        let mut history = ClipboardHistory::default();
+
+        for text in ["a", "b", "", "c", "a", "d"] {
+            history.record(&config, text);
+        }
+        assert_eq!(history.entries(&config), vec!["d", "a", "c"]);
+
+        let config = ClipboardConfig { history_size: 1, ..config };
+        assert_eq!(history.entries(&config), vec!["d"]);
+
+        let config = ClipboardConfig { history_size: 0, ..config };
+        history.record(&config, "e");
+        assert!(history.entries(&config).is_empty());
+    }
+
+    #[test]
+    fn persisted_history() {
+        let path = env::temp_dir().join(format!("alacritty-clipboard-{}.json", process::id()));
+        let entries = VecDeque::from([String::from("new\nline"), String::from("\u{1b}[0m")]);
+
+        write_history(&path, &entries).unwrap();
+        let read = read_history(&path);
+        #[cfg(unix)]
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(read.unwrap(), Vec::from(entries));
+        assert!(!path.with_extension("json.tmp").exists());
+        #[cfg(unix)]
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(clippy::enum_glob_use)]
 
+use std::collections::HashMap;
 use std::fmt::{self, Debug, Display};
 
 use bitflags::bitflags;
@@ -36,6 +37,9 @@ pub struct Binding<T> {
     /// Excluded binding modes where the binding won't be activated.
     pub notmode: BindingMode,
 
+    /// User variable required to activate binding.
+    pub user_var: Option<UserVarCondition>,
+
     /// This property is used as part of the trigger detection code.
     ///
     /// For example, this might be a key like "G", or a mouse button.
@@ -60,6 +64,12 @@ impl<T: Eq> Binding<T> {
             && !mode.intersects(self.notmode)
     }
 
+    /// Check if the binding's user variable condition is satisfied.
+    #[inline]
+    pub fn user_var_matches(&self, user_vars: &HashMap<String, String>) -> bool {
+        self.user_var.as_ref().map_or(true, |condition| condition.matches(user_vars))
+    }
+
     #[inline]
     pub fn triggers_match(&self, binding: &Binding<T>) -> bool {
         // Check the binding's key and modifiers.
@@ -80,10 +90,43 @@ impl<T: Eq> Binding<T> {
             return false;
         }
 
+        // Bindings requiring different values for the same variable are mutually exclusive.
+        if let (Some(self_var), Some(binding_var)) = (&self.user_var, &binding.user_var) {
+            if self_var.name == binding_var.name
+                && self_var.value.is_some()
+                && binding_var.value.is_some()
+                && self_var.value != binding_var.value
+            {
+                return false;
+            }
+        }
+
         true
     }
 }
 
+/// Condition on a user variable set by the terminal application.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct UserVarCondition {
+    /// Name of the variable.
+    pub name: String,
+
+    /// Required value, any value is accepted when this is `None`.
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+impl UserVarCondition {
+    fn matches(&self, user_vars: &HashMap<String, String>) -> bool {
+        match (user_vars.get(&self.name), &self.value) {
+            (Some(value), Some(required)) => value == required,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
 #[derive(ConfigDeserialize, Debug, Clone, PartialEq, Eq)]
 pub enum Action {
     /// Write an escape sequence.
@@ -246,6 +289,27 @@ pub enum Action {
     /// Start a backward buffer search.
     SearchBackward,
 
+    /// Save the scrollback history as text with ANSI escape sequences.
+    SaveScrollbackAs,
+
+    /// Store current selection as HTML into clipboard.
+    CopySelectionAsHtml,
+
+    /// Spawn the shell again in the same window, keeping the scrollback history.
+    RestartShell,
+
+    /// Toggle the untrusted escape sequence policy for the window.
+    ToggleUntrusted,
+
+    /// Open the clipboard history picker.
+    ShowClipboardHistory,
+
+    /// Open the fuzzy search picker for scrollback lines.
+    FuzzySearch,
+
+    /// Ring the bell.
+    Bell,
+
     /// No action.
     None,
 }
@@ -327,6 +391,20 @@ pub enum ViAction {
     InlineSearchNext,
     /// Jump to the previous inline search match.
     InlineSearchPrevious,
+    /// Copy the selection, or wait for a motion or text object to copy.
+    Yank,
+    /// Repeat the last command with an operator.
+    Repeat,
+    /// Wait for the second key of a `g` command, like `gg` to move to the top.
+    Goto,
+    /// Set a mark at the vi cursor, named by the next character.
+    SetMark,
+    /// Jump to a mark, named by the next character.
+    JumpToMark,
+    /// Jump to the previous position in the jump list.
+    JumpBack,
+    /// Jump to the next position in the jump list.
+    JumpForward,
 }
 
 /// Search mode specific actions.
@@ -349,6 +427,14 @@ pub enum SearchAction {
     SearchHistoryPrevious,
     /// Go to the next regex in the search history.
     SearchHistoryNext,
+    /// Toggle between regex and literal search.
+    SearchToggleRegex,
+    /// Toggle case-sensitive search, instead of only matching case with uppercase characters.
+    SearchToggleCaseSensitive,
+    /// Toggle matching only whole words.
+    SearchToggleWholeWord,
+    /// Copy all matches of the active search to the clipboard, separated by newlines.
+    CopyAllMatches,
 }
 
 /// Mouse binding specific actions.
@@ -386,6 +472,7 @@ macro_rules! bindings {
                 mods: _mods,
                 mode: _mode,
                 notmode: _notmode,
+                user_var: None,
                 action: $action.into(),
             });
         )*
@@ -461,16 +548,20 @@ pub fn default_key_bindings() -> Vec<KeyBinding> {
         "i",                                +BindingMode::VI, ~BindingMode::SEARCH; Action::ToggleViMode;
         "i",                                +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollToBottom;
         "c",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ToggleViMode;
-        "y",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollLineUp;
-        "e",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollLineDown;
-        "g",                                +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollToTop;
-        "g",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollToBottom;
+        "y",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::ScrollUp;
+        "e",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::ScrollDown;
+        "g",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::Goto;
+        "g",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Bottom;
         "b",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollPageUp;
         "f",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollPageDown;
         "u",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollHalfPageUp;
         "d",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollHalfPageDown;
-        "y",                                +BindingMode::VI, ~BindingMode::SEARCH; Action::Copy;
-        "y",                                +BindingMode::VI, ~BindingMode::SEARCH; Action::ClearSelection;
+        "y",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::Yank;
+        ".",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::Repeat;
+        "m",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::SetMark;
+        "'",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::JumpToMark;
+        "o",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; ViAction::JumpBack;
+        "i",      ModifiersState::CONTROL,  +BindingMode::VI, ~BindingMode::SEARCH; ViAction::JumpForward;
         "/",                                +BindingMode::VI, ~BindingMode::SEARCH; Action::SearchForward;
         "?",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; Action::SearchBackward;
         "v",                                +BindingMode::VI, ~BindingMode::SEARCH; ViAction::ToggleNormalSelection;
@@ -510,6 +601,10 @@ pub fn default_key_bindings() -> Vec<KeyBinding> {
         "w",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::WordRight;
         "e",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::WordRightEnd;
         "%",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Bracket;
+        "{",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::ParagraphUp;
+        "}",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::ParagraphDown;
+        "(",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::SentenceLeft;
+        ")",      ModifiersState::SHIFT,    +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::SentenceRight;
         Enter,                              +BindingMode::VI, +BindingMode::SEARCH; SearchAction::SearchConfirm;
         // Plain search.
         Escape,                             +BindingMode::SEARCH; SearchAction::SearchCancel;
@@ -546,6 +641,9 @@ fn common_keybindings() -> Vec<KeyBinding> {
         "-",    ModifiersState::CONTROL;                                                                 Action::DecreaseFontSize;
         "+" => KeyLocation::Numpad, ModifiersState::CONTROL;                                             Action::IncreaseFontSize;
         "-" => KeyLocation::Numpad, ModifiersState::CONTROL;                                             Action::DecreaseFontSize;
+        "r",    ModifiersState::ALT,                             +BindingMode::SEARCH;                   SearchAction::SearchToggleRegex;
+        "c",    ModifiersState::ALT,                             +BindingMode::SEARCH;                   SearchAction::SearchToggleCaseSensitive;
+        "w",    ModifiersState::ALT,                             +BindingMode::SEARCH;                   SearchAction::SearchToggleWholeWord;
     )
 }
 
@@ -605,6 +703,9 @@ pub fn platform_key_bindings() -> Vec<KeyBinding> {
         "b",    ModifiersState::SUPER, ~BindingMode::SEARCH;                   Action::SearchBackward;
         "+" => KeyLocation::Numpad, ModifiersState::SUPER;                     Action::IncreaseFontSize;
         "-" => KeyLocation::Numpad, ModifiersState::SUPER;                     Action::DecreaseFontSize;
+        "r",    ModifiersState::SUPER   | ModifiersState::ALT, +BindingMode::SEARCH; SearchAction::SearchToggleRegex;
+        "c",    ModifiersState::SUPER   | ModifiersState::ALT, +BindingMode::SEARCH; SearchAction::SearchToggleCaseSensitive;
+        "w",    ModifiersState::SUPER   | ModifiersState::ALT, +BindingMode::SEARCH; SearchAction::SearchToggleWholeWord;
     )
 }
 
@@ -899,6 +1000,7 @@ struct RawBinding {
     mods: ModifiersState,
     mode: BindingMode,
     notmode: BindingMode,
+    user_var: Option<UserVarCondition>,
     action: Action,
 }
 
@@ -911,6 +1013,7 @@ impl RawBinding {
                 action: self.action,
                 mode: self.mode,
                 notmode: self.notmode,
+                user_var: self.user_var,
             })
         } else {
             Err(Box::new(self))
@@ -925,6 +1028,7 @@ impl RawBinding {
                 action: self.action,
                 mode: self.mode,
                 notmode: self.notmode,
+                user_var: self.user_var,
             })
         } else {
             Err(Box::new(self))
@@ -937,7 +1041,8 @@ impl<'a> Deserialize<'a> for RawBinding {
     where
         D: Deserializer<'a>,
     {
-        const FIELDS: &[&str] = &["key", "mods", "mode", "action", "chars", "mouse", "command"];
+        const FIELDS: &[&str] =
+            &["key", "mods", "mode", "action", "chars", "mouse", "command", "user_var"];
 
         enum Field {
             Key,
@@ -947,6 +1052,7 @@ impl<'a> Deserialize<'a> for RawBinding {
             Chars,
             Mouse,
             Command,
+            UserVar,
         }
 
         impl<'a> Deserialize<'a> for Field {
@@ -975,6 +1081,7 @@ impl<'a> Deserialize<'a> for RawBinding {
                             "chars" => Ok(Field::Chars),
                             "mouse" => Ok(Field::Mouse),
                             "command" => Ok(Field::Command),
+                            "user_var" => Ok(Field::UserVar),
                             _ => Err(E::unknown_field(value, FIELDS)),
                         }
                     }
@@ -1004,6 +1111,7 @@ impl<'a> Deserialize<'a> for RawBinding {
                 let mut not_mode: Option<BindingMode> = None;
                 let mut mouse: Option<MouseButton> = None;
                 let mut command: Option<Program> = None;
+                let mut user_var: Option<UserVarCondition> = None;
 
                 use de::Error;
 
@@ -1108,6 +1216,13 @@ impl<'a> Deserialize<'a> for RawBinding {
 
                             command = Some(map.next_value::<Program>()?);
                         },
+                        Field::UserVar => {
+                            if user_var.is_some() {
+                                return Err(<V::Error as Error>::duplicate_field("user_var"));
+                            }
+
+                            user_var = Some(map.next_value::<UserVarCondition>()?);
+                        },
                     }
                 }
 
@@ -1142,7 +1257,7 @@ impl<'a> Deserialize<'a> for RawBinding {
                     return Err(V::Error::custom("bindings require mouse button or key"));
                 }
 
-                Ok(RawBinding { mode, notmode: not_mode, action, key, mouse, mods })
+                Ok(RawBinding { mode, notmode: not_mode, user_var, action, key, mouse, mods })
             }
         }
 
@@ -1253,6 +1368,7 @@ mod tests {
                 action: Action::None,
                 mode: BindingMode::empty(),
                 notmode: BindingMode::empty(),
+                user_var: None,
                 trigger: Default::default(),
             }
         }
@@ -1443,4 +1559,38 @@ mod tests {
         assert!(!binding.is_triggered_by(BindingMode::ALT_SCREEN, mods, &t));
         assert!(!binding.is_triggered_by(BindingMode::ALT_SCREEN | BindingMode::VI, mods, &t));
     }
+
+    #[test]
+    fn binding_trigger_user_var() {
+        let condition = UserVarCondition { name: String::from("profile"), value: None };
+        let any = MockBinding { user_var: Some(condition.clone()), ..MockBinding::default() };
+        let condition = UserVarCondition { value: Some(String::from("prod")), ..condition };
+        let prod = MockBinding { user_var: Some(condition), ..MockBinding::default() };
+
+        let mut user_vars = HashMap::new();
+        assert!(MockBinding::default().user_var_matches(&user_vars));
+        assert!(!any.user_var_matches(&user_vars));
+        assert!(!prod.user_var_matches(&user_vars));
+
+        user_vars.insert(String::from("profile"), String::from("dev"));
+        assert!(any.user_var_matches(&user_vars));
+        assert!(!prod.user_var_matches(&user_vars));
+
+        user_vars.insert(String::from("profile"), String::from("prod"));
+        assert!(prod.user_var_matches(&user_vars));
+    }
+
+    #[test]
+    fn binding_mismatches_user_var_value() {
+        let condition = UserVarCondition { name: String::from("profile"), value: None };
+        let any = MockBinding { user_var: Some(condition.clone()), ..MockBinding::default() };
+        let prod = UserVarCondition { value: Some(String::from("prod")), ..condition.clone() };
+        let prod = MockBinding { user_var: Some(prod), ..MockBinding::default() };
+        let dev = UserVarCondition { value: Some(String::from("dev")), ..condition };
+        let dev = MockBinding { user_var: Some(dev), ..MockBinding::default() };
+
+        assert!(!prod.triggers_match(&dev));
+        assert!(prod.triggers_match(&any));
+        assert!(prod.triggers_match(&MockBinding::default()));
+    }
 }
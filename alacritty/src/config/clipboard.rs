@@ -0,0 +1,16 @@
+use alacritty_config_derive::ConfigDeserialize;
+
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ClipboardConfig {
+    /// Maximum number of entries in the clipboard history.
+    pub history_size: usize,
+
+    /// Keep the clipboard history across restarts.
+    pub persist_history: bool,
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self { history_size: 50, persist_history: false }
+    }
+}
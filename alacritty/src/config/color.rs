@@ -2,7 +2,9 @@ use serde::de::Error as SerdeError;
 use serde::{Deserialize, Deserializer};
 
 use alacritty_config_derive::ConfigDeserialize;
+use alacritty_terminal::term::cell::Flags;
 
+use crate::config::ui_config::LazyRegex;
 use crate::display::color::{CellRgb, Rgb};
 
 #[derive(ConfigDeserialize, Clone, Debug, Default, PartialEq, Eq)]
@@ -18,6 +20,7 @@ pub struct Colors {
     pub search: SearchColors,
     pub line_indicator: LineIndicatorColors,
     pub hints: HintColors,
+    pub highlight: Vec<HighlightRule>,
     pub transparent_background_colors: bool,
     pub draw_bold_text_with_bright_colors: bool,
     footer_bar: BarColors,
@@ -75,6 +78,63 @@ impl Default for HintEndColors {
     }
 }
 
+/// Colors and flags applied to all visible matches of a regex.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HighlightRule {
+    pub regex: LazyRegex,
+    #[serde(default = "cell_foreground")]
+    pub foreground: CellRgb,
+    #[serde(default = "cell_background")]
+    pub background: CellRgb,
+    #[serde(default)]
+    flags: Vec<HighlightFlag>,
+}
+
+impl HighlightRule {
+    /// Cell flags added to the matches.
+    pub fn flags(&self) -> Flags {
+        self.flags.iter().fold(Flags::empty(), |flags, flag| flags | Flags::from(*flag))
+    }
+}
+
+fn cell_foreground() -> CellRgb {
+    CellRgb::CellForeground
+}
+
+fn cell_background() -> CellRgb {
+    CellRgb::CellBackground
+}
+
+#[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HighlightFlag {
+    Bold,
+    Italic,
+    Dim,
+    Underline,
+    DoubleUnderline,
+    Undercurl,
+    DottedUnderline,
+    DashedUnderline,
+    Strikeout,
+}
+
+impl From<HighlightFlag> for Flags {
+    fn from(flag: HighlightFlag) -> Self {
+        match flag {
+            HighlightFlag::Bold => Flags::BOLD,
+            HighlightFlag::Italic => Flags::ITALIC,
+            HighlightFlag::Dim => Flags::DIM,
+            HighlightFlag::Underline => Flags::UNDERLINE,
+            HighlightFlag::DoubleUnderline => Flags::DOUBLE_UNDERLINE,
+            HighlightFlag::Undercurl => Flags::UNDERCURL,
+            HighlightFlag::DottedUnderline => Flags::DOTTED_UNDERLINE,
+            HighlightFlag::DashedUnderline => Flags::DASHED_UNDERLINE,
+            HighlightFlag::Strikeout => Flags::STRIKEOUT,
+        }
+    }
+}
+
 #[derive(Deserialize, Copy, Clone, Default, Debug, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
 pub struct IndexedColor {
@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{de, Deserialize, Deserializer};
+
+use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
+use alacritty_terminal::event_loop::session_log::{self, LogFormat, SessionLogOptions};
+
+use crate::config::ui_config::StringVisitor;
+
+/// Default file name template for session logs.
+const DEFAULT_FILE_NAME: &str = "alacritty-{date}_{time}-{pid}-{window_id}.log";
+
+#[derive(ConfigDeserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct Logging {
+    /// Transcripts of the terminal output.
+    pub session: SessionLogging,
+}
+
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SessionLogging {
+    /// Directory for the session logs, logging is disabled without it.
+    pub directory: Option<PathBuf>,
+
+    /// Format of the session logs.
+    pub format: SerdeLogFormat,
+
+    /// Template for the log file names.
+    pub file_name: String,
+
+    /// Size in MiB after which a log file is rotated.
+    pub max_size: u64,
+
+    /// Number of rotated log files which are kept, the log is truncated when this is `0`.
+    pub max_files: usize,
+}
+
+impl Default for SessionLogging {
+    fn default() -> Self {
+        Self {
+            file_name: DEFAULT_FILE_NAME.into(),
+            max_files: 5,
+            directory: Default::default(),
+            max_size: Default::default(),
+            format: Default::default(),
+        }
+    }
+}
+
+impl SessionLogging {
+    /// Session log options for a new window.
+    pub fn options(&self, directory: &Path, window_id: u64, pid: u32) -> SessionLogOptions {
+        let file_name =
+            session_log::expand_file_name(&self.file_name, window_id, pid, SystemTime::now());
+
+        SessionLogOptions {
+            path: directory.join(file_name),
+            format: self.format.0,
+            max_size: (self.max_size > 0).then(|| self.max_size.saturating_mul(1024 * 1024)),
+            max_files: self.max_files,
+        }
+    }
+}
+
+#[derive(SerdeReplace, Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SerdeLogFormat(pub LogFormat);
+
+impl<'de> Deserialize<'de> for SerdeLogFormat {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = deserializer.deserialize_str(StringVisitor)?;
+        value.parse().map(SerdeLogFormat).map_err(de::Error::custom)
+    }
+}
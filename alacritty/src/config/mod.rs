@@ -11,15 +11,18 @@ use toml::ser::Error as TomlSeError;
 use toml::{Table, Value};
 
 pub mod bell;
+pub mod clipboard;
 pub mod color;
 pub mod cursor;
 pub mod debug;
 pub mod font;
+pub mod logging;
 pub mod monitor;
 pub mod scrolling;
 pub mod selection;
 pub mod serde_utils;
 pub mod terminal;
+pub mod trigger;
 pub mod ui_config;
 pub mod window;
 
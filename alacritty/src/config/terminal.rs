@@ -2,14 +2,207 @@ use serde::{de, Deserialize, Deserializer};
 use toml::Value;
 
 use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
-use alacritty_terminal::term::Osc52;
+use alacritty_terminal::term::flood::FloodProtection as TermFloodProtection;
+use alacritty_terminal::term::{EscapeCategories, Osc52};
+#[cfg(unix)]
+use alacritty_terminal::tty::serial::SerialOptions;
 
 use crate::config::ui_config::StringVisitor;
 
-#[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq)]
+#[derive(ConfigDeserialize, Default, Clone, Debug, PartialEq)]
 pub struct Terminal {
     /// OSC52 support mode.
     pub osc52: SerdeOsc52,
+
+    /// Serial port used instead of spawning a shell.
+    #[cfg(unix)]
+    pub serial: Option<SerdeSerial>,
+
+    /// Protection against PTY output floods.
+    pub flood_protection: FloodProtection,
+
+    /// Behavior after the shell exited.
+    pub on_exit: OnExit,
+
+    /// Confirmation before closing windows with running processes.
+    pub confirm_close: ConfirmClose,
+
+    /// Protection of passwords entered into the terminal.
+    pub secure_input: SecureInput,
+
+    /// Permissions for escape sequences.
+    pub policy: EscapePolicy,
+
+    /// Confirmation before pasting potentially dangerous text.
+    pub paste_protection: PasteProtection,
+}
+
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PasteProtection {
+    /// Confirm multi-line pastes while the application did not enable bracketed paste.
+    pub multiline: bool,
+
+    /// Confirm pastes containing control characters.
+    pub control_characters: bool,
+
+    /// Confirm pastes containing any of these words.
+    pub patterns: Vec<String>,
+}
+
+impl Default for PasteProtection {
+    fn default() -> Self {
+        Self { multiline: true, control_characters: true, patterns: vec![String::from("sudo")] }
+    }
+}
+
+/// Permissions for escape sequences of trusted and untrusted windows.
+#[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EscapePolicy {
+    /// Permissions of trusted windows, allowing everything by default.
+    pub trusted: PolicyRules,
+
+    /// Permissions of untrusted windows, denying everything by default.
+    pub untrusted: PolicyRules,
+}
+
+impl EscapePolicy {
+    /// Permission for an escape sequence category.
+    pub fn permission(&self, category: EscapeCategories, untrusted: bool) -> Permission {
+        if untrusted {
+            self.untrusted.permission(category).unwrap_or(Permission::Deny)
+        } else {
+            self.trusted.permission(category).unwrap_or(Permission::Allow)
+        }
+    }
+}
+
+#[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PolicyRules {
+    /// Window title changes.
+    pub title: Option<Permission>,
+
+    /// Palette color changes.
+    pub colors: Option<Permission>,
+
+    /// Clipboard access through OSC 52.
+    pub clipboard: Option<Permission>,
+
+    /// Hyperlinks through OSC 8.
+    pub hyperlinks: Option<Permission>,
+
+    /// Reports of the terminal state.
+    pub queries: Option<Permission>,
+
+    /// User variables through OSC 1337.
+    pub user_vars: Option<Permission>,
+}
+
+impl PolicyRules {
+    fn permission(&self, category: EscapeCategories) -> Option<Permission> {
+        match category {
+            EscapeCategories::TITLE => self.title,
+            EscapeCategories::COLORS => self.colors,
+            EscapeCategories::CLIPBOARD => self.clipboard,
+            EscapeCategories::HYPERLINKS => self.hyperlinks,
+            EscapeCategories::QUERIES => self.queries,
+            EscapeCategories::USER_VARS => self.user_vars,
+            _ => None,
+        }
+    }
+}
+
+/// Handling of an escape sequence category.
+#[derive(SerdeReplace, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Apply the escape sequences.
+    Allow,
+
+    /// Ignore the escape sequences.
+    Deny,
+
+    /// Ask before applying escape sequences of this category.
+    Prompt,
+}
+
+#[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SecureInput {
+    /// Detect password prompts based on the PTY's echo and canonical mode.
+    pub enabled: bool,
+
+    /// Ignore clipboard read requests while a password is entered.
+    pub block_clipboard_read: bool,
+}
+
+impl Default for SecureInput {
+    fn default() -> Self {
+        Self { enabled: true, block_clipboard_read: false }
+    }
+}
+
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConfirmClose {
+    /// Ask before closing a window while a process other than the shell is running.
+    pub enabled: bool,
+
+    /// Processes which can be closed without confirmation.
+    pub allowlist: Vec<String>,
+}
+
+impl Default for ConfirmClose {
+    fn default() -> Self {
+        Self { enabled: true, allowlist: vec![String::from("tmux"), String::from("screen")] }
+    }
+}
+
+impl ConfirmClose {
+    /// Check if closing a window with this foreground process requires confirmation.
+    pub fn required(&self, process: &str) -> bool {
+        self.enabled && !self.allowlist.iter().any(|allowed| allowed == process)
+    }
+}
+
+/// Behavior after the shell exited.
+#[derive(SerdeReplace, Deserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnExit {
+    /// Close the window.
+    #[default]
+    Close,
+
+    /// Keep the window open and show the exit status.
+    Hold,
+
+    /// Close the window if the shell exited successfully, otherwise hold it.
+    HoldOnError,
+
+    /// Spawn the shell again in the same window.
+    Restart,
+}
+
+#[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FloodProtection {
+    /// Throughput in MiB/s above which output is considered a flood.
+    pub threshold: usize,
+
+    /// Maximum number of scrollback lines kept while the terminal is flooded.
+    pub history_limit: Option<usize>,
+}
+
+impl Default for FloodProtection {
+    fn default() -> Self {
+        let default = TermFloodProtection::default();
+        Self { threshold: default.threshold / 1024 / 1024, history_limit: default.history_limit }
+    }
+}
+
+impl From<FloodProtection> for TermFloodProtection {
+    fn from(flood_protection: FloodProtection) -> Self {
+        Self {
+            threshold: flood_protection.threshold.saturating_mul(1024 * 1024),
+            history_limit: flood_protection.history_limit,
+        }
+    }
 }
 
 #[derive(SerdeReplace, Default, Copy, Clone, Debug, PartialEq)]
@@ -24,3 +217,18 @@ impl<'de> Deserialize<'de> for SerdeOsc52 {
         Osc52::deserialize(Value::String(value)).map(SerdeOsc52).map_err(de::Error::custom)
     }
 }
+
+#[cfg(unix)]
+#[derive(SerdeReplace, Clone, Debug, PartialEq, Eq)]
+pub struct SerdeSerial(pub SerialOptions);
+
+#[cfg(unix)]
+impl<'de> Deserialize<'de> for SerdeSerial {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = deserializer.deserialize_str(StringVisitor)?;
+        value.parse().map(SerdeSerial).map_err(de::Error::custom)
+    }
+}
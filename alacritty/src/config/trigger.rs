@@ -0,0 +1,161 @@
+use std::fmt::{self, Debug, Formatter};
+use std::time::Duration;
+
+use log::warn;
+use regex_automata::meta::Regex;
+use serde::de::{Error as SerdeError, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::config::bindings::Action;
+use crate::config::ui_config::Program;
+use crate::config::LOG_TARGET_CONFIG;
+
+/// Default minimum time between two runs of a trigger in milliseconds.
+const DEFAULT_RATE_LIMIT: u64 = 1000;
+
+/// Action run when a new line of terminal output matches a regex.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trigger {
+    /// Regex matched against every completed line.
+    pub regex: TriggerRegex,
+
+    /// Action executed for every match.
+    pub action: TriggerAction,
+
+    /// Minimum time between two runs in milliseconds.
+    rate_limit: u64,
+}
+
+impl Trigger {
+    /// Minimum time between two runs.
+    pub fn rate_limit(&self) -> Duration {
+        Duration::from_millis(self.rate_limit)
+    }
+}
+
+impl<'de> Deserialize<'de> for Trigger {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TriggerVisitor;
+        impl<'a> Visitor<'a> for TriggerVisitor {
+            type Value = Trigger;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("a mapping")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: MapAccess<'a>,
+            {
+                let mut regex = None;
+                let mut actions = Vec::new();
+                let mut rate_limit = DEFAULT_RATE_LIMIT;
+
+                while let Some((key, value)) = map.next_entry::<String, toml::Value>()? {
+                    match key.as_str() {
+                        "regex" => {
+                            regex =
+                                Some(TriggerRegex::deserialize(value).map_err(M::Error::custom)?)
+                        },
+                        "action" => {
+                            let action = Action::deserialize(value).map_err(M::Error::custom)?;
+                            actions.push(TriggerAction::Action(action));
+                        },
+                        "command" => {
+                            let program = Program::deserialize(value).map_err(M::Error::custom)?;
+                            actions.push(TriggerAction::Command(program));
+                        },
+                        "write" => {
+                            let text = String::deserialize(value).map_err(M::Error::custom)?;
+                            actions.push(TriggerAction::Write(text));
+                        },
+                        "notify" => {
+                            let text = String::deserialize(value).map_err(M::Error::custom)?;
+                            actions.push(TriggerAction::Notify(text));
+                        },
+                        "rate_limit" => {
+                            rate_limit = u64::deserialize(value).map_err(M::Error::custom)?
+                        },
+                        key => {
+                            warn!(target: LOG_TARGET_CONFIG, "Unrecognized trigger field: {key}")
+                        },
+                    }
+                }
+
+                let regex = regex.ok_or_else(|| M::Error::missing_field("regex"))?;
+
+                if actions.len() != 1 {
+                    return Err(M::Error::custom(
+                        "trigger requires exactly one of `action`, `command`, `write` or `notify`",
+                    ));
+                }
+                let action = actions.remove(0);
+
+                Ok(Trigger { regex, action, rate_limit })
+            }
+        }
+
+        deserializer.deserialize_map(TriggerVisitor)
+    }
+}
+
+/// Actions for triggers.
+///
+/// Text of [`TriggerAction::Write`] and [`TriggerAction::Notify`] can reference capture groups
+/// of the regex using `$1` or `${name}`. Arguments of [`TriggerAction::Command`] consisting of
+/// only a capture group reference are replaced with the captured text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TriggerAction {
+    /// Built-in action.
+    Action(Action),
+
+    /// Command which is spawned.
+    Command(Program),
+
+    /// Text written to the PTY.
+    Write(String),
+
+    /// Text shown as a desktop notification.
+    Notify(String),
+}
+
+/// Compiled trigger regex.
+#[derive(Clone)]
+pub struct TriggerRegex {
+    pattern: String,
+    regex: Regex,
+}
+
+impl TriggerRegex {
+    /// Compiled regex.
+    pub fn regex(&self) -> &Regex {
+        &self.regex
+    }
+}
+
+impl<'de> Deserialize<'de> for TriggerRegex {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let pattern = String::deserialize(deserializer)?;
+        let regex = Regex::new(&pattern).map_err(D::Error::custom)?;
+        Ok(Self { pattern, regex })
+    }
+}
+
+impl Debug for TriggerRegex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TriggerRegex").field(&self.pattern).finish()
+    }
+}
+
+impl PartialEq for TriggerRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+impl Eq for TriggerRegex {}
@@ -22,14 +22,17 @@ use crate::config::bindings::{
     self, Action, Binding, BindingKey, KeyBinding, KeyLocation, ModeWrapper, ModsWrapper,
     MouseBinding,
 };
+use crate::config::clipboard::ClipboardConfig;
 use crate::config::color::Colors;
 use crate::config::cursor::Cursor;
 use crate::config::debug::Debug;
 use crate::config::font::Font;
+use crate::config::logging::Logging;
 use crate::config::mouse::{Mouse, MouseBindings};
 use crate::config::scrolling::Scrolling;
 use crate::config::selection::Selection;
 use crate::config::terminal::Terminal;
+use crate::config::trigger::Trigger;
 use crate::config::window::WindowConfig;
 use crate::config::LOG_TARGET_CONFIG;
 
@@ -52,6 +55,9 @@ pub struct UiConfig {
     /// Selection configuration.
     pub selection: Selection,
 
+    /// Clipboard history configuration.
+    pub clipboard: ClipboardConfig,
+
     /// Font configuration.
     pub font: Font,
 
@@ -64,6 +70,9 @@ pub struct UiConfig {
     /// Debug options.
     pub debug: Debug,
 
+    /// Session logging options.
+    pub logging: Logging,
+
     /// Send escape sequences using the alt key.
     #[config(removed = "It's now always set to 'true'. If you're on macOS use \
                         'window.option_as_alt' to alter behavior of Option")]
@@ -85,6 +94,9 @@ pub struct UiConfig {
     /// Regex hints for interacting with terminal content.
     pub hints: Hints,
 
+    /// Actions run when new output matches a regex.
+    pub triggers: Vec<Trigger>,
+
     /// Offer IPC through a unix socket.
     #[cfg(unix)]
     pub ipc_socket: bool,
@@ -134,6 +146,7 @@ impl Default for UiConfig {
             alt_send_esc: Default::default(),
             scrolling: Default::default(),
             selection: Default::default(),
+            clipboard: Default::default(),
             keyboard: Default::default(),
             terminal: Default::default(),
             import: Default::default(),
@@ -143,7 +156,9 @@ impl Default for UiConfig {
             shell: Default::default(),
             mouse: Default::default(),
             debug: Default::default(),
+            logging: Default::default(),
             hints: Default::default(),
+            triggers: Default::default(),
             font: Default::default(),
             bell: Default::default(),
             env: Default::default(),
@@ -160,7 +175,9 @@ impl UiConfig {
             vi_mode_cursor_style: self.cursor.vi_mode_style(),
             default_cursor_style: self.cursor.style(),
             osc52: self.terminal.osc52.0,
+            flood_protection: self.terminal.flood_protection.into(),
             kitty_keyboard: true,
+            track_completed_lines: !self.triggers.is_empty(),
         }
     }
 
@@ -197,6 +214,7 @@ impl UiConfig {
                 mods: binding.mods.0,
                 mode: binding.mode.mode,
                 notmode: binding.mode.not_mode,
+                user_var: None,
                 action: Action::Hint(hint.clone()),
             };
 
@@ -582,7 +600,7 @@ impl LazyRegexVariant {
         let regex_search = match RegexSearch::new(regex) {
             Ok(regex_search) => regex_search,
             Err(err) => {
-                error!("could not compile hint regex: {err}");
+                error!("could not compile regex: {err}");
                 *self = Self::Uncompilable;
                 return None;
             },
@@ -37,6 +37,9 @@ pub struct WindowConfig {
     /// Use dynamic title.
     pub dynamic_title: bool,
 
+    /// Template for the window title.
+    pub title_template: Option<String>,
+
     /// Information to identify a particular window.
     #[config(flatten)]
     pub identity: Identity,
@@ -67,6 +70,7 @@ impl Default for WindowConfig {
     fn default() -> Self {
         Self {
             dynamic_title: true,
+            title_template: Default::default(),
             blur: Default::default(),
             embed: Default::default(),
             padding: Default::default(),
@@ -85,6 +89,14 @@ impl Default for WindowConfig {
 }
 
 impl WindowConfig {
+    /// Check if the title depends on the terminal's foreground process.
+    #[inline]
+    pub fn title_tracks_process(&self) -> bool {
+        self.title_template
+            .as_ref()
+            .is_some_and(|template| template.contains("{process}") || template.contains("{cwd}"))
+    }
+
     #[inline]
     pub fn dimensions(&self) -> Option<Dimensions> {
         let (lines, columns) = (self.dimensions.lines, self.dimensions.columns);
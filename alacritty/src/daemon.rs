@@ -4,6 +4,7 @@ use std::fs;
 use std::io;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
 
 #[rustfmt::skip]
@@ -12,7 +13,6 @@ use {
     std::error::Error,
     std::os::unix::process::CommandExt,
     std::os::unix::io::RawFd,
-    std::path::PathBuf,
 };
 
 #[cfg(not(windows))]
@@ -82,17 +82,71 @@ where
     }
 }
 
+/// Foreground process of a terminal.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ForegroundProcess {
+    /// Name of the process.
+    pub name: String,
+
+    /// Working directory of the process.
+    pub cwd: Option<PathBuf>,
+}
+
+/// Get the foreground process of a terminal, falling back to the shell.
+#[cfg(not(windows))]
+pub fn foreground_process(master_fd: RawFd, shell_pid: u32) -> ForegroundProcess {
+    let pid = foreground_pid(master_fd, shell_pid);
+    ForegroundProcess { name: process_name(pid), cwd: process_cwd(pid).ok() }
+}
+
+/// Get name of the foreground process, unless it is the shell itself.
+#[cfg(not(windows))]
+pub fn foreground_process_name(master_fd: RawFd, shell_pid: u32) -> Option<String> {
+    let pid = foreground_pid(master_fd, shell_pid);
+    (pid != shell_pid as pid_t).then(|| process_name(pid))
+}
+
 /// Get working directory of controlling process.
 #[cfg(not(windows))]
 pub fn foreground_process_path(
     master_fd: RawFd,
     shell_pid: u32,
 ) -> Result<PathBuf, Box<dyn Error>> {
-    let mut pid = unsafe { libc::tcgetpgrp(master_fd) };
+    process_cwd(foreground_pid(master_fd, shell_pid))
+}
+
+/// Get the process group ID of the terminal's foreground process.
+#[cfg(not(windows))]
+fn foreground_pid(master_fd: RawFd, shell_pid: u32) -> pid_t {
+    let pid = unsafe { libc::tcgetpgrp(master_fd) };
     if pid < 0 {
-        pid = shell_pid as pid_t;
+        shell_pid as pid_t
+    } else {
+        pid
     }
+}
+
+/// Get the name of a process.
+#[cfg(not(windows))]
+fn process_name(pid: pid_t) -> String {
+    #[cfg(not(any(target_os = "macos", target_os = "freebsd")))]
+    let comm_path = format!("/proc/{}/comm", pid);
+    #[cfg(target_os = "freebsd")]
+    let comm_path = format!("/compat/linux/proc/{}/comm", pid);
+
+    #[cfg(not(target_os = "macos"))]
+    let name = fs::read_to_string(comm_path).map(|name| name.trim_end().to_owned());
+
+    #[cfg(target_os = "macos")]
+    let name = macos::proc::name(pid);
 
+    // Still identify the process if its name is not accessible.
+    name.unwrap_or_else(|_| pid.to_string())
+}
+
+/// Get the working directory of a process.
+#[cfg(not(windows))]
+fn process_cwd(pid: pid_t) -> Result<PathBuf, Box<dyn Error>> {
     #[cfg(not(any(target_os = "macos", target_os = "freebsd")))]
     let link_path = format!("/proc/{}/cwd", pid);
     #[cfg(target_os = "freebsd")]
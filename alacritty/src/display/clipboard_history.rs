@@ -0,0 +1,126 @@
+//! Overlay for picking entries from the clipboard history.
+
+use crate::display::paste;
+
+/// Symbol shown in place of line breaks.
+const NEWLINE_SYMBOL: &str = "↵";
+
+/// Clipboard history picker state.
+#[derive(Debug, Default)]
+pub struct ClipboardPicker {
+    /// History entries, starting with the most recent one.
+    entries: Vec<String>,
+
+    /// Text used to filter the entries.
+    filter: String,
+
+    /// Indices of all entries matching the filter.
+    matches: Vec<usize>,
+
+    /// Index of the selected match.
+    selected: usize,
+}
+
+impl ClipboardPicker {
+    pub fn new(entries: Vec<String>) -> Self {
+        let matches = (0..entries.len()).collect();
+        Self { entries, matches, ..Default::default() }
+    }
+
+    /// Current filter text.
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// Add a character to the filter.
+    pub fn push(&mut self, c: char) {
+        self.filter.push(c);
+        self.update_matches();
+    }
+
+    /// Remove the last character from the filter.
+    pub fn pop(&mut self) {
+        self.filter.pop();
+        self.update_matches();
+    }
+
+    /// Move the selection, positive deltas select older entries.
+    pub fn select(&mut self, delta: isize) {
+        let max = self.matches.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(max);
+    }
+
+    /// Text of the selected entry.
+    pub fn selected_entry(&self) -> Option<&str> {
+        let index = *self.matches.get(self.selected)?;
+        Some(self.entries[index].as_str())
+    }
+
+    /// Number of entries matching the filter and total number of entries.
+    pub fn count(&self) -> (usize, usize) {
+        (self.matches.len(), self.entries.len())
+    }
+
+    /// Single-line previews of the matches visible in a list of the specified height.
+    ///
+    /// The list is scrolled to always include the selected entry, which is marked as `true`.
+    pub fn visible_matches(&self, height: usize) -> impl Iterator<Item = (String, bool)> + '_ {
+        let skip = (self.selected + 1).saturating_sub(height);
+        self.matches.iter().enumerate().skip(skip).take(height).map(|(i, &index)| {
+            let preview = self.entries[index].lines().map(paste::printable);
+            (preview.collect::<Vec<_>>().join(NEWLINE_SYMBOL), i == self.selected)
+        })
+    }
+
+    /// Update the entries matching the filter, ignoring case.
+    fn update_matches(&mut self) {
+        let filter = self.filter.to_lowercase();
+        let entries = self.entries.iter().enumerate();
+        self.matches = entries
+            .filter(|(_, entry)| entry.to_lowercase().contains(&filter))
+            .map(|(index, _)| index)
+            .collect();
+        self.selected = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_entries() {
+        let entries = vec![String::from("Git log"), String::from("cargo\ntest"), "git".into()];
+        let mut picker = ClipboardPicker::new(entries);
+        assert_eq!(picker.count(), (3, 3));
+
+        for c in "GIT".chars() {
+            picker.push(c);
+        }
+        assert_eq!(picker.count(), (2, 3));
+        assert_eq!(picker.selected_entry(), Some("Git log"));
+
+        picker.select(5);
+        assert_eq!(picker.selected_entry(), Some("git"));
+
+        picker.push('x');
+        assert_eq!(picker.selected_entry(), None);
+
+        picker.pop();
+        assert_eq!(picker.filter(), "GIT");
+        assert_eq!(picker.selected_entry(), Some("Git log"));
+    }
+
+    #[test]
+    fn visible_matches() {
+        let entries = vec![String::from("a\tb"), String::from("c\r\nd"), "e".into()];
+        let mut picker = ClipboardPicker::new(entries);
+
+        let visible: Vec<_> = picker.visible_matches(2).collect();
+        assert_eq!(visible, vec![(String::from("a    b"), true), (String::from("c↵d"), false)]);
+
+        picker.select(2);
+        let visible: Vec<_> = picker.visible_matches(2).collect();
+        assert_eq!(visible, vec![(String::from("c↵d"), false), (String::from("e"), true)]);
+    }
+}
@@ -7,7 +7,7 @@ use serde::de::{Error as SerdeError, Visitor};
 use serde::{Deserialize, Deserializer};
 
 use alacritty_config_derive::SerdeReplace;
-use alacritty_terminal::term::color::COUNT;
+use alacritty_terminal::term::color::{Colors as TermColors, COUNT};
 use alacritty_terminal::vte::ansi::{NamedColor, Rgb as VteRgb};
 
 use crate::config::color::Colors;
@@ -31,6 +31,18 @@ impl<'a> From<&'a Colors> for List {
     }
 }
 
+impl From<&List> for TermColors {
+    fn from(list: &List) -> TermColors {
+        let mut colors = TermColors::default();
+
+        for (index, color) in list.0.iter().enumerate() {
+            colors[index] = Some(color.0);
+        }
+
+        colors
+    }
+}
+
 impl List {
     pub fn fill_named(&mut self, colors: &Colors) {
         // Normals.
@@ -11,8 +11,10 @@ use alacritty_terminal::term::search::{Match, RegexSearch};
 use alacritty_terminal::term::{self, RenderableContent as TerminalContent, Term, TermMode};
 use alacritty_terminal::vte::ansi::{Color, CursorShape, NamedColor};
 
+use crate::config::color::HighlightRule;
 use crate::config::UiConfig;
 use crate::display::color::{CellRgb, List, Rgb, DIM_FACTOR};
+use crate::display::highlight::HighlightState;
 use crate::display::hint::{self, HintState};
 use crate::display::{Display, SizeInfo};
 use crate::event::SearchState;
@@ -30,6 +32,7 @@ pub struct RenderableContent<'a> {
     cursor_point: Point<usize>,
     search: Option<HintMatches<'a>>,
     hint: Option<Hint<'a>>,
+    highlights: &'a HighlightState,
     config: &'a UiConfig,
     colors: &'a List,
     focused_match: Option<&'a Match>,
@@ -74,6 +77,7 @@ impl<'a> RenderableContent<'a> {
 
         Self {
             colors: &display.colors,
+            highlights: &display.highlights,
             size: &display.size_info,
             cursor: RenderableCursor::new_hidden(),
             terminal_content,
@@ -108,6 +112,13 @@ impl<'a> RenderableContent<'a> {
         self.terminal_content.selection
     }
 
+    /// Get the highlight rule matching a cell.
+    fn highlight_rule(&self, point: Point) -> Option<&'a HighlightRule> {
+        let point = term::point_to_viewport(self.terminal_content.display_offset, point)?;
+        let rule = self.highlights.rule_at(point)?;
+        self.config.colors.highlight.get(rule)
+    }
+
     /// Assemble the information required to render the terminal cursor.
     fn renderable_cursor(&mut self, cell: &RenderableCell) -> RenderableCursor {
         // Cursor colors.
@@ -264,6 +275,10 @@ impl RenderableCell {
                 (colors.search.matches.foreground, colors.search.matches.background)
             };
             Self::compute_cell_rgb(&mut fg, &mut bg, &mut bg_alpha, config_fg, config_bg);
+        } else if let Some(rule) = content.highlight_rule(cell.point) {
+            let (config_fg, config_bg) = (rule.foreground, rule.background);
+            Self::compute_cell_rgb(&mut fg, &mut bg, &mut bg_alpha, config_fg, config_bg);
+            flags.insert(rule.flags());
         }
 
         // Apply transparency to all renderable cells if `transparent_background_colors` is set
@@ -0,0 +1,364 @@
+//! Fuzzy search over the lines of the scrollback history.
+
+use std::cmp::Reverse;
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::Term;
+
+/// Score for every matched character.
+const SCORE_MATCH: i32 = 16;
+
+/// Bonus for characters directly following the previously matched character.
+const BONUS_CONSECUTIVE: i32 = 8;
+
+/// Bonus for characters at the start of a word.
+const BONUS_WORD_START: i32 = 8;
+
+/// Penalty for every skipped character between two matched characters.
+const PENALTY_GAP: i32 = 1;
+
+/// Number of lines collected from the scrollback history in one step.
+const LINES_PER_STEP: usize = 1_000;
+
+/// Number of lines scored in one step.
+const SCORES_PER_STEP: usize = 10_000;
+
+/// Line of the scrollback history, including all its wrapped lines.
+#[derive(Debug)]
+struct HistoryLine {
+    /// First grid line when the line was collected.
+    line: Line,
+
+    /// Text of the line.
+    text: String,
+
+    /// Lowercase text used for matching.
+    lowercase: String,
+}
+
+/// Fuzzy search picker state.
+///
+/// Lines are collected and scored in steps, starting at the bottom of the scrollback history, so
+/// large histories do not block the UI thread.
+#[derive(Debug, Default)]
+pub struct FuzzySearch {
+    /// All non-empty lines collected so far, from newest to oldest.
+    lines: Vec<HistoryLine>,
+
+    /// Next line to collect, `None` once the top of the scrollback history was reached.
+    next_line: Option<Line>,
+
+    /// Text used to filter the lines.
+    query: String,
+
+    /// Number of lines scored for the current query.
+    scored: usize,
+
+    /// Score and index of all scored lines matching the query, from best to worst.
+    matches: Vec<(i32, usize)>,
+
+    /// Index of the selected match.
+    selected: usize,
+}
+
+impl FuzzySearch {
+    pub fn new<T>(term: &Term<T>) -> Self {
+        Self { next_line: Some(term.bottommost_line()), ..Default::default() }
+    }
+
+    /// Collect and score the next batch of lines.
+    ///
+    /// Returns `true` once all lines have been collected and scored.
+    pub fn step<T>(&mut self, term: &Term<T>) -> bool {
+        self.collect_lines(term);
+
+        let query = self.query.to_lowercase();
+        let end = self.lines.len().min(self.scored + SCORES_PER_STEP);
+        let lines = self.lines[self.scored..end].iter().enumerate();
+        let scored = self.scored;
+        self.matches.extend(
+            lines.filter_map(|(i, line)| Some((fuzzy_score(&query, &line.lowercase)?, scored + i))),
+        );
+        self.scored = end;
+        self.sort_matches();
+
+        self.done()
+    }
+
+    /// Whether all lines have been collected and scored.
+    pub fn done(&self) -> bool {
+        self.next_line.is_none() && self.scored == self.lines.len()
+    }
+
+    /// Current query text.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Add a character to the query.
+    ///
+    /// Since the query only gets more specific, just the previous matches are scored again.
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+
+        let query = self.query.to_lowercase();
+        let lines = &self.lines;
+        self.matches.retain_mut(|(score, index)| {
+            match fuzzy_score(&query, &lines[*index].lowercase) {
+                Some(new_score) => {
+                    *score = new_score;
+                    true
+                },
+                None => false,
+            }
+        });
+        self.sort_matches();
+    }
+
+    /// Remove the last character from the query.
+    ///
+    /// All lines need to be scored again, which happens in the following steps.
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.matches.clear();
+        self.scored = 0;
+        self.selected = 0;
+    }
+
+    /// Move the selection, positive deltas select worse matches.
+    pub fn select(&mut self, delta: isize) {
+        let max = self.matches.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(max);
+    }
+
+    /// Current grid line of the selected match.
+    ///
+    /// Since the terminal content might have moved since the line was collected, this looks for
+    /// the closest line with the same text, preferring lines above the original position.
+    pub fn selected_line<T>(&self, term: &Term<T>) -> Option<Line> {
+        let (_, index) = *self.matches.get(self.selected)?;
+        let history_line = &self.lines[index];
+
+        let topmost_line = term.topmost_line();
+        let bottommost_line = term.bottommost_line();
+        let is_match =
+            |(start, end)| term.bounds_to_string(start, end).trim_end() == history_line.text;
+
+        // Look for the line above the original position, since new output moves content up.
+        let origin = history_line.line.max(topmost_line).min(bottommost_line);
+        let mut line = origin;
+        loop {
+            let bounds = line_bounds(term, line);
+            if is_match(bounds) {
+                return Some(bounds.0.line);
+            }
+
+            if bounds.0.line <= topmost_line {
+                break;
+            }
+            line = bounds.0.line - 1;
+        }
+
+        let mut line = line_bounds(term, origin).1.line + 1;
+        while line <= bottommost_line {
+            let bounds = line_bounds(term, line);
+            if is_match(bounds) {
+                return Some(bounds.0.line);
+            }
+            line = bounds.1.line + 1;
+        }
+
+        None
+    }
+
+    /// Number of lines matching the query and total number of lines collected so far.
+    pub fn count(&self) -> (usize, usize) {
+        (self.matches.len(), self.lines.len())
+    }
+
+    /// Matches visible in a list of the specified height.
+    ///
+    /// The list is scrolled to always include the selected match, which is marked as `true`.
+    pub fn visible_matches(&self, height: usize) -> impl Iterator<Item = (&str, bool)> + '_ {
+        let skip = (self.selected + 1).saturating_sub(height);
+        self.matches.iter().enumerate().skip(skip).take(height).map(|(i, &(_, index))| {
+            let text = self.lines[index].text.as_str();
+            (text, i == self.selected)
+        })
+    }
+
+    /// Collect the next batch of lines, moving up from the bottom of the scrollback history.
+    fn collect_lines<T>(&mut self, term: &Term<T>) {
+        let mut line = match self.next_line {
+            // Lines might have been removed by terminal changes since the last step.
+            Some(line) => line.min(term.bottommost_line()),
+            None => return,
+        };
+
+        let topmost_line = term.topmost_line();
+        for _ in 0..LINES_PER_STEP {
+            if line < topmost_line {
+                self.next_line = None;
+                return;
+            }
+
+            let (start, end) = line_bounds(term, line);
+            let text = term.bounds_to_string(start, end);
+            let text = text.trim_end();
+
+            if !text.is_empty() {
+                let lowercase = text.to_lowercase();
+                self.lines.push(HistoryLine { line: start.line, text: text.to_owned(), lowercase });
+            }
+
+            line = start.line - 1;
+        }
+
+        self.next_line = Some(line);
+    }
+
+    /// Sort matches from best to worst.
+    ///
+    /// Lines with the same score are ordered from newest to oldest. Once the user moved the
+    /// selection away from the best match, the selected line stays selected.
+    fn sort_matches(&mut self) {
+        let selected = match self.selected {
+            0 => None,
+            selected => self.matches.get(selected).map(|&(_, index)| index),
+        };
+
+        self.matches.sort_unstable_by_key(|&(score, index)| (Reverse(score), index));
+
+        self.selected = selected
+            .and_then(|selected| self.matches.iter().position(|&(_, index)| index == selected))
+            .unwrap_or(0);
+    }
+}
+
+/// Start and end of the line containing a grid line, including all its wrapped lines.
+fn line_bounds<T>(term: &Term<T>, line: Line) -> (Point, Point) {
+    let start = term.line_search_left(Point::new(line, Column(0)));
+    let end = term.line_search_right(Point::new(line, Column(0)));
+    (Point::new(start.line, Column(0)), end)
+}
+
+/// Score of a text for a query, higher is better.
+///
+/// All whitespace separated terms of the query must match the text, with the characters of each
+/// term appearing in order. Returns `None` if the text does not match.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    query.split_whitespace().map(|term| term_score(term, text)).sum()
+}
+
+/// Best score of a single query term.
+fn term_score(term: &str, text: &str) -> Option<i32> {
+    let first = term.chars().next()?;
+    text.char_indices()
+        .filter(|&(_, c)| c == first)
+        .filter_map(|(start, _)| term_score_at(term, text, start))
+        .max()
+}
+
+/// Score of a term, greedily matching its characters from a start offset.
+fn term_score_at(term: &str, text: &str, start: usize) -> Option<i32> {
+    let mut previous = text[..start].chars().next_back();
+    let mut text_chars = text[start..].chars();
+    let mut score = 0;
+    let mut gap = 0;
+
+    for (i, c) in term.chars().enumerate() {
+        loop {
+            let text_char = text_chars.next()?;
+            let word_start = previous.map_or(true, |previous| !previous.is_alphanumeric());
+            previous = Some(text_char);
+
+            if text_char != c {
+                gap += 1;
+                continue;
+            }
+
+            score += SCORE_MATCH;
+            if word_start {
+                score += BONUS_WORD_START;
+            }
+            if i > 0 && gap == 0 {
+                score += BONUS_CONSECUTIVE;
+            }
+            score -= gap * PENALTY_GAP;
+            gap = 0;
+            break;
+        }
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::term::test::mock_term;
+
+    #[test]
+    fn scores() {
+        assert_eq!(fuzzy_score("xyz", "timeout"), None);
+        assert_eq!(fuzzy_score("timeout redis", "redis connection timeout").map(|_| ()), Some(()));
+        assert_eq!(fuzzy_score("timeout redis", "redis connection"), None);
+
+        // Consecutive characters and word starts are preferred.
+        let consecutive = fuzzy_score("redis", "error: redis").unwrap();
+        let scattered = fuzzy_score("redis", "read disk").unwrap();
+        assert!(consecutive > scattered);
+
+        let word_start = fuzzy_score("cat", "cat file").unwrap();
+        let inside_word = fuzzy_score("cat", "concat file").unwrap();
+        assert!(word_start > inside_word);
+    }
+
+    #[test]
+    fn rank_lines() {
+        let term = mock_term("redis timeout\r\n\r\nreading\nfile timed out\r\nredis timeout");
+        let mut fuzzy_search = FuzzySearch::new(&term);
+        assert!(fuzzy_search.step(&term));
+        assert_eq!(fuzzy_search.count(), (3, 3));
+        assert_eq!(fuzzy_search.selected_line(&term), Some(Line(4)));
+
+        for c in "timeout redis".chars() {
+            fuzzy_search.push(c);
+        }
+        assert_eq!(fuzzy_search.count(), (2, 3));
+        assert_eq!(fuzzy_search.selected_line(&term), Some(Line(4)));
+
+        fuzzy_search.select(1);
+        assert_eq!(fuzzy_search.selected_line(&term), Some(Line(0)));
+
+        let visible: Vec<_> = fuzzy_search.visible_matches(1).collect();
+        assert_eq!(visible, vec![("redis timeout", true)]);
+
+        fuzzy_search.pop();
+        fuzzy_search.pop();
+        assert_eq!(fuzzy_search.count(), (0, 3));
+        assert!(fuzzy_search.step(&term));
+        assert_eq!(fuzzy_search.count(), (3, 3));
+        assert_eq!(fuzzy_search.selected_line(&term), Some(Line(4)));
+
+        fuzzy_search.select(5);
+        assert_eq!(fuzzy_search.selected_line(&term), Some(Line(2)));
+    }
+
+    #[test]
+    fn resolve_moved_line() {
+        let term = mock_term("one\r\ntwo\r\nthree");
+        let mut fuzzy_search = FuzzySearch::new(&term);
+        fuzzy_search.step(&term);
+        "two".chars().for_each(|c| fuzzy_search.push(c));
+
+        // Content moved up by one line since the lines were collected.
+        let term = mock_term("two\r\nthree\r\nfour");
+        assert_eq!(fuzzy_search.selected_line(&term), Some(Line(0)));
+
+        let term = mock_term("one\r\nthree");
+        assert_eq!(fuzzy_search.selected_line(&term), None);
+    }
+}
@@ -0,0 +1,206 @@
+//! Persistent highlighting of regex matches in the visible terminal content.
+
+use std::mem;
+use std::ops::{Range, RangeInclusive};
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Direction, Line, Point};
+use alacritty_terminal::term::search::{Match, RegexIter};
+use alacritty_terminal::term::{LineDamageBounds, Term};
+
+use crate::config::color::HighlightRule;
+use crate::display::hint;
+
+/// Part of a highlighted match on a single line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Segment {
+    /// Highlighted columns.
+    columns: RangeInclusive<usize>,
+
+    /// Index of the rule which matched.
+    rule: usize,
+}
+
+/// Cached highlight rule matches for every line of the viewport.
+///
+/// Matches are only searched again for lines which were damaged since the last frame, so
+/// highlighting an unchanged viewport is cheap.
+#[derive(Debug, Default)]
+pub struct HighlightState {
+    /// Highlighted segments of each viewport line, ordered by rule.
+    lines: Vec<Vec<Segment>>,
+
+    /// Display offset of the cached matches.
+    display_offset: usize,
+
+    /// Whether all lines have to be searched again.
+    stale: bool,
+}
+
+impl HighlightState {
+    /// Search all lines again on the next update.
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /// Update the matches of all damaged lines.
+    ///
+    /// Without damage information all lines are searched again. Returns the viewport lines whose
+    /// highlighting changed.
+    pub fn update<T>(
+        &mut self,
+        term: &Term<T>,
+        rules: &[HighlightRule],
+        damage: Option<&[LineDamageBounds]>,
+    ) -> Vec<usize> {
+        if rules.is_empty() {
+            let changed = (0..self.lines.len()).filter(|&i| !self.lines[i].is_empty()).collect();
+            self.lines.clear();
+            return changed;
+        }
+
+        let screen_lines = term.screen_lines();
+        let display_offset = term.grid().display_offset();
+        let full_update =
+            self.stale || self.lines.len() != screen_lines || self.display_offset != display_offset;
+
+        let damage = match damage {
+            Some(damage) if !full_update => damage,
+            _ => return self.update_all(term, rules),
+        };
+
+        // Get the logical lines containing damaged lines, since matches can span wrapped lines.
+        let mut bounds: Vec<(Line, Line)> = damage
+            .iter()
+            .map(|damage| {
+                let line = Line(damage.line as i32 - display_offset as i32);
+                let start = term.line_search_left(Point::new(line, Column(0))).line;
+                let end = term.line_search_right(Point::new(line, Column(0))).line;
+                (start, end)
+            })
+            .collect();
+        bounds.dedup();
+
+        let mut changed = Vec::new();
+        for (start, end) in bounds {
+            let viewport_lines = self.viewport_lines(start, end);
+            let old_lines: Vec<_> =
+                viewport_lines.clone().map(|line| mem::take(&mut self.lines[line])).collect();
+
+            let start = Point::new(start, Column(0));
+            let end = Point::new(end, term.last_column());
+            for (index, rule) in rules.iter().enumerate() {
+                rule.regex.with_compiled(|regex| {
+                    for regex_match in RegexIter::new(start, end, Direction::Right, term, regex) {
+                        self.insert(&regex_match, index);
+                    }
+                });
+            }
+
+            let lines = viewport_lines.zip(old_lines);
+            changed.extend(lines.filter(|(line, old)| self.lines[*line] != *old).map(|(l, _)| l));
+        }
+
+        changed
+    }
+
+    /// Rule highlighting a point in the viewport.
+    pub fn rule_at(&self, point: Point<usize>) -> Option<usize> {
+        let segments = self.lines.get(point.line)?;
+        let segment = segments.iter().find(|segment| segment.columns.contains(&point.column.0))?;
+        Some(segment.rule)
+    }
+
+    /// Search all visible lines.
+    fn update_all<T>(&mut self, term: &Term<T>, rules: &[HighlightRule]) -> Vec<usize> {
+        let old_lines = mem::replace(&mut self.lines, vec![Vec::new(); term.screen_lines()]);
+        self.display_offset = term.grid().display_offset();
+        self.stale = false;
+
+        for (index, rule) in rules.iter().enumerate() {
+            rule.regex.with_compiled(|regex| {
+                for regex_match in hint::visible_regex_match_iter(term, regex) {
+                    self.insert(&regex_match, index);
+                }
+            });
+        }
+
+        let old_line = |line: usize| old_lines.get(line).map_or(&[][..], Vec::as_slice);
+        (0..self.lines.len()).filter(|&line| old_line(line) != self.lines[line]).collect()
+    }
+
+    /// Add the visible part of a match.
+    fn insert(&mut self, regex_match: &Match, rule: usize) {
+        let (start, end) = (*regex_match.start(), *regex_match.end());
+        for line in self.viewport_lines(start.line, end.line) {
+            let grid_line = Line(line as i32 - self.display_offset as i32);
+            let first = if grid_line == start.line { start.column.0 } else { 0 };
+            let last = if grid_line == end.line { end.column.0 } else { usize::MAX };
+            self.lines[line].push(Segment { columns: first..=last, rule });
+        }
+    }
+
+    /// Viewport lines between two grid lines.
+    fn viewport_lines(&self, start: Line, end: Line) -> Range<usize> {
+        let offset = self.display_offset as i32;
+        let start = (start.0 + offset).clamp(0, self.lines.len() as i32) as usize;
+        let end = (end.0 + offset + 1).clamp(0, self.lines.len() as i32) as usize;
+        start..end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::term::test::mock_term;
+
+    fn rule(regex: &str) -> HighlightRule {
+        toml::from_str(&format!("regex = '{regex}'")).unwrap()
+    }
+
+    #[test]
+    fn highlight_damaged_lines() {
+        let mut term = mock_term("ok\r\nERROR: fail\r\nerror");
+        let rules = [rule("ERROR"), rule("fail|ERR")];
+
+        let mut highlights = HighlightState::default();
+        assert_eq!(highlights.update(&term, &rules, None), vec![1]);
+        assert_eq!(highlights.rule_at(Point::new(1, Column(0))), Some(0));
+        assert_eq!(highlights.rule_at(Point::new(1, Column(4))), Some(0));
+        assert_eq!(highlights.rule_at(Point::new(1, Column(5))), None);
+        assert_eq!(highlights.rule_at(Point::new(1, Column(8))), Some(1));
+        assert_eq!(highlights.rule_at(Point::new(2, Column(0))), None);
+
+        // Only damaged lines are searched again.
+        term.grid_mut()[Line(2)][Column(0)].c = 'E';
+        term.grid_mut()[Line(2)][Column(1)].c = 'R';
+        term.grid_mut()[Line(2)][Column(2)].c = 'R';
+        term.grid_mut()[Line(1)][Column(0)].c = 'e';
+        let damage = [LineDamageBounds::new(2, 0, 2)];
+        assert_eq!(highlights.update(&term, &rules, Some(&damage)), vec![2]);
+        assert_eq!(highlights.rule_at(Point::new(2, Column(0))), Some(1));
+        assert_eq!(highlights.rule_at(Point::new(1, Column(0))), Some(0));
+
+        highlights.invalidate();
+        assert_eq!(highlights.update(&term, &rules, Some(&damage)), vec![1]);
+        assert_eq!(highlights.rule_at(Point::new(1, Column(0))), None);
+    }
+
+    #[test]
+    fn highlight_wrapped_match() {
+        let term = mock_term("xxERR\nOR\r\nok");
+        let rules = [rule("ERROR")];
+
+        let mut highlights = HighlightState::default();
+        highlights.update(&term, &rules, None);
+
+        // Damage on the second line updates the entire wrapped line.
+        let damage = [LineDamageBounds::new(1, 0, 0)];
+        assert!(highlights.update(&term, &rules, Some(&damage)).is_empty());
+        assert_eq!(highlights.rule_at(Point::new(0, Column(1))), None);
+        assert_eq!(highlights.rule_at(Point::new(0, Column(2))), Some(0));
+        assert_eq!(highlights.rule_at(Point::new(1, Column(1))), Some(0));
+        assert_eq!(highlights.rule_at(Point::new(1, Column(2))), None);
+    }
+}
@@ -2,6 +2,7 @@
 //! GPU drawing.
 
 use std::cmp;
+use std::collections::HashMap;
 use std::fmt::{self, Formatter};
 use std::mem::{self, ManuallyDrop};
 use std::num::NonZeroU32;
@@ -28,6 +29,8 @@ use alacritty_terminal::grid::Dimensions as TermDimensions;
 use alacritty_terminal::index::{Column, Direction, Line, Point};
 use alacritty_terminal::selection::Selection;
 use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::flood::FloodStats;
+use alacritty_terminal::term::search::SearchMode;
 use alacritty_terminal::term::{
     self, point_to_viewport, LineDamageBounds, Term, TermDamage, TermMode, MIN_COLUMNS,
     MIN_SCREEN_LINES,
@@ -40,24 +43,36 @@ use crate::config::window::Dimensions;
 use crate::config::window::StartupMode;
 use crate::config::UiConfig;
 use crate::display::bell::VisualBell;
+use crate::display::clipboard_history::ClipboardPicker;
 use crate::display::color::{List, Rgb};
 use crate::display::content::{RenderableContent, RenderableCursor};
 use crate::display::cursor::IntoRects;
 use crate::display::damage::{damage_y_to_viewport_y, DamageTracker};
+use crate::display::fuzzy_search::FuzzySearch;
+use crate::display::highlight::HighlightState;
 use crate::display::hint::{HintMatch, HintState};
 use crate::display::meter::Meter;
+use crate::display::paste::PastePreview;
+use crate::display::title::TitleState;
 use crate::display::window::Window;
 use crate::event::{Event, EventType, Mouse, SearchState};
+use crate::input::vi::ViCommandState;
 use crate::message_bar::{MessageBuffer, MessageType};
 use crate::renderer::rects::{RenderLine, RenderLines, RenderRect};
 use crate::renderer::{self, GlyphCache, Renderer};
 use crate::scheduler::{Scheduler, TimerId, Topic};
 use crate::string::{ShortenDirection, StrShortener};
 
+pub mod clipboard_history;
 pub mod color;
 pub mod content;
 pub mod cursor;
+pub mod fuzzy_search;
+pub mod highlight;
 pub mod hint;
+pub mod paste;
+pub mod search;
+pub mod title;
 pub mod window;
 
 mod bell;
@@ -65,10 +80,29 @@ mod damage;
 mod meter;
 
 /// Label for the forward terminal search bar.
-const FORWARD_SEARCH_LABEL: &str = "Search: ";
+const FORWARD_SEARCH_LABEL: &str = "Search";
 
 /// Label for the backward terminal search bar.
-const BACKWARD_SEARCH_LABEL: &str = "Backward Search: ";
+const BACKWARD_SEARCH_LABEL: &str = "Backward Search";
+
+/// Label for the password prompt indicator.
+const SECURE_INPUT_LABEL: &str = "[Secure Input]";
+
+/// Keys available in the paste preview.
+const PASTE_PREVIEW_HELP: &str =
+    "[Enter] Paste  [S] Paste as single line  [Esc] Cancel  [Up/Down] Scroll";
+
+/// Number of lines above the text in the paste preview.
+pub const PASTE_PREVIEW_HEADER_LINES: usize = 2;
+
+/// Label for the clipboard history picker.
+const CLIPBOARD_HISTORY_LABEL: &str = "Clipboard";
+
+/// Label for the fuzzy search picker.
+const FUZZY_SEARCH_LABEL: &str = "Fuzzy Search";
+
+/// Maximum number of entries visible in picker overlays.
+const PICKER_LINES: usize = 10;
 
 /// The character used to shorten the visible text like uri preview or search regex.
 const SHORTENER: char = '…';
@@ -360,6 +394,9 @@ pub struct Display {
     /// State of the keyboard hints.
     pub hint_state: HintState,
 
+    /// Matches of the highlight rules in the viewport.
+    pub highlights: HighlightState,
+
     /// Unprocessed display updates.
     pub pending_update: DisplayUpdate,
 
@@ -378,6 +415,21 @@ pub struct Display {
     /// Font size used by the window.
     pub font_size: FontSize,
 
+    /// Whether the terminal is prompting for a password.
+    pub secure_input: bool,
+
+    /// State used to render the window title.
+    pub title_state: TitleState,
+
+    /// Paste waiting for confirmation.
+    pub paste_preview: Option<PastePreview>,
+
+    /// Clipboard history picker, while it is open.
+    pub clipboard_picker: Option<ClipboardPicker>,
+
+    /// Fuzzy search over the scrollback history, while it is open.
+    pub fuzzy_search: Option<FuzzySearch>,
+
     // Mouse point position when highlighting hints.
     hint_mouse_point: Option<Point>,
 
@@ -522,6 +574,12 @@ impl Display {
             hint_mouse_point: Default::default(),
             pending_update: Default::default(),
             cursor_hidden: Default::default(),
+            secure_input: Default::default(),
+            title_state: Default::default(),
+            paste_preview: Default::default(),
+            clipboard_picker: Default::default(),
+            fuzzy_search: Default::default(),
+            highlights: Default::default(),
             meter: Default::default(),
             ime: Default::default(),
         })
@@ -712,6 +770,27 @@ impl Display {
         info!("Width: {}, Height: {}", self.size_info.width(), self.size_info.height());
     }
 
+    /// Update the window title from the title state.
+    pub fn update_title(&mut self, config: &UiConfig, user_vars: &HashMap<String, String>) {
+        let window_config = &config.window;
+        let title = match &self.title_state.terminal_title {
+            Some(title) if window_config.dynamic_title => title,
+            _ => &window_config.identity.title,
+        };
+
+        let title = match &window_config.title_template {
+            Some(template) => {
+                let (columns, lines) = (self.size_info.columns(), self.size_info.screen_lines());
+                self.title_state.render(template, title, user_vars, columns, lines)
+            },
+            None => title.clone(),
+        };
+
+        if title != self.window.title() {
+            self.window.set_title(title);
+        }
+    }
+
     /// Draw the screen.
     ///
     /// A reference to Term whose state is being drawn must be provided.
@@ -724,7 +803,19 @@ impl Display {
         message_buffer: &MessageBuffer,
         config: &UiConfig,
         search_state: &mut SearchState,
+        vi_command: &ViCommandState,
     ) {
+        // Collect terminal damage, which is also used to update only the changed highlights.
+        let terminal_damage = match terminal.damage() {
+            TermDamage::Full => None,
+            TermDamage::Partial(damaged_lines) => Some(damaged_lines.collect::<Vec<_>>()),
+        };
+        terminal.reset_damage();
+
+        let highlight_rules = &config.colors.highlight;
+        let highlight_damage =
+            self.highlights.update(&terminal, highlight_rules, terminal_damage.as_deref());
+
         // Collect renderable content before the terminal is dropped.
         let mut content = RenderableContent::new(config, self, &terminal, search_state);
         let mut grid_cells = Vec::new();
@@ -745,19 +836,38 @@ impl Display {
         let vi_mode = terminal.mode().contains(TermMode::VI);
         let vi_cursor_point = if vi_mode { Some(terminal.vi_mode_cursor.point) } else { None };
 
+        // Collect vi mode marks visible in the viewport.
+        let marks: Vec<_> = if vi_mode {
+            let marks = terminal.marks().iter();
+            marks
+                .filter_map(|(name, point)| Some((name, point_to_viewport(display_offset, point)?)))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
         // Add damage from the terminal.
         if self.collect_damage() {
-            match terminal.damage() {
-                TermDamage::Full => self.damage_tracker.frame().mark_fully_damaged(),
-                TermDamage::Partial(damaged_lines) => {
+            match terminal_damage {
+                None => self.damage_tracker.frame().mark_fully_damaged(),
+                Some(damaged_lines) => {
                     for damage in damaged_lines {
                         self.damage_tracker.frame().damage_line(damage);
                     }
                 },
             }
-            terminal.reset_damage();
+
+            // Damage the entire line when highlights changed, since matches can grow or shrink.
+            let last_column = size_info.columns() - 1;
+            for line in highlight_damage {
+                let damage = LineDamageBounds::new(line, 0, last_column);
+                self.damage_tracker.frame().damage_line(damage);
+            }
         }
 
+        terminal.flood_stats_mut().frame_rendered();
+        let flood_stats = terminal.flood_stats().clone();
+
         // Drop terminal as early as possible to free lock.
         drop(terminal);
 
@@ -837,6 +947,9 @@ impl Display {
         let mut rects = lines.rects(&metrics, &size_info);
 
         if let Some(vi_cursor_point) = vi_cursor_point {
+            let vi_cursor_viewport_point = point_to_viewport(display_offset, vi_cursor_point);
+            self.draw_marks(config, &marks, vi_cursor_viewport_point);
+
             // Indicate vi mode by showing the cursor's position in the top right corner.
             let line = (-vi_cursor_point.line.0 + size_info.bottommost_line().0) as usize;
             let obstructed_column = Some(vi_cursor_point)
@@ -848,6 +961,12 @@ impl Display {
             self.draw_line_indicator(config, total_lines, None, display_offset);
         };
 
+        if self.secure_input {
+            // Avoid overlapping with the line indicator.
+            let line = usize::from(vi_mode || search_state.regex().is_some());
+            self.draw_secure_input_indicator(config, line);
+        }
+
         // Draw cursor.
         rects.extend(cursor.rects(&size_info, config.cursor.thickness()));
 
@@ -868,15 +987,20 @@ impl Display {
         // Handle IME positioning and search bar rendering.
         let ime_position = match search_state.regex() {
             Some(regex) => {
-                let search_label = match search_state.direction() {
-                    Direction::Right => FORWARD_SEARCH_LABEL,
-                    Direction::Left => BACKWARD_SEARCH_LABEL,
-                };
-
-                let search_text = Self::format_search(regex, search_label, size_info.columns());
+                let search_label =
+                    Self::search_label(search_state.direction(), search_state.mode());
+
+                // Reserve space for the match count at the end of the search bar.
+                let match_count = search_state
+                    .match_counter()
+                    .map(|counter| format!(" {}", counter.label(search_state.focused_match())))
+                    .filter(|count| count.len() * 2 <= size_info.columns())
+                    .unwrap_or_default();
+                let max_width = size_info.columns() - match_count.len();
+                let search_text = Self::format_search(regex, &search_label, max_width);
 
                 // Render the search bar.
-                self.draw_search(config, &search_text);
+                self.draw_search(config, &search_text, &match_count);
 
                 // Draw search bar cursor.
                 let line = size_info.screen_lines();
@@ -899,8 +1023,8 @@ impl Display {
             },
         };
 
-        // Handle IME.
-        if self.ime.is_enabled() {
+        // Handle IME, without revealing passwords in the preview.
+        if self.ime.is_enabled() && !self.secure_input {
             if let Some(point) = ime_position {
                 let (fg, bg) = if search_state.regex().is_some() {
                     (config.colors.footer_bar_foreground(), config.colors.footer_bar_background())
@@ -923,6 +1047,7 @@ impl Display {
             let bg = match message.ty() {
                 MessageType::Error => config.colors.normal.red,
                 MessageType::Warning => config.colors.normal.yellow,
+                MessageType::Info => config.colors.normal.blue,
             };
 
             let x = 0;
@@ -959,7 +1084,18 @@ impl Display {
             self.renderer.draw_rects(&size_info, &metrics, rects);
         }
 
-        self.draw_render_timer(config);
+        self.draw_clipboard_picker(config);
+
+        self.draw_fuzzy_search(config);
+
+        self.draw_paste_preview(config);
+
+        self.draw_render_timer(config, &flood_stats);
+
+        // Show pending vi commands, unless they're obstructed by the search bar.
+        if vi_mode && vi_command.is_pending() && search_state.regex().is_none() {
+            self.draw_vi_command(config, &vi_command.to_string());
+        }
 
         // Draw hyperlink uri preview.
         if has_highlighted_hint {
@@ -1002,6 +1138,7 @@ impl Display {
         self.damage_tracker.debug = config.debug.highlight_damage;
         self.visual_bell.update_config(&config.bell);
         self.colors = List::from(&config.colors);
+        self.highlights.invalidate();
     }
 
     /// Update the mouse/vi mode cursor hint highlighting.
@@ -1146,6 +1283,28 @@ impl Display {
         self.window.update_ime_position(ime_popup_point, &self.size_info);
     }
 
+    /// Label of the search bar, including all enabled search modes.
+    fn search_label(direction: Direction, mode: SearchMode) -> String {
+        let label = match direction {
+            Direction::Right => FORWARD_SEARCH_LABEL,
+            Direction::Left => BACKWARD_SEARCH_LABEL,
+        };
+
+        let modes = [
+            (SearchMode::LITERAL, "literal"),
+            (SearchMode::CASE_SENSITIVE, "case"),
+            (SearchMode::WHOLE_WORD, "word"),
+        ];
+        let modes: Vec<_> =
+            modes.iter().filter(|(flag, _)| mode.contains(*flag)).map(|(_, name)| *name).collect();
+
+        if modes.is_empty() {
+            format!("{label}: ")
+        } else {
+            format!("{label} [{}]: ", modes.join(", "))
+        }
+    }
+
     /// Format search regex to account for the cursor and fullwidth characters.
     fn format_search(search_regex: &str, search_label: &str, max_width: usize) -> String {
         let label_len = search_label.len();
@@ -1238,10 +1397,10 @@ impl Display {
 
     /// Draw current search regex.
     #[inline(never)]
-    fn draw_search(&mut self, config: &UiConfig, text: &str) {
-        // Assure text length is at least num_cols.
+    fn draw_search(&mut self, config: &UiConfig, text: &str, match_count: &str) {
+        // Assure text length is at least num_cols, with the match count aligned to the right.
         let num_cols = self.size_info.columns();
-        let text = format!("{:<1$}", text, num_cols);
+        let text = format!("{:<1$}{match_count}", text, num_cols - match_count.len());
 
         let point = Point::new(self.size_info.screen_lines(), Column(0));
 
@@ -1258,14 +1417,39 @@ impl Display {
         );
     }
 
+    /// Draw the pending vi command in the bottom right corner of the terminal.
+    #[inline(never)]
+    fn draw_vi_command(&mut self, config: &UiConfig, text: &str) {
+        let columns = self.size_info.columns();
+        let column = Column(columns.saturating_sub(text.len()));
+        let point = Point::new(self.size_info.screen_lines().saturating_sub(1), column);
+
+        if self.collect_damage() {
+            let damage = LineDamageBounds::new(point.line, column.0, columns - 1);
+            self.damage_tracker.frame().damage_line(damage);
+            // Damage the command for the next frame, to remove it once it was completed.
+            self.damage_tracker.next_frame().damage_line(damage);
+        }
+
+        let fg = config.colors.footer_bar_foreground();
+        let bg = config.colors.footer_bar_background();
+        let glyph_cache = &mut self.glyph_cache;
+        self.renderer.draw_string(point, fg, bg, text.chars(), &self.size_info, glyph_cache);
+    }
+
     /// Draw render timer.
     #[inline(never)]
-    fn draw_render_timer(&mut self, config: &UiConfig) {
+    fn draw_render_timer(&mut self, config: &UiConfig, flood_stats: &FloodStats) {
         if !config.debug.render_timer {
             return;
         }
 
-        let timing = format!("{:.3} usec", self.meter.average());
+        let timing = format!(
+            "{:.3} usec | {:.2} MiB/s | {} dropped",
+            self.meter.average(),
+            flood_stats.throughput() as f64 / 1024. / 1024.,
+            flood_stats.dropped_frames(),
+        );
         let point = Point::new(self.size_info.screen_lines().saturating_sub(2), Column(0));
         let fg = config.colors.primary.background;
         let bg = config.colors.normal.red;
@@ -1281,6 +1465,38 @@ impl Display {
         self.renderer.draw_string(point, fg, bg, timing.chars(), &self.size_info, glyph_cache);
     }
 
+    /// Draw the names of vi mode marks in the last column of their line.
+    #[inline(never)]
+    fn draw_marks(
+        &mut self,
+        config: &UiConfig,
+        marks: &[(char, Point<usize>)],
+        vi_cursor_point: Option<Point<usize>>,
+    ) {
+        let colors = &config.colors;
+        let fg = colors.line_indicator.foreground.unwrap_or(colors.primary.background);
+        let bg = colors.line_indicator.background.unwrap_or(colors.primary.foreground);
+        let column = self.size_info.last_column();
+
+        for &(name, mark) in marks {
+            let point = Point::new(mark.line, column);
+
+            if self.collect_damage() {
+                let damage = LineDamageBounds::new(point.line, column.0, column.0);
+                self.damage_tracker.frame().damage_line(damage);
+                // Damage it on the next frame in case the mark is removed.
+                self.damage_tracker.next_frame().damage_line(damage);
+            }
+
+            // Do not render anything if it would obscure the vi mode cursor.
+            if vi_cursor_point != Some(point) {
+                let glyph_cache = &mut self.glyph_cache;
+                let name = std::iter::once(name);
+                self.renderer.draw_string(point, fg, bg, name, &self.size_info, glyph_cache);
+            }
+        }
+    }
+
     /// Draw an indicator for the position of a line in history.
     #[inline(never)]
     fn draw_line_indicator(
@@ -1313,6 +1529,140 @@ impl Display {
         }
     }
 
+    /// Draw an indicator for password prompts.
+    #[inline(never)]
+    fn draw_secure_input_indicator(&mut self, config: &UiConfig, line: usize) {
+        let columns = self.size_info.columns();
+        let column = Column(columns.saturating_sub(SECURE_INPUT_LABEL.len()));
+        let point = Point::new(line, column);
+
+        if self.collect_damage() {
+            let damage = LineDamageBounds::new(point.line, point.column.0, columns - 1);
+            self.damage_tracker.frame().damage_line(damage);
+            // Damage it on the next frame in case it goes away.
+            self.damage_tracker.next_frame().damage_line(damage);
+        }
+
+        let fg = config.colors.footer_bar_foreground();
+        let bg = config.colors.footer_bar_background();
+        let glyph_cache = &mut self.glyph_cache;
+        let text = SECURE_INPUT_LABEL.chars();
+        self.renderer.draw_string(point, fg, bg, text, &self.size_info, glyph_cache);
+    }
+
+    /// Draw the confirmation overlay for pastes.
+    #[inline(never)]
+    fn draw_paste_preview(&mut self, config: &UiConfig) {
+        let preview = match &self.paste_preview {
+            Some(preview) => preview,
+            None => return,
+        };
+
+        if self.collect_damage() {
+            self.damage_tracker.frame().mark_fully_damaged();
+            // Damage it on the next frame in case it goes away.
+            self.damage_tracker.next_frame().mark_fully_damaged();
+        }
+
+        let columns = self.size_info.columns();
+        let screen_lines = self.size_info.screen_lines();
+        let text_lines = screen_lines.saturating_sub(PASTE_PREVIEW_HEADER_LINES);
+
+        let header = preview.header();
+        let bar_colors =
+            (config.colors.footer_bar_foreground(), config.colors.footer_bar_background());
+        let text_colors = (config.colors.primary.foreground, config.colors.primary.background);
+        let lines = [(header.as_str(), bar_colors), (PASTE_PREVIEW_HELP, bar_colors)]
+            .into_iter()
+            .chain(preview.visible_lines(text_lines, columns).map(|line| (line, text_colors)))
+            .chain(std::iter::repeat(("", text_colors)))
+            .take(screen_lines);
+
+        for (i, (line, (fg, bg))) in lines.enumerate() {
+            let text = StrShortener::new(line, columns, ShortenDirection::Right, Some(SHORTENER));
+            let text = format!("{:<1$}", text.collect::<String>(), columns);
+
+            let point = Point::new(i, Column(0));
+            let glyph_cache = &mut self.glyph_cache;
+            self.renderer.draw_string(point, fg, bg, text.chars(), &self.size_info, glyph_cache);
+        }
+    }
+
+    /// Draw the clipboard history picker above the bottom of the terminal.
+    #[inline(never)]
+    fn draw_clipboard_picker(&mut self, config: &UiConfig) {
+        let picker = match &self.clipboard_picker {
+            Some(picker) => picker,
+            None => return,
+        };
+
+        let entries: Vec<_> = picker.visible_matches(self.picker_height()).collect();
+
+        let (matches, total) = picker.count();
+        let label = format!("{CLIPBOARD_HISTORY_LABEL} [{matches}/{total}]: ");
+        let prompt = Self::format_search(picker.filter(), &label, self.size_info.columns());
+
+        self.draw_picker(config, &prompt, &entries);
+    }
+
+    /// Draw the fuzzy search picker above the bottom of the terminal.
+    #[inline(never)]
+    fn draw_fuzzy_search(&mut self, config: &UiConfig) {
+        let fuzzy_search = match &self.fuzzy_search {
+            Some(fuzzy_search) => fuzzy_search,
+            None => return,
+        };
+
+        let entries: Vec<_> = fuzzy_search
+            .visible_matches(self.picker_height())
+            .map(|(text, selected)| (text.to_owned(), selected))
+            .collect();
+
+        // Counts are followed by `+` while more lines might be found.
+        let (matches, total) = fuzzy_search.count();
+        let more = if fuzzy_search.done() { "" } else { "+" };
+        let label = format!("{FUZZY_SEARCH_LABEL} [{matches}/{total}{more}]: ");
+        let prompt = Self::format_search(fuzzy_search.query(), &label, self.size_info.columns());
+
+        self.draw_picker(config, &prompt, &entries);
+    }
+
+    /// Maximum number of entries visible in a picker overlay.
+    fn picker_height(&self) -> usize {
+        cmp::min(self.size_info.screen_lines().saturating_sub(1), PICKER_LINES)
+    }
+
+    /// Draw a picker prompt on the last line, with its entries right above it.
+    fn draw_picker(&mut self, config: &UiConfig, prompt: &str, entries: &[(String, bool)]) {
+        if self.collect_damage() {
+            self.damage_tracker.frame().mark_fully_damaged();
+            // Damage it on the next frame in case it goes away.
+            self.damage_tracker.next_frame().mark_fully_damaged();
+        }
+
+        let columns = self.size_info.columns();
+        let prompt_line = self.size_info.screen_lines().saturating_sub(1);
+
+        let fg = config.colors.footer_bar_foreground();
+        let bg = config.colors.footer_bar_background();
+
+        // Draw the first entry right above the prompt.
+        for (i, (entry, selected)) in entries.iter().enumerate() {
+            let text = StrShortener::new(entry, columns, ShortenDirection::Right, Some(SHORTENER));
+            let text = format!("{:<1$}", text.collect::<String>(), columns);
+            let (fg, bg) = if *selected { (bg, fg) } else { (fg, bg) };
+
+            let point = Point::new(prompt_line - 1 - i, Column(0));
+            let glyph_cache = &mut self.glyph_cache;
+            self.renderer.draw_string(point, fg, bg, text.chars(), &self.size_info, glyph_cache);
+        }
+
+        let text = format!("{:<1$}", prompt, columns);
+        let point = Point::new(prompt_line, Column(0));
+        let glyph_cache = &mut self.glyph_cache;
+        self.renderer.draw_string(point, fg, bg, text.chars(), &self.size_info, glyph_cache);
+    }
+
     /// Returns `true` if damage information should be collected, `false` otherwise.
     #[inline]
     fn collect_damage(&self) -> bool {
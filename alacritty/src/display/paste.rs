@@ -0,0 +1,257 @@
+//! Confirmation of potentially dangerous pastes.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::config::terminal::PasteProtection;
+
+/// Destination of a paste waiting for confirmation.
+#[derive(Clone)]
+pub enum PasteTarget {
+    /// Text pasted by the user.
+    Paste { bracketed: bool },
+
+    /// Clipboard content requested by the application, with the formatter for its response.
+    ClipboardLoad(Arc<dyn Fn(&str) -> String + Sync + Send + 'static>),
+}
+
+impl Debug for PasteTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Paste { bracketed } => write!(f, "Paste {{ bracketed: {bracketed} }}"),
+            Self::ClipboardLoad(_) => write!(f, "ClipboardLoad"),
+        }
+    }
+}
+
+/// Paste waiting for confirmation by the user.
+#[derive(Debug)]
+pub struct PastePreview {
+    /// Text which should be pasted.
+    pub text: String,
+
+    /// Destination of the text.
+    pub target: PasteTarget,
+
+    /// Reasons for requiring confirmation.
+    warnings: Vec<String>,
+
+    /// Printable lines of the text.
+    lines: Vec<String>,
+
+    /// Index of the first visible row, after wrapping lines.
+    scroll: usize,
+}
+
+impl PastePreview {
+    pub fn new(text: String, target: PasteTarget, warnings: Vec<String>) -> Self {
+        let lines = text.lines().map(printable).collect();
+        Self { text, target, warnings, lines, scroll: 0 }
+    }
+
+    /// Text describing why the paste requires confirmation.
+    pub fn header(&self) -> String {
+        let warnings = self.warnings.join(", ");
+        match self.target {
+            PasteTarget::Paste { .. } => format!("Paste contains {warnings}"),
+            PasteTarget::ClipboardLoad(_) => {
+                format!("Application requests clipboard content with {warnings}")
+            },
+        }
+    }
+
+    /// Rows visible in a preview of the specified size, with long lines wrapped.
+    pub fn visible_lines(&self, height: usize, columns: usize) -> impl Iterator<Item = &str> {
+        self.rows(columns).skip(self.scroll).take(height)
+    }
+
+    /// Scroll the preview, keeping a preview of the specified size filled.
+    pub fn scroll(&mut self, delta: isize, height: usize, columns: usize) {
+        let max_scroll = self.rows(columns).count().saturating_sub(height);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max_scroll);
+    }
+
+    /// Text with all lines joined by spaces and control characters removed.
+    pub fn single_line(&self) -> String {
+        let lines = self.text.split(['\n', '\r']).map(|line| {
+            let line: String = line
+                .chars()
+                .filter_map(|c| match c {
+                    '\t' => Some(' '),
+                    c if c.is_control() => None,
+                    c => Some(c),
+                })
+                .collect();
+            line.trim().to_owned()
+        });
+        lines.filter(|line| !line.is_empty()).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Printable lines wrapped to the specified number of columns.
+    fn rows(&self, columns: usize) -> impl Iterator<Item = &str> {
+        self.lines.iter().flat_map(move |line| wrap(line, columns))
+    }
+}
+
+/// Split a line into rows which fit into the specified number of columns.
+fn wrap(line: &str, columns: usize) -> Vec<&str> {
+    let columns = columns.max(1);
+
+    let mut rows = Vec::new();
+    let mut start = 0;
+    let mut width = 0;
+    for (index, c) in line.char_indices() {
+        let char_width = c.width().unwrap_or(0);
+        if width + char_width > columns && index > start {
+            rows.push(&line[start..index]);
+            start = index;
+            width = 0;
+        }
+        width += char_width;
+    }
+    rows.push(&line[start..]);
+
+    rows
+}
+
+/// Reasons why pasting a text requires confirmation.
+///
+/// Multi-line text is only considered dangerous when `multiline_safe` is `false`, since it would
+/// otherwise be executed line by line.
+pub fn warnings(config: &PasteProtection, text: &str, multiline_safe: bool) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if config.multiline && !multiline_safe && text.contains(['\n', '\r']) {
+        warnings.push(String::from("multiple lines"));
+    }
+
+    if config.control_characters && text.chars().any(is_hidden_control) {
+        warnings.push(String::from("control characters"));
+    }
+
+    for pattern in config.patterns.iter().filter(|pattern| !pattern.is_empty()) {
+        if contains_word(text, pattern) {
+            warnings.push(format!("\"{pattern}\""));
+        }
+    }
+
+    warnings
+}
+
+/// Check if the text contains the pattern, without being part of a longer word.
+///
+/// Word boundaries are only required where the pattern itself starts or ends with a word
+/// character, so `sudo` does not match `pseudo`, while `rm -rf /` still matches `rm -rf /tmp`.
+fn contains_word(text: &str, pattern: &str) -> bool {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let word_start = is_word(pattern.chars().next());
+    let word_end = is_word(pattern.chars().next_back());
+
+    text.match_indices(pattern).any(|(index, _)| {
+        (!word_start || !is_word(text[..index].chars().next_back()))
+            && (!word_end || !is_word(text[index + pattern.len()..].chars().next()))
+    })
+}
+
+/// Check if a character is a control character other than whitespace.
+fn is_hidden_control(c: char) -> bool {
+    c.is_control() && !matches!(c, '\n' | '\r' | '\t')
+}
+
+/// Make control characters in a line visible using caret notation.
+pub fn printable(line: &str) -> String {
+    let mut printable = String::with_capacity(line.len());
+    for c in line.chars() {
+        match c {
+            '\t' => printable.push_str("    "),
+            '\x7f' => printable.push_str("^?"),
+            c if (c as u32) < 0x20 => {
+                printable.push('^');
+                printable.push((c as u8 + b'@') as char);
+            },
+            c if c.is_control() => printable.push_str(&format!("<U+{:04X}>", c as u32)),
+            c => printable.push(c),
+        }
+    }
+    printable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paste_warnings() {
+        let config = PasteProtection::default();
+
+        assert!(warnings(&config, "ls -la", false).is_empty());
+        assert!(warnings(&config, "echo a\necho b", true).is_empty());
+        assert_eq!(warnings(&config, "echo a\necho b", false), vec!["multiple lines"]);
+        assert_eq!(warnings(&config, "echo \x1b[31m", true), vec!["control characters"]);
+        assert_eq!(warnings(&config, "curl x | sudo sh\n", false), vec![
+            String::from("multiple lines"),
+            String::from("\"sudo\"")
+        ]);
+
+        let config = PasteProtection { multiline: false, ..Default::default() };
+        assert!(warnings(&config, "echo a\necho b", false).is_empty());
+    }
+
+    #[test]
+    fn paste_pattern_word_boundaries() {
+        let config = PasteProtection::default();
+
+        assert!(warnings(&config, "echo pseudo", true).is_empty());
+        assert!(warnings(&config, "play sudoku", true).is_empty());
+        assert!(warnings(&config, "sudo_user", true).is_empty());
+        assert_eq!(warnings(&config, "sudo -i", true), vec!["\"sudo\""]);
+        assert_eq!(warnings(&config, "pseudo;sudo rm", true), vec!["\"sudo\""]);
+
+        let patterns = vec![String::from("rm -rf /")];
+        let config = PasteProtection { patterns, ..Default::default() };
+        assert_eq!(warnings(&config, "rm -rf /tmp", true), vec!["\"rm -rf /\""]);
+        assert!(warnings(&config, "norm -rf /tmp", true).is_empty());
+    }
+
+    #[test]
+    fn preview_lines() {
+        let text = String::from("a\tb\x1b[0m\r\n\nc\u{9b}\n");
+        let target = PasteTarget::Paste { bracketed: true };
+        let mut preview = PastePreview::new(text, target, Vec::new());
+
+        let lines: Vec<_> = preview.visible_lines(10, 80).collect();
+        assert_eq!(lines, vec!["a    b^[[0m", "", "c<U+009B>"]);
+
+        preview.scroll(5, 2, 80);
+        assert_eq!(preview.visible_lines(2, 80).collect::<Vec<_>>(), vec!["", "c<U+009B>"]);
+        preview.scroll(-5, 2, 80);
+        assert_eq!(preview.visible_lines(1, 80).next(), Some("a    b^[[0m"));
+
+        assert_eq!(preview.single_line(), "a b[0m c");
+    }
+
+    #[test]
+    fn preview_wrapping() {
+        let text = String::from("abcdefg\n\n\u{4f60}\u{597d}\u{4e16}");
+        let target = PasteTarget::Paste { bracketed: true };
+        let mut preview = PastePreview::new(text, target, Vec::new());
+
+        let lines: Vec<_> = preview.visible_lines(10, 3).collect();
+        assert_eq!(lines, vec!["abc", "def", "g", "", "\u{4f60}", "\u{597d}", "\u{4e16}"]);
+
+        preview.scroll(10, 3, 3);
+        let lines: Vec<_> = preview.visible_lines(3, 3).collect();
+        assert_eq!(lines, vec!["\u{4f60}", "\u{597d}", "\u{4e16}"]);
+    }
+
+    #[test]
+    fn single_line_carriage_return() {
+        let text = String::from("echo a\recho b\x1b[0m\r\n\u{9b}echo c\x07");
+        let target = PasteTarget::Paste { bracketed: false };
+        let preview = PastePreview::new(text, target, Vec::new());
+
+        assert_eq!(preview.single_line(), "echo a echo b[0m echo c");
+    }
+}
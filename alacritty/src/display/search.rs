@@ -0,0 +1,322 @@
+//! Incremental search and match counting across the entire scrollback history.
+
+use std::mem;
+use std::task::Poll;
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Boundary, Column, Direction, Line, Point, Side};
+use alacritty_terminal::term::search::{Match, RegexIter, RegexSearch};
+use alacritty_terminal::term::Term;
+
+/// Number of lines searched in one step.
+///
+/// This limits the time spent counting matches before other events are processed again.
+const LINES_PER_STEP: usize = 1_000;
+
+/// Search for the next match which is performed in steps.
+///
+/// Like [`Term::search_next`], this wraps around at the end of the scrollback history, but only
+/// searches a limited number of lines at a time so it does not block the UI thread.
+#[derive(Debug)]
+pub struct IncrementalSearch {
+    /// Point where the search started.
+    origin: Point,
+
+    /// Search direction from the origin.
+    direction: Direction,
+
+    /// Side of the match which must be beyond the origin.
+    side: Side,
+
+    /// Next line to search, `None` before the first step.
+    next_line: Option<Line>,
+
+    /// Whether the search wrapped around the end of the scrollback history.
+    wrapped: bool,
+
+    /// Terminal's [`Term::scrolled_lines`] during the last step.
+    scrolled_lines: usize,
+}
+
+impl IncrementalSearch {
+    pub fn new(origin: Point, direction: Direction, side: Side) -> Self {
+        Self { origin, direction, side, next_line: None, wrapped: false, scrolled_lines: 0 }
+    }
+
+    /// Search the next batch of lines.
+    ///
+    /// This returns [`Poll::Pending`] until either a match is found, or all lines were searched.
+    pub fn step<T>(&mut self, term: &Term<T>, regex: &mut RegexSearch) -> Poll<Option<Match>> {
+        let topmost_line = term.topmost_line();
+        let bottommost_line = term.bottommost_line();
+
+        // Follow the content which was scrolled up by new output since the last step.
+        let last_scrolled_lines = mem::replace(&mut self.scrolled_lines, term.scrolled_lines());
+        if let Some(next_line) = &mut self.next_line {
+            let scrolled = self.scrolled_lines.wrapping_sub(last_scrolled_lines);
+            let scrolled = scrolled.min(term.total_lines());
+            *next_line -= scrolled;
+            self.origin.line -= scrolled;
+            self.origin = self.origin.grid_clamp(term, Boundary::Grid);
+        }
+        let origin = self.origin;
+
+        // Lines might have been removed by terminal changes since the last step.
+        let clamp = |line: Line| line.max(topmost_line).min(bottommost_line);
+
+        // Get the lines of this step, always including complete wrapped lines.
+        let (start, end, last_step) = match self.direction {
+            Direction::Right => {
+                let start = match self.next_line {
+                    Some(line) => clamp(line),
+                    None => term.line_search_left(origin).line,
+                };
+
+                let mut end = (start + (LINES_PER_STEP - 1)).min(bottommost_line);
+                end = term.line_search_right(Point::new(end, Column(0))).line;
+
+                let last_step = self.wrapped && end >= origin.line;
+                if last_step {
+                    end = term.line_search_right(origin).line;
+                }
+
+                let start = Point::new(start, Column(0));
+                (start, Point::new(end, term.last_column()), last_step)
+            },
+            Direction::Left => {
+                let start = match self.next_line {
+                    Some(line) => clamp(line),
+                    None => term.line_search_right(origin).line,
+                };
+
+                let mut end = (start - (LINES_PER_STEP - 1)).max(topmost_line);
+                end = term.line_search_left(Point::new(end, Column(0))).line;
+
+                let last_step = self.wrapped && end <= origin.line;
+                if last_step {
+                    end = term.line_search_left(origin).line;
+                }
+
+                let start = Point::new(start, term.last_column());
+                (start, Point::new(end, Column(0)), last_step)
+            },
+        };
+
+        // Before wrapping around, only matches beyond the origin are accepted.
+        let wrapped = self.wrapped;
+        let direction = self.direction;
+        let side = self.side;
+        let mut iter = RegexIter::new(start, end, direction, term, regex);
+        let regex_match = iter.find(|regex_match| {
+            let point = match side {
+                Side::Left => *regex_match.start(),
+                Side::Right => *regex_match.end(),
+            };
+
+            wrapped
+                || match direction {
+                    Direction::Right => point >= origin,
+                    Direction::Left => point <= origin,
+                }
+        });
+
+        if regex_match.is_some() || last_step {
+            return Poll::Ready(regex_match);
+        }
+
+        // Continue on the other end after reaching the end of the scrollback history.
+        self.next_line = Some(match direction {
+            Direction::Right if end.line >= bottommost_line => {
+                self.wrapped = true;
+                topmost_line
+            },
+            Direction::Left if end.line <= topmost_line => {
+                self.wrapped = true;
+                bottommost_line
+            },
+            Direction::Right => end.line + 1,
+            Direction::Left => end.line - 1,
+        });
+
+        Poll::Pending
+    }
+}
+
+/// Incremental search match counter.
+///
+/// Matches are counted from the top of the scrollback history in steps, so counting can be
+/// interrupted by user input or cancelled at any point.
+#[derive(Debug)]
+pub struct MatchCounter {
+    /// Start of every match found so far, in grid order.
+    matches: Vec<Point>,
+
+    /// First line which has not been searched yet.
+    next_line: Line,
+
+    /// Whether all lines have been searched.
+    done: bool,
+
+    /// Whether the terminal content changed since counting started.
+    stale: bool,
+}
+
+impl MatchCounter {
+    pub fn new<T>(term: &Term<T>) -> Self {
+        Self { matches: Vec::new(), next_line: term.topmost_line(), done: false, stale: false }
+    }
+
+    /// Count the matches in the next batch of lines.
+    ///
+    /// Returns `true` once all lines have been searched.
+    pub fn step<T>(&mut self, term: &Term<T>, regex: &mut RegexSearch) -> bool {
+        if self.stale {
+            *self = Self::new(term);
+        }
+
+        if self.done {
+            return true;
+        }
+
+        // Always search until the end of a wrapped line, since matches can span across it.
+        let bottommost_line = term.bottommost_line();
+        let end_line = (self.next_line + (LINES_PER_STEP - 1)).min(bottommost_line);
+        let end = term.line_search_right(Point::new(end_line, Column(0)));
+        let start = Point::new(self.next_line, Column(0));
+
+        let iter = RegexIter::new(start, end, Direction::Right, term, regex);
+        self.matches.extend(iter.map(|regex_match| *regex_match.start()));
+
+        self.done = end.line >= bottommost_line;
+        self.next_line = end.line + 1;
+
+        self.done
+    }
+
+    /// Restart counting with the next step.
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /// Number of matches found so far.
+    pub fn count(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// Whether all matches have been counted.
+    pub fn done(&self) -> bool {
+        self.done && !self.stale
+    }
+
+    /// One-based position of a match, if its region was searched already.
+    pub fn position(&self, regex_match: &Match) -> Option<usize> {
+        let start = *regex_match.start();
+        if !self.done && start.line >= self.next_line {
+            return None;
+        }
+
+        Some(self.matches.partition_point(|point| *point < start) + 1)
+    }
+
+    /// Text describing the position of the focused match, like `3/127`.
+    ///
+    /// Counts are followed by `+` while more matches might be found.
+    pub fn label(&self, focused_match: Option<&Match>) -> String {
+        let position = focused_match.and_then(|regex_match| self.position(regex_match));
+        let position = position.map_or_else(|| String::from("?"), |position| position.to_string());
+        let more = if self.done() { "" } else { "+" };
+        format!("{position}/{}{more}", self.count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::term::test::mock_term;
+    use alacritty_terminal::vte::ansi::Handler;
+
+    #[test]
+    fn incremental_search() {
+        let term = mock_term("foo\r\nbar foo\r\nbaz");
+        let mut regex = RegexSearch::new("foo").unwrap();
+
+        let first = Point::new(Line(0), Column(0))..=Point::new(Line(0), Column(2));
+        let second = Point::new(Line(1), Column(4))..=Point::new(Line(1), Column(6));
+
+        let origin = Point::new(Line(1), Column(1));
+        let mut search = IncrementalSearch::new(origin, Direction::Right, Side::Left);
+        assert_eq!(search.step(&term, &mut regex), Poll::Ready(Some(second.clone())));
+
+        // Wrap around to the top of the scrollback history.
+        let origin = Point::new(Line(1), Column(5));
+        let mut search = IncrementalSearch::new(origin, Direction::Right, Side::Left);
+        assert_eq!(search.step(&term, &mut regex), Poll::Pending);
+        assert_eq!(search.step(&term, &mut regex), Poll::Ready(Some(first.clone())));
+
+        // Accept matches ending beyond the origin.
+        let mut search = IncrementalSearch::new(origin, Direction::Right, Side::Right);
+        assert_eq!(search.step(&term, &mut regex), Poll::Ready(Some(second.clone())));
+
+        let mut search = IncrementalSearch::new(origin, Direction::Left, Side::Left);
+        assert_eq!(search.step(&term, &mut regex), Poll::Ready(Some(second)));
+
+        let origin = Point::new(Line(1), Column(3));
+        let mut search = IncrementalSearch::new(origin, Direction::Left, Side::Left);
+        assert_eq!(search.step(&term, &mut regex), Poll::Ready(Some(first)));
+
+        let mut regex = RegexSearch::new("nothing").unwrap();
+        let mut search = IncrementalSearch::new(origin, Direction::Left, Side::Left);
+        assert_eq!(search.step(&term, &mut regex), Poll::Pending);
+        assert_eq!(search.step(&term, &mut regex), Poll::Ready(None));
+    }
+
+    #[test]
+    fn incremental_search_scrolled() {
+        let mut term = mock_term("foo\r\nbar\r\nbaz");
+        let mut regex = RegexSearch::new("foo").unwrap();
+
+        // Search wraps around to the top after finding no match below the origin.
+        let origin = Point::new(Line(1), Column(0));
+        let mut search = IncrementalSearch::new(origin, Direction::Right, Side::Left);
+        assert_eq!(search.step(&term, &mut regex), Poll::Pending);
+
+        // Scroll the match into the history between the steps.
+        term.goto(2, 0);
+        term.linefeed();
+
+        let first = Point::new(Line(-1), Column(0))..=Point::new(Line(-1), Column(2));
+        assert_eq!(search.step(&term, &mut regex), Poll::Ready(Some(first)));
+    }
+
+    #[test]
+    fn count_matches() {
+        let term = mock_term("foo bar\r\nbar\nfoo\r\nfoo");
+        let mut regex = RegexSearch::new("foo").unwrap();
+
+        let mut counter = MatchCounter::new(&term);
+        assert_eq!(counter.label(None), "?/0+");
+
+        assert!(counter.step(&term, &mut regex));
+        assert_eq!(counter.count(), 3);
+
+        let focused = Point::new(Line(2), Column(0))..=Point::new(Line(2), Column(2));
+        assert_eq!(counter.position(&focused), Some(2));
+        assert_eq!(counter.label(Some(&focused)), "2/3");
+
+        counter.invalidate();
+        assert!(!counter.done());
+        assert!(counter.step(&term, &mut regex));
+        assert_eq!(counter.label(None), "?/3");
+    }
+
+    #[test]
+    fn count_wrapped_match() {
+        let term = mock_term("abc\nd\r\nxcd");
+        let mut regex = RegexSearch::new("cd").unwrap();
+
+        let mut counter = MatchCounter::new(&term);
+        counter.step(&term, &mut regex);
+        assert_eq!(counter.count(), 2);
+    }
+}
@@ -0,0 +1,163 @@
+//! Window title templates.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::daemon::ForegroundProcess;
+
+/// Replacement for the `{bell}` placeholder after the bell rang.
+const BELL_INDICATOR: &str = "🔔";
+
+/// State used to render the window title.
+#[derive(Default, Debug)]
+pub struct TitleState {
+    /// Title requested by the terminal application.
+    pub terminal_title: Option<String>,
+
+    /// Foreground process of the terminal.
+    pub process: Option<ForegroundProcess>,
+
+    /// Whether the bell rang while the window was unfocused.
+    pub bell: bool,
+}
+
+impl TitleState {
+    /// Render a title template.
+    ///
+    /// Unknown placeholders are kept as they are.
+    pub fn render(
+        &self,
+        template: &str,
+        title: &str,
+        user_vars: &HashMap<String, String>,
+        columns: usize,
+        lines: usize,
+    ) -> String {
+        let mut rendered = String::with_capacity(template.len());
+
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let value = rest.find('}').and_then(|end| {
+                let value = self.placeholder(&rest[1..end], title, user_vars, columns, lines)?;
+                Some((end, value))
+            });
+            match value {
+                Some((end, value)) => {
+                    rendered.push_str(&value);
+                    rest = &rest[end + 1..];
+                },
+                None => {
+                    rendered.push('{');
+                    rest = &rest[1..];
+                },
+            }
+        }
+        rendered.push_str(rest);
+
+        rendered
+    }
+
+    /// Value of a template placeholder.
+    fn placeholder<'a>(
+        &'a self,
+        name: &str,
+        title: &'a str,
+        user_vars: &'a HashMap<String, String>,
+        columns: usize,
+        lines: usize,
+    ) -> Option<Cow<'a, str>> {
+        let value: Cow<'a, str> = match name {
+            "title" => title.into(),
+            "process" => self.process.as_ref().map_or("", |process| &process.name).into(),
+            "cwd" => match self.process.as_ref().and_then(|process| process.cwd.as_deref()) {
+                Some(cwd) => shorten_home(cwd).into(),
+                None => "".into(),
+            },
+            "host" => hostname().into(),
+            "cols" => columns.to_string().into(),
+            "lines" => lines.to_string().into(),
+            "bell" if self.bell => BELL_INDICATOR.into(),
+            "bell" => "".into(),
+            _ => {
+                let name = name.strip_prefix("user_var:")?;
+                user_vars.get(name).map_or("", String::as_str).into()
+            },
+        };
+
+        Some(value)
+    }
+}
+
+/// Replace the home directory prefix of a path with `~`.
+fn shorten_home(path: &Path) -> String {
+    match home::home_dir().and_then(|home| path.strip_prefix(home).ok()) {
+        Some(relative) if relative.as_os_str().is_empty() => String::from("~"),
+        Some(relative) => Path::new("~").join(relative).display().to_string(),
+        None => path.display().to_string(),
+    }
+}
+
+/// Get the name of this machine.
+#[cfg(not(windows))]
+fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+        return String::new();
+    }
+
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..len]).into_owned()
+}
+
+/// Get the name of this machine.
+#[cfg(windows)]
+fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+
+    #[test]
+    fn render_placeholders() {
+        let process = ForegroundProcess {
+            name: String::from("vim"),
+            cwd: Some(PathBuf::from("/tmp/project")),
+        };
+        let mut state = TitleState { process: Some(process), ..Default::default() };
+        let mut user_vars = HashMap::new();
+        user_vars.insert(String::from("branch"), String::from("main"));
+
+        let template = "{process} in {cwd} ({user_var:branch}{user_var:unset}) {cols}x{lines}";
+        let title = state.render(template, "Alacritty", &user_vars, 80, 24);
+        assert_eq!(title, "vim in /tmp/project (main) 80x24");
+
+        assert_eq!(state.render("{title}{bell}", "Alacritty", &user_vars, 80, 24), "Alacritty");
+        state.bell = true;
+        let title = state.render("{title}{bell}", "Alacritty", &user_vars, 80, 24);
+        assert_eq!(title, "Alacritty🔔");
+    }
+
+    #[test]
+    fn render_unknown_placeholders() {
+        let state = TitleState::default();
+        let user_vars = HashMap::new();
+        let title = state.render("{unknown} {title", "Alacritty", &user_vars, 80, 24);
+        assert_eq!(title, "{unknown} {title");
+        assert_eq!(state.render("{{title}}", "Alacritty", &user_vars, 80, 24), "{Alacritty}");
+    }
+
+    #[test]
+    fn shorten_home_directory() {
+        let home = home::home_dir().unwrap();
+        assert_eq!(shorten_home(&home), "~");
+        assert_eq!(shorten_home(&home.join("src")), format!("~{}src", std::path::MAIN_SEPARATOR));
+    }
+}
@@ -8,10 +8,13 @@ use std::ffi::OsStr;
 use std::fmt::Debug;
 #[cfg(not(windows))]
 use std::os::unix::io::RawFd;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use std::time::{Duration, Instant};
-use std::{env, f32, mem};
+#[cfg(unix)]
+use std::sync::mpsc;
+use std::task::Poll;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{env, f32, fs, io, mem};
 
 use ahash::RandomState;
 use crossfont::Size as FontSize;
@@ -28,35 +31,65 @@ use winit::event_loop::{
 use winit::window::WindowId;
 
 use alacritty_terminal::event::{Event as TerminalEvent, EventListener, Notify};
-use alacritty_terminal::event_loop::Notifier;
+use alacritty_terminal::event_loop::{Notifier, Reactor};
 use alacritty_terminal::grid::{BidirectionalIterator, Dimensions, Scroll};
 use alacritty_terminal::index::{Boundary, Column, Direction, Line, Point, Side};
 use alacritty_terminal::selection::{Selection, SelectionType};
-use alacritty_terminal::term::search::{Match, RegexSearch};
-use alacritty_terminal::term::{self, ClipboardType, Term, TermMode};
+use alacritty_terminal::term::color::Colors as TermColors;
+use alacritty_terminal::term::export::{ExportFormat, ExportRange};
+use alacritty_terminal::term::search::{Match, RegexIter, RegexSearch, SearchMode};
+use alacritty_terminal::term::{self, ClipboardType, EscapeCategories, Term, TermMode};
 
 #[cfg(unix)]
-use crate::cli::{IpcConfig, ParsedOptions};
+use crate::cli::{IpcConfig, IpcExport, IpcGetUserVar, ParsedOptions};
 use crate::cli::{Options as CliOptions, WindowOptions};
 use crate::clipboard::Clipboard;
+use crate::config::terminal::{EscapePolicy, Permission};
 use crate::config::ui_config::{HintAction, HintInternalAction};
 use crate::config::{self, UiConfig};
-#[cfg(not(windows))]
-use crate::daemon::foreground_process_path;
 use crate::daemon::spawn_daemon;
+#[cfg(not(windows))]
+use crate::daemon::{foreground_process, foreground_process_name, foreground_process_path};
+use crate::display::clipboard_history::ClipboardPicker;
 use crate::display::color::Rgb;
+use crate::display::fuzzy_search::FuzzySearch;
 use crate::display::hint::HintMatch;
+use crate::display::paste::{self, PastePreview, PasteTarget};
+use crate::display::search::{IncrementalSearch, MatchCounter};
 use crate::display::window::Window;
 use crate::display::{Display, Preedit, SizeInfo};
+use crate::input::vi::ViCommandState;
 use crate::input::{self, ActionContext as _, FONT_SIZE_STEP};
 use crate::logging::LOG_TARGET_CONFIG;
-use crate::message_bar::{Message, MessageBuffer};
+use crate::message_bar::{Message, MessageBuffer, MessageType};
 use crate::scheduler::{Scheduler, TimerId, Topic};
+use crate::trigger::TriggeredAction;
 use crate::window_context::WindowContext;
 
 /// Duration after the last user input until an unlimited search is performed.
 pub const TYPING_SEARCH_DELAY: Duration = Duration::from_millis(500);
 
+/// Delay before recounting search matches after the terminal content changed.
+pub const SEARCH_COUNT_DELAY: Duration = Duration::from_millis(100);
+
+/// Message bar target of the confirmation prompt for closing a window.
+pub const CLOSE_CONFIRMATION_TARGET: &str = "alacritty_close_confirmation";
+
+/// Message bar target of the prompt for allowing blocked escape sequences.
+pub const ESCAPE_PROMPT_TARGET: &str = "alacritty_escape_prompt";
+
+/// Message bar target of the blocked escape sequence counter.
+const ESCAPE_BLOCKED_TARGET: &str = "alacritty_escape_blocked";
+
+/// Minimum interval between reports of blocked escape sequences.
+const ESCAPE_BLOCKED_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Delay before writing the clipboard history to disk, to batch rapid copies.
+const CLIPBOARD_SAVE_DELAY: Duration = Duration::from_secs(5);
+
+/// Interval for checking if the terminal's foreground process changed.
+const FOREGROUND_PROCESS_INTERVAL: Duration = Duration::from_millis(500);
+
 /// Maximum number of lines for the blocking search while still typing the search regex.
 const MAX_SEARCH_WHILE_TYPING: Option<usize> = Some(1000);
 
@@ -98,10 +131,22 @@ pub enum EventType {
     CreateWindow(WindowOptions),
     #[cfg(unix)]
     IpcConfig(IpcConfig),
+    #[cfg(unix)]
+    IpcExport(IpcExport),
+    #[cfg(unix)]
+    IpcGetUserVar(IpcGetUserVar, mpsc::Sender<Option<String>>),
     BlinkCursor,
     BlinkCursorTimeout,
     SearchNext,
+    SearchCount,
     Frame,
+    RestartShell,
+    PollSecureInput,
+    PollForegroundProcess,
+    ReportBlockedEscapes,
+    SaveClipboardHistory,
+    FuzzySearch,
+    Trigger(TriggeredAction),
 }
 
 impl From<TerminalEvent> for EventType {
@@ -110,6 +155,56 @@ impl From<TerminalEvent> for EventType {
     }
 }
 
+/// Runtime state of the escape sequence policy.
+#[derive(Default, Debug)]
+pub struct EscapePolicyState {
+    /// Whether the window uses the untrusted policy.
+    pub untrusted: bool,
+
+    /// Categories allowed by the user after prompting.
+    allowed: EscapeCategories,
+
+    /// Categories denied by the user after prompting.
+    denied: EscapeCategories,
+
+    /// Category the user is currently prompted for.
+    prompt: Option<EscapeCategories>,
+
+    /// Number of blocked escape sequences.
+    blocked_count: usize,
+
+    /// Categories of all blocked escape sequences.
+    blocked_categories: EscapeCategories,
+}
+
+impl EscapePolicyState {
+    /// Escape sequence categories which should be blocked by the terminal.
+    fn blocked(&self, policy: &EscapePolicy) -> EscapeCategories {
+        EscapeCategories::all()
+            .iter()
+            .filter(|&category| match policy.permission(category, self.untrusted) {
+                Permission::Allow => false,
+                Permission::Deny => true,
+                Permission::Prompt => !self.allowed.contains(category),
+            })
+            .collect()
+    }
+
+    /// Whether replies to queries should be kept until the user answered the prompt.
+    fn hold_queries(&self, policy: &EscapePolicy) -> bool {
+        let category = EscapeCategories::QUERIES;
+        policy.permission(category, self.untrusted) == Permission::Prompt
+            && !self.allowed.contains(category)
+            && !self.denied.contains(category)
+    }
+
+    /// Apply the policy to the terminal.
+    pub fn apply<T: EventListener>(&self, policy: &EscapePolicy, terminal: &mut Term<T>) {
+        terminal.set_blocked_escapes(self.blocked(policy));
+        terminal.set_hold_queries(self.hold_queries(policy));
+    }
+}
+
 /// Regex search state.
 pub struct SearchState {
     /// Search direction.
@@ -133,16 +228,27 @@ pub struct SearchState {
     ///
     /// While going through history, the [`SearchState::history_index`] will point to the element
     /// in history which is currently being previewed.
-    history: VecDeque<String>,
+    history: VecDeque<SearchHistoryEntry>,
 
     /// Compiled search automatons.
     dfas: Option<RegexSearch>,
+
+    /// Number of matches of the active search.
+    match_counter: Option<MatchCounter>,
+
+    /// Search which is running in the background, with the action taken once it is done.
+    incremental_search: Option<(IncrementalSearch, SearchTarget)>,
 }
 
 impl SearchState {
     /// Search regex text if a search is active.
     pub fn regex(&self) -> Option<&String> {
-        self.history_index.and_then(|index| self.history.get(index))
+        self.history_entry().map(|entry| &entry.regex)
+    }
+
+    /// Search mode of the active search.
+    pub fn mode(&self) -> SearchMode {
+        self.history_entry().map(|entry| entry.mode).unwrap_or_default()
     }
 
     /// Direction of the search from the search origin.
@@ -165,12 +271,72 @@ impl SearchState {
         self.dfas.as_mut()
     }
 
+    /// Number of matches of the active search.
+    pub fn match_counter(&self) -> Option<&MatchCounter> {
+        self.match_counter.as_ref()
+    }
+
+    /// Recount all matches, since the terminal content has changed.
+    ///
+    /// Returns `false` if no matches are being counted.
+    pub fn invalidate_match_counter(&mut self) -> bool {
+        match &mut self.match_counter {
+            Some(match_counter) => {
+                match_counter.invalidate();
+                true
+            },
+            None => false,
+        }
+    }
+
     /// Search regex text if a search is active.
     fn regex_mut(&mut self) -> Option<&mut String> {
-        self.history_index.and_then(move |index| self.history.get_mut(index))
+        let index = self.history_index?;
+        self.history.get_mut(index).map(|entry| &mut entry.regex)
+    }
+
+    /// History entry of the active search.
+    fn history_entry(&self) -> Option<&SearchHistoryEntry> {
+        self.history_index.and_then(|index| self.history.get(index))
+    }
+
+    /// Make the previewed history entry the user's current input.
+    ///
+    /// Returns `false` if no search is active.
+    fn edit_history_entry(&mut self) -> bool {
+        match self.history_index {
+            Some(0) => (),
+            // When currently in history, replace active regex with history on change.
+            Some(index) => {
+                self.history[0] = self.history[index].clone();
+                self.history_index = Some(0);
+            },
+            None => return false,
+        }
+        true
     }
 }
 
+/// Action taken once a search running in the background is done.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum SearchTarget {
+    /// Focus the first match from the search origin.
+    Focus,
+
+    /// Focus the next match and move the search origin in front of it.
+    Advance,
+
+    /// Move the vi cursor to the specified side of the next match.
+    ViCursor(Side),
+}
+
+/// Search regex and the mode it was used with.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+struct SearchHistoryEntry {
+    regex: String,
+    mode: SearchMode,
+}
+
 impl Default for SearchState {
     fn default() -> Self {
         Self {
@@ -181,6 +347,8 @@ impl Default for SearchState {
             history: Default::default(),
             origin: Default::default(),
             dfas: Default::default(),
+            match_counter: Default::default(),
+            incremental_search: Default::default(),
         }
     }
 }
@@ -222,6 +390,8 @@ pub struct ActionContext<'a, N, T> {
     pub scheduler: &'a mut Scheduler,
     pub search_state: &'a mut SearchState,
     pub inline_search_state: &'a mut InlineSearchState,
+    pub vi_command_state: &'a mut ViCommandState,
+    pub escape_policy: &'a mut EscapePolicyState,
     pub dirty: &'a mut bool,
     pub occluded: &'a mut bool,
     pub preserve_title: bool,
@@ -291,12 +461,48 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
             None => return,
         };
 
+        self.record_clipboard(&text);
+
         if ty == ClipboardType::Selection && self.config.selection.save_to_clipboard {
             self.clipboard.store(ClipboardType::Clipboard, text.clone());
         }
         self.clipboard.store(ty, text);
     }
 
+    fn copy_selection_as_html(&mut self) {
+        let colors = TermColors::from(&self.display.colors);
+        let html = self.terminal.export(ExportRange::Selection, ExportFormat::Html, &colors);
+        if let Some(html) = html.filter(|_| !self.selection_is_empty()) {
+            self.clipboard.store(ClipboardType::Clipboard, html);
+        }
+    }
+
+    fn save_scrollback(&mut self) {
+        // Save next to the shell's working directory, since that is where the user is working.
+        #[cfg(not(windows))]
+        let directory = foreground_process_path(self.master_fd, self.shell_pid).ok();
+        #[cfg(windows)]
+        let directory = None;
+        let directory = directory.or_else(home::home_dir).unwrap_or_default();
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let path = directory.join(format!("alacritty-scrollback-{}.ansi", timestamp.as_secs()));
+
+        let message = match self.export(&path, ExportRange::Scrollback, ExportFormat::Ansi) {
+            Ok(()) => {
+                info!("Saved scrollback to {path:?}");
+                Message::new(format!("Saved scrollback to {}", path.display()), MessageType::Info)
+            },
+            Err(err) => {
+                error!("Unable to save scrollback to {path:?}: {err}");
+                let text = format!("Unable to save scrollback to {}: {err}", path.display());
+                Message::new(text, MessageType::Error)
+            },
+        };
+        self.message_buffer.push(message);
+        self.display.pending_update.dirty = true;
+    }
+
     fn selection_is_empty(&self) -> bool {
         self.terminal.selection.as_ref().map_or(true, Selection::is_empty)
     }
@@ -447,6 +653,62 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
             .send_event(Event::new(EventType::CreateWindow(WindowOptions::default()), None));
     }
 
+    fn request_close(&mut self) {
+        // Ask for confirmation if a process other than the shell is running.
+        #[cfg(not(windows))]
+        let process = foreground_process_name(self.master_fd, self.shell_pid);
+        #[cfg(windows)]
+        let process: Option<String> = None;
+
+        let confirm_close = &self.config.terminal.confirm_close;
+        if let Some(process) = process.filter(|process| confirm_close.required(process)) {
+            let text = format!("{process} is still running, close the window anyway? [y/N]");
+            let mut message = Message::new(text, MessageType::Warning);
+            message.set_target(CLOSE_CONFIRMATION_TARGET.into());
+
+            if !self.message_buffer.is_queued(&message) {
+                self.message_buffer.push(message);
+                self.display.pending_update.dirty = true;
+            }
+
+            return;
+        }
+
+        self.terminal.exit();
+    }
+
+    fn restart_shell(&mut self) {
+        let window_id = self.display.window.id();
+        let _ = self.event_proxy.send_event(Event::new(EventType::RestartShell, window_id));
+    }
+
+    fn toggle_untrusted(&mut self) {
+        let untrusted = !self.escape_policy.untrusted;
+        *self.escape_policy = EscapePolicyState { untrusted, ..Default::default() };
+        self.update_blocked_escapes();
+
+        self.message_buffer.remove_target(ESCAPE_PROMPT_TARGET);
+        self.message_buffer.remove_target(ESCAPE_BLOCKED_TARGET);
+        self.display.pending_update.dirty = true;
+
+        let policy = if untrusted { "untrusted" } else { "trusted" };
+        info!("Using {policy} escape sequence policy");
+    }
+
+    fn answer_escape_prompt(&mut self, allow: bool) {
+        if let Some(category) = self.escape_policy.prompt.take() {
+            if allow {
+                self.escape_policy.allowed.insert(category);
+            } else {
+                self.escape_policy.denied.insert(category);
+            }
+            self.update_blocked_escapes();
+        }
+
+        self.message_buffer.remove_target(ESCAPE_PROMPT_TARGET);
+        self.display.pending_update.dirty = true;
+    }
+
     fn spawn_daemon<I, S>(&self, program: &str, args: I)
     where
         I: IntoIterator<Item = S> + Debug + Copy,
@@ -490,8 +752,12 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
     #[inline]
     fn start_search(&mut self, direction: Direction) {
         // Only create new history entry if the previous regex wasn't empty.
-        if self.search_state.history.front().map_or(true, |regex| !regex.is_empty()) {
-            self.search_state.history.push_front(String::new());
+        let previous = self.search_state.history.front();
+        if previous.map_or(true, |entry| !entry.regex.is_empty()) {
+            // Keep using the search mode of the last search.
+            let mode = previous.map(|entry| entry.mode).unwrap_or_default();
+            let entry = SearchHistoryEntry { regex: String::new(), mode };
+            self.search_state.history.push_front(entry);
             self.search_state.history.truncate(MAX_SEARCH_HISTORY_SIZE);
         }
 
@@ -501,7 +767,9 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
 
         // Store original search position as origin and reset location.
         if self.terminal.mode().contains(TermMode::VI) {
-            self.search_state.origin = self.terminal.vi_mode_cursor.point;
+            let vi_point = self.terminal.vi_mode_cursor.point;
+            self.terminal.marks_mut().record_jump(vi_point);
+            self.search_state.origin = vi_point;
             self.search_state.display_offset_delta = 0;
 
             // Adjust origin for content moving upward on search start.
@@ -533,13 +801,21 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
             return;
         }
 
-        // Force unlimited search if the previous one was interrupted.
+        // Finish the search in the background if the previous one was interrupted.
         let timer_id = TimerId::new(Topic::DelayedSearch, self.display.window.id());
-        if self.scheduler.scheduled(timer_id) {
-            self.goto_match(None);
-        }
+        let pending = self.scheduler.scheduled(timer_id);
+        let search = self.search_state.incremental_search.take();
 
         self.exit_search();
+
+        if pending {
+            let origin = self.search_state.origin.grid_clamp(self.terminal, Boundary::Grid);
+            let direction = self.search_state.direction;
+            self.search_state.incremental_search = Some(search.unwrap_or_else(|| {
+                (IncrementalSearch::new(origin, direction, Side::Left), SearchTarget::Focus)
+            }));
+            self.continue_search();
+        }
     }
 
     #[inline]
@@ -563,16 +839,10 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
 
     #[inline]
     fn search_input(&mut self, c: char) {
-        match self.search_state.history_index {
-            Some(0) => (),
-            // When currently in history, replace active regex with history on change.
-            Some(index) => {
-                self.search_state.history[0] = self.search_state.history[index].clone();
-                self.search_state.history_index = Some(0);
-            },
-            None => return,
+        if !self.search_state.edit_history_entry() {
+            return;
         }
-        let regex = &mut self.search_state.history[0];
+        let regex = &mut self.search_state.history[0].regex;
 
         match c {
             // Handle backspace/ctrl+h.
@@ -602,6 +872,16 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         }
     }
 
+    #[inline]
+    fn search_toggle_mode(&mut self, mode: SearchMode) {
+        if !self.search_state.edit_history_entry() {
+            return;
+        }
+
+        self.search_state.history[0].mode.toggle(mode);
+        self.update_search();
+    }
+
     /// Go to the previous regex in the search history.
     #[inline]
     fn search_history_previous(&mut self) {
@@ -643,41 +923,18 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         }
 
         // Search for the next match using the supplied direction.
-        let search_direction = mem::replace(&mut self.search_state.direction, direction);
-        self.goto_match(None);
-        self.search_state.direction = search_direction;
-
-        // If we found a match, we set the search origin right in front of it to make sure that
-        // after modifications to the regex the search is started without moving the focused match
-        // around.
-        let focused_match = match &self.search_state.focused_match {
-            Some(focused_match) => focused_match,
-            None => return,
-        };
-
-        // Set new origin to the left/right of the match, depending on search direction.
-        let new_origin = match self.search_state.direction {
-            Direction::Right => *focused_match.start(),
-            Direction::Left => *focused_match.end(),
-        };
-
-        // Store the search origin with display offset by checking how far we need to scroll to it.
-        let old_display_offset = self.terminal.grid().display_offset() as i32;
-        self.terminal.scroll_to_point(new_origin);
-        let new_display_offset = self.terminal.grid().display_offset() as i32;
-        self.search_state.display_offset_delta = new_display_offset - old_display_offset;
-
-        // Store origin and scroll back to the match.
-        self.terminal.scroll_display(Scroll::Delta(-self.search_state.display_offset_delta));
-        self.search_state.origin = new_origin;
+        let origin = self.search_state.origin.grid_clamp(self.terminal, Boundary::Grid);
+        let search = IncrementalSearch::new(origin, direction, Side::Left);
+        self.search_state.incremental_search = Some((search, SearchTarget::Advance));
+        self.continue_search();
     }
 
-    /// Find the next search match.
-    fn search_next(&mut self, origin: Point, direction: Direction, side: Side) -> Option<Match> {
-        self.search_state
-            .dfas
-            .as_mut()
-            .and_then(|dfas| self.terminal.search_next(dfas, origin, direction, side, None))
+    /// Move the vi cursor to the next search match.
+    fn vi_search(&mut self, origin: Point, direction: Direction, side: Side) {
+        let origin = self.terminal.expand_wide(origin, direction);
+        let search = IncrementalSearch::new(origin, direction, side);
+        self.search_state.incremental_search = Some((search, SearchTarget::ViCursor(side)));
+        self.continue_search();
     }
 
     #[inline]
@@ -747,10 +1004,11 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
             },
             // Copy the text to the clipboard.
             HintAction::Action(HintInternalAction::Copy) => {
+                self.record_clipboard(&text);
                 self.clipboard.store(ClipboardType::Clipboard, text);
             },
             // Write the text to the PTY/search.
-            HintAction::Action(HintInternalAction::Paste) => self.paste(&text, true),
+            HintAction::Action(HintInternalAction::Paste) => self.guarded_paste(&text),
             // Select the text.
             HintAction::Action(HintInternalAction::Select) => {
                 self.start_selection(SelectionType::Simple, *hint_bounds.start(), Side::Left);
@@ -816,6 +1074,170 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         }
     }
 
+    /// Paste a text into the terminal, asking for confirmation if it looks dangerous.
+    fn guarded_paste(&mut self, text: &str) {
+        // Multi-line text cannot be executed while bracketed paste is active.
+        let bracketed_paste = self.terminal.mode().contains(TermMode::BRACKETED_PASTE);
+        let protection = &self.config.terminal.paste_protection;
+        let warnings = paste::warnings(protection, text, bracketed_paste);
+        if warnings.is_empty() || self.search_active() {
+            self.paste(text, true);
+            return;
+        }
+
+        let target = PasteTarget::Paste { bracketed: true };
+        self.display.paste_preview = Some(PastePreview::new(text.into(), target, warnings));
+        *self.dirty = true;
+    }
+
+    fn confirm_paste(&mut self, single_line: bool) {
+        let preview = match self.display.paste_preview.take() {
+            Some(preview) => preview,
+            None => return,
+        };
+        *self.dirty = true;
+
+        let text = if single_line { preview.single_line() } else { preview.text };
+        match preview.target {
+            PasteTarget::Paste { bracketed } => self.paste(&text, bracketed),
+            PasteTarget::ClipboardLoad(format) => self.write_to_pty(format(&text).into_bytes()),
+        }
+    }
+
+    fn copy_all_search_matches(&mut self) {
+        let dfas = match &mut self.search_state.dfas {
+            Some(dfas) => dfas,
+            None => return,
+        };
+
+        let term = &*self.terminal;
+        let start = Point::new(term.topmost_line(), Column(0));
+        let end = Point::new(term.bottommost_line(), term.last_column());
+        let iter = RegexIter::new(start, end, Direction::Right, term, dfas);
+        let matches: Vec<_> = iter
+            .map(|regex_match| term.bounds_to_string(*regex_match.start(), *regex_match.end()))
+            .collect();
+
+        if !matches.is_empty() {
+            let text = matches.join("\n");
+            self.record_clipboard(&text);
+            self.clipboard.store(ClipboardType::Clipboard, text);
+        }
+    }
+
+    fn cancel_paste(&mut self) {
+        if self.display.paste_preview.take().is_some() {
+            *self.dirty = true;
+        }
+    }
+
+    #[inline]
+    fn paste_pending(&self) -> bool {
+        self.display.paste_preview.is_some()
+    }
+
+    fn show_clipboard_history(&mut self) {
+        let entries = self.clipboard.history.entries(&self.config.clipboard);
+        self.display.clipboard_picker = Some(ClipboardPicker::new(entries));
+        *self.dirty = true;
+    }
+
+    fn accept_clipboard_entry(&mut self, copy: bool) {
+        let picker = match self.display.clipboard_picker.take() {
+            Some(picker) => picker,
+            None => return,
+        };
+        *self.dirty = true;
+
+        let text = match picker.selected_entry() {
+            Some(text) => text.to_owned(),
+            None => return,
+        };
+
+        if copy {
+            self.record_clipboard(&text);
+            self.clipboard.store(ClipboardType::Clipboard, text);
+        } else {
+            self.guarded_paste(&text);
+        }
+    }
+
+    fn ring_bell(&mut self) {
+        // Set window urgency hint when window is not focused.
+        let focused = self.terminal.is_focused;
+        if !focused && self.terminal.mode().contains(TermMode::URGENCY_HINTS) {
+            self.window().set_urgent(true);
+        }
+
+        // Indicate the bell in the title until the window is focused.
+        if !focused && !self.preserve_title {
+            self.display.title_state.bell = true;
+            self.display.update_title(self.config, self.terminal.user_vars());
+        }
+
+        // Ring visual bell.
+        self.display.visual_bell.ring();
+
+        // Execute bell command.
+        if let Some(bell_command) = &self.config.bell.command {
+            self.spawn_daemon(bell_command.program(), bell_command.args());
+        }
+    }
+
+    fn start_fuzzy_search(&mut self) {
+        self.display.fuzzy_search = Some(FuzzySearch::new(self.terminal));
+        self.continue_fuzzy_search();
+    }
+
+    /// Collect and score the next lines of the fuzzy search picker.
+    fn continue_fuzzy_search(&mut self) {
+        let window_id = self.display.window.id();
+        let timer_id = TimerId::new(Topic::FuzzySearch, window_id);
+        self.scheduler.unschedule(timer_id);
+
+        let fuzzy_search = match &mut self.display.fuzzy_search {
+            Some(fuzzy_search) => fuzzy_search,
+            None => return,
+        };
+
+        // Continue after other events had a chance to be processed.
+        if !fuzzy_search.step(self.terminal) {
+            let event = Event::new(EventType::FuzzySearch, window_id);
+            self.scheduler.schedule(event, Duration::ZERO, false, timer_id);
+        }
+
+        *self.dirty = true;
+    }
+
+    fn confirm_fuzzy_search(&mut self) {
+        let fuzzy_search = match self.display.fuzzy_search.take() {
+            Some(fuzzy_search) => fuzzy_search,
+            None => return,
+        };
+        *self.dirty = true;
+
+        let line = match fuzzy_search.selected_line(self.terminal) {
+            Some(line) => line,
+            None => return,
+        };
+
+        // Scroll the selected line into the center of the viewport.
+        let history_size = self.terminal.history_size() as i32;
+        let target_offset = (self.terminal.screen_lines() / 2) as i32 - line.0;
+        let display_offset = self.terminal.grid().display_offset() as i32;
+        self.scroll(Scroll::Delta(target_offset.clamp(0, history_size) - display_offset));
+
+        if self.terminal.mode().contains(TermMode::VI) {
+            let vi_point = self.terminal.vi_mode_cursor.point;
+            self.terminal.marks_mut().record_jump(vi_point);
+            self.terminal.vi_mode_cursor.point = Point::new(line, Column(0));
+
+            if self.terminal.selection.as_ref().is_some_and(|s| !s.is_empty()) {
+                self.update_selection(self.terminal.vi_mode_cursor.point, Side::Right);
+            }
+        }
+    }
+
     /// Paste a text into the terminal.
     fn paste(&mut self, text: &str, bracketed: bool) {
         if self.search_active() {
@@ -890,6 +1312,11 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         self.inline_search_state
     }
 
+    /// Get pending vi command state.
+    fn vi_command_state(&mut self) -> &mut ViCommandState {
+        self.vi_command_state
+    }
+
     /// Start vi mode inline search.
     fn start_inline_search(&mut self, direction: Direction, stop_short: bool) {
         self.inline_search_state.stop_short = stop_short;
@@ -932,9 +1359,18 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
 }
 
 impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
+    /// Write terminal content to a file.
+    fn export(&self, path: &Path, range: ExportRange, format: ExportFormat) -> io::Result<()> {
+        let colors = TermColors::from(&self.display.colors);
+        let text = self.terminal.export(range, format, &colors).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no content for export range")
+        })?;
+        fs::write(path, text)
+    }
+
     fn update_search(&mut self) {
-        let regex = match self.search_state.regex() {
-            Some(regex) => regex,
+        let (regex, mode) = match self.search_state.history_entry() {
+            Some(entry) => (&entry.regex, entry.mode),
             None => return,
         };
 
@@ -949,12 +1385,14 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
             self.search_state.dfas = None;
         } else {
             // Create search dfas for the new regex string.
-            self.search_state.dfas = RegexSearch::new(regex).ok();
+            self.search_state.dfas = RegexSearch::with_mode(regex, mode).ok();
 
             // Update search highlighting.
             self.goto_match(MAX_SEARCH_WHILE_TYPING);
         }
 
+        self.start_match_count();
+
         *self.dirty = true;
     }
 
@@ -963,6 +1401,7 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
         // Unschedule pending timers.
         let timer_id = TimerId::new(Topic::DelayedSearch, self.display.window.id());
         self.scheduler.unschedule(timer_id);
+        self.search_state.incremental_search = None;
 
         // Clear focused match.
         self.search_state.focused_match = None;
@@ -984,6 +1423,9 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
 
     /// Jump to the first regex match from the search origin.
     fn goto_match(&mut self, mut limit: Option<usize>) {
+        // Cancel background search, since this search supersedes it.
+        self.search_state.incremental_search = None;
+
         let dfas = match &mut self.search_state.dfas {
             Some(dfas) => dfas,
             None => return,
@@ -996,28 +1438,7 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
         let direction = self.search_state.direction;
         let clamped_origin = self.search_state.origin.grid_clamp(self.terminal, Boundary::Grid);
         match self.terminal.search_next(dfas, clamped_origin, direction, Side::Left, limit) {
-            Some(regex_match) => {
-                let old_offset = self.terminal.grid().display_offset() as i32;
-
-                if self.terminal.mode().contains(TermMode::VI) {
-                    // Move vi cursor to the start of the match.
-                    self.terminal.vi_goto_point(*regex_match.start());
-                } else {
-                    // Select the match when vi mode is not active.
-                    self.terminal.scroll_to_point(*regex_match.start());
-                }
-
-                // Update the focused match.
-                self.search_state.focused_match = Some(regex_match);
-
-                // Store number of lines the viewport had to be moved.
-                let display_offset = self.terminal.grid().display_offset();
-                self.search_state.display_offset_delta += old_offset - display_offset as i32;
-
-                // Since we found a result, we require no delayed re-search.
-                let timer_id = TimerId::new(Topic::DelayedSearch, self.display.window.id());
-                self.scheduler.unschedule(timer_id);
-            },
+            Some(regex_match) => self.focus_match(regex_match),
             // Reset viewport only when we know there is no match, to prevent unnecessary jumping.
             None if limit.is_none() => self.search_reset_state(),
             None => {
@@ -1036,6 +1457,126 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
         *self.dirty = true;
     }
 
+    /// Search the next lines of the search running in the background.
+    ///
+    /// Without a background search, this starts searching for the first regex match from the
+    /// search origin. Unlike [`Self::goto_match`], this only searches a limited number of lines at
+    /// a time and schedules the remaining search, so input can be processed while searching.
+    fn continue_search(&mut self) {
+        let dfas = match &mut self.search_state.dfas {
+            Some(dfas) => dfas,
+            None => return,
+        };
+
+        let (search, target) = self.search_state.incremental_search.get_or_insert_with(|| {
+            let origin = self.search_state.origin.grid_clamp(self.terminal, Boundary::Grid);
+            let search = IncrementalSearch::new(origin, self.search_state.direction, Side::Left);
+            (search, SearchTarget::Focus)
+        });
+        let target = *target;
+
+        let window_id = self.display.window.id();
+        let timer_id = TimerId::new(Topic::DelayedSearch, window_id);
+        self.scheduler.unschedule(timer_id);
+
+        let regex_match = match search.step(self.terminal, dfas) {
+            Poll::Ready(regex_match) => regex_match,
+            Poll::Pending => {
+                let event = Event::new(EventType::SearchNext, window_id);
+                self.scheduler.schedule(event, Duration::ZERO, false, timer_id);
+                return;
+            },
+        };
+        self.search_state.incremental_search = None;
+
+        match (regex_match, target) {
+            (Some(regex_match), SearchTarget::ViCursor(side)) => {
+                // Ignore the match if vi mode was left while searching.
+                if !self.terminal.mode().contains(TermMode::VI) {
+                    return;
+                }
+
+                let vi_point = self.terminal.vi_mode_cursor.point;
+                self.terminal.marks_mut().record_jump(vi_point);
+
+                let point = match side {
+                    Side::Left => *regex_match.start(),
+                    Side::Right => *regex_match.end(),
+                };
+                self.terminal.vi_goto_point(point);
+            },
+            (None, SearchTarget::ViCursor(_)) => return,
+            (Some(regex_match), target) => {
+                self.focus_match(regex_match);
+
+                if target == SearchTarget::Advance {
+                    self.anchor_search_origin();
+                }
+
+                // Searches finished after the search was confirmed only move the vi cursor.
+                if !self.search_active() {
+                    self.search_state.focused_match = None;
+                }
+            },
+            // Reset viewport only once we know there is no match.
+            (None, _) => self.search_reset_state(),
+        }
+
+        // Update match highlighting with the new result.
+        *self.dirty = true;
+    }
+
+    /// Set the search origin right in front of the focused match.
+    ///
+    /// This makes sure that after modifications to the regex the search is started without
+    /// moving the focused match around.
+    fn anchor_search_origin(&mut self) {
+        let focused_match = match &self.search_state.focused_match {
+            Some(focused_match) => focused_match,
+            None => return,
+        };
+
+        // Set new origin to the left/right of the match, depending on search direction.
+        let new_origin = match self.search_state.direction {
+            Direction::Right => *focused_match.start(),
+            Direction::Left => *focused_match.end(),
+        };
+
+        // Store the search origin with display offset by checking how far we need to scroll to it.
+        let old_display_offset = self.terminal.grid().display_offset() as i32;
+        self.terminal.scroll_to_point(new_origin);
+        let new_display_offset = self.terminal.grid().display_offset() as i32;
+        self.search_state.display_offset_delta = new_display_offset - old_display_offset;
+
+        // Store origin and scroll back to the match.
+        self.terminal.scroll_display(Scroll::Delta(-self.search_state.display_offset_delta));
+        self.search_state.origin = new_origin;
+    }
+
+    /// Move the viewport to a search match and focus it.
+    fn focus_match(&mut self, regex_match: Match) {
+        let old_offset = self.terminal.grid().display_offset() as i32;
+
+        if self.terminal.mode().contains(TermMode::VI) {
+            // Move vi cursor to the start of the match.
+            self.terminal.vi_goto_point(*regex_match.start());
+        } else {
+            // Select the match when vi mode is not active.
+            self.terminal.scroll_to_point(*regex_match.start());
+        }
+
+        // Update the focused match.
+        self.search_state.focused_match = Some(regex_match);
+
+        // Store number of lines the viewport had to be moved.
+        let display_offset = self.terminal.grid().display_offset();
+        self.search_state.display_offset_delta += old_offset - display_offset as i32;
+
+        // Since we found a result, we require no delayed re-search.
+        let timer_id = TimerId::new(Topic::DelayedSearch, self.display.window.id());
+        self.scheduler.unschedule(timer_id);
+    }
+
     /// Cleanup the search state.
     fn exit_search(&mut self) {
         let vi_mode = self.terminal.mode().contains(TermMode::VI);
@@ -1047,6 +1588,42 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
 
         // Clear focused match.
         self.search_state.focused_match = None;
+
+        // Stop counting matches and searching in the background.
+        self.search_state.incremental_search = None;
+        self.search_state.match_counter = None;
+        let timer_id = TimerId::new(Topic::SearchCount, self.display.window.id());
+        self.scheduler.unschedule(timer_id);
+    }
+
+    /// Start counting the matches of the active search.
+    fn start_match_count(&mut self) {
+        let timer_id = TimerId::new(Topic::SearchCount, self.display.window.id());
+        self.scheduler.unschedule(timer_id);
+
+        self.search_state.match_counter =
+            self.search_state.dfas.as_ref().map(|_| MatchCounter::new(self.terminal));
+
+        self.count_search_matches();
+    }
+
+    /// Count the search matches in the next batch of lines.
+    fn count_search_matches(&mut self) {
+        let (match_counter, dfas) =
+            match (&mut self.search_state.match_counter, &mut self.search_state.dfas) {
+                (Some(match_counter), Some(dfas)) => (match_counter, dfas),
+                _ => return,
+            };
+
+        // Continue counting after other events had a chance to be processed.
+        if !match_counter.step(self.terminal, dfas) {
+            let window_id = self.display.window.id();
+            let timer_id = TimerId::new(Topic::SearchCount, window_id);
+            let event = Event::new(EventType::SearchCount, window_id);
+            self.scheduler.schedule(event, Duration::ZERO, false, timer_id);
+        }
+
+        *self.dirty = true;
     }
 
     /// Update the cursor blinking state.
@@ -1081,6 +1658,117 @@ impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
         }
     }
 
+    /// Apply the escape sequence policy to the terminal.
+    fn update_blocked_escapes(&mut self) {
+        self.escape_policy.apply(&self.config.terminal.policy, self.terminal);
+    }
+
+    /// Add copied text to the clipboard history, unless it was copied during a password prompt.
+    ///
+    /// Writing the persisted history is delayed by [`CLIPBOARD_SAVE_DELAY`].
+    fn record_clipboard(&mut self, text: &str) {
+        if self.display.secure_input {
+            return;
+        }
+
+        self.clipboard.history.record(&self.config.clipboard, text);
+
+        let window_id = self.display.window.id();
+        let timer_id = TimerId::new(Topic::ClipboardHistory, window_id);
+        if self.config.clipboard.persist_history && !self.scheduler.scheduled(timer_id) {
+            let event = Event::new(EventType::SaveClipboardHistory, window_id);
+            self.scheduler.schedule(event, CLIPBOARD_SAVE_DELAY, false, timer_id);
+        }
+    }
+
+    /// Report blocked escape sequences, at most once per [`ESCAPE_BLOCKED_INTERVAL`].
+    fn escape_blocked(&mut self) {
+        let timer_id = TimerId::new(Topic::EscapeBlocked, self.display.window.id());
+        if !self.scheduler.scheduled(timer_id) {
+            self.report_blocked_escapes();
+        }
+    }
+
+    /// Count the blocked escape sequences and prompt the user if necessary.
+    fn report_blocked_escapes(&mut self) {
+        let blocked = self.terminal.take_blocked_escapes();
+        if blocked.count == 0 {
+            return;
+        }
+
+        // Collect further blocked escape sequences until the next report.
+        let window_id = self.display.window.id();
+        let timer_id = TimerId::new(Topic::EscapeBlocked, window_id);
+        let event = Event::new(EventType::ReportBlockedEscapes, window_id);
+        self.scheduler.schedule(event, ESCAPE_BLOCKED_INTERVAL, false, timer_id);
+
+        let names: Vec<_> = blocked.categories.iter().map(|c| c.name()).collect();
+        info!("Blocked {} escape sequence(s): {}", blocked.count, names.join(", "));
+
+        let state = &mut *self.escape_policy;
+        state.blocked_count += blocked.count;
+        state.blocked_categories.insert(blocked.categories);
+
+        // Keep the prompt in front of the blocked escape sequence counter.
+        self.message_buffer.remove_target(ESCAPE_BLOCKED_TARGET);
+
+        let policy = &self.config.terminal.policy;
+        let prompt = blocked.categories.iter().find(|&category| {
+            policy.permission(category, state.untrusted) == Permission::Prompt
+                && !state.denied.contains(category)
+        });
+        if let Some(category) =
+            prompt.filter(|_| !self.message_buffer.has_target(ESCAPE_PROMPT_TARGET))
+        {
+            state.prompt = Some(category);
+
+            let text = format!("Allow {} escape sequences? [y/n]", category.name());
+            let mut message = Message::new(text, MessageType::Warning);
+            message.set_target(ESCAPE_PROMPT_TARGET.into());
+            self.message_buffer.push(message);
+        }
+
+        let categories: Vec<_> = state.blocked_categories.iter().map(|c| c.name()).collect();
+        let text = format!(
+            "Blocked {} escape sequence(s): {}",
+            state.blocked_count,
+            categories.join(", ")
+        );
+        let mut message = Message::new(text, MessageType::Warning);
+        message.set_target(ESCAPE_BLOCKED_TARGET.into());
+        self.message_buffer.push(message);
+
+        self.display.pending_update.dirty = true;
+    }
+
+    /// Update the title after the foreground process changed.
+    ///
+    /// This is repeated for all windows every [`FOREGROUND_PROCESS_INTERVAL`].
+    fn poll_foreground_process(&mut self) {
+        let window_id = self.display.window.id();
+        let timer_id = TimerId::new(Topic::ForegroundProcess, window_id);
+
+        if self.preserve_title || !self.config.window.title_tracks_process() {
+            self.scheduler.unschedule(timer_id);
+            return;
+        }
+
+        if !self.scheduler.scheduled(timer_id) {
+            let event = Event::new(EventType::PollForegroundProcess, window_id);
+            self.scheduler.schedule(event, FOREGROUND_PROCESS_INTERVAL, true, timer_id);
+        }
+
+        #[cfg(not(windows))]
+        let process = Some(foreground_process(self.master_fd, self.shell_pid));
+        #[cfg(windows)]
+        let process = None;
+
+        if process != self.display.title_state.process {
+            self.display.title_state.process = process;
+            self.display.update_title(self.config, self.terminal.user_vars());
+        }
+    }
+
     fn schedule_blinking(&mut self) {
         let window_id = self.display.window.id();
         let timer_id = TimerId::new(Topic::BlinkCursor, window_id);
@@ -1282,7 +1970,8 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
     pub fn handle_event(&mut self, event: WinitEvent<Event>) {
         match event {
             WinitEvent::UserEvent(Event { payload, .. }) => match payload {
-                EventType::SearchNext => self.ctx.goto_match(None),
+                EventType::SearchNext => self.ctx.continue_search(),
+                EventType::SearchCount => self.ctx.count_search_matches(),
                 EventType::Scroll(scroll) => self.ctx.scroll(scroll),
                 EventType::BlinkCursor => {
                     // Only change state when timeout isn't reached, since we could get
@@ -1292,6 +1981,11 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                         *self.ctx.dirty = true;
                     }
                 },
+                EventType::PollForegroundProcess => self.ctx.poll_foreground_process(),
+                EventType::ReportBlockedEscapes => self.ctx.report_blocked_escapes(),
+                EventType::SaveClipboardHistory => self.ctx.clipboard.history.save(),
+                EventType::FuzzySearch => self.ctx.continue_fuzzy_search(),
+                EventType::Trigger(action) => self.trigger(action),
                 EventType::BlinkCursorTimeout => {
                     // Disable blinking after timeout reached.
                     let timer_id = TimerId::new(Topic::BlinkCursor, self.ctx.display.window.id());
@@ -1308,39 +2002,40 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                 EventType::Terminal(event) => match event {
                     TerminalEvent::Title(title) => {
                         if !self.ctx.preserve_title && self.ctx.config.window.dynamic_title {
-                            self.ctx.window().set_title(title);
+                            self.ctx.display.title_state.terminal_title = Some(title);
+                            let user_vars = self.ctx.terminal.user_vars();
+                            self.ctx.display.update_title(self.ctx.config, user_vars);
                         }
                     },
                     TerminalEvent::ResetTitle => {
-                        let window_config = &self.ctx.config.window;
-                        if window_config.dynamic_title {
-                            self.ctx.display.window.set_title(window_config.identity.title.clone());
-                        }
-                    },
-                    TerminalEvent::Bell => {
-                        // Set window urgency hint when window is not focused.
-                        let focused = self.ctx.terminal.is_focused;
-                        if !focused && self.ctx.terminal.mode().contains(TermMode::URGENCY_HINTS) {
-                            self.ctx.window().set_urgent(true);
-                        }
-
-                        // Ring visual bell.
-                        self.ctx.display.visual_bell.ring();
-
-                        // Execute bell command.
-                        if let Some(bell_command) = &self.ctx.config.bell.command {
-                            self.ctx.spawn_daemon(bell_command.program(), bell_command.args());
+                        self.ctx.display.title_state.terminal_title = None;
+                        if self.ctx.config.window.dynamic_title {
+                            let user_vars = self.ctx.terminal.user_vars();
+                            self.ctx.display.update_title(self.ctx.config, user_vars);
                         }
                     },
+                    TerminalEvent::Bell => self.ctx.ring_bell(),
                     TerminalEvent::ClipboardStore(clipboard_type, content) => {
                         if self.ctx.terminal.is_focused {
+                            self.ctx.record_clipboard(&content);
                             self.ctx.clipboard.store(clipboard_type, content);
                         }
                     },
                     TerminalEvent::ClipboardLoad(clipboard_type, format) => {
-                        if self.ctx.terminal.is_focused {
-                            let text = format(self.ctx.clipboard.load(clipboard_type).as_str());
-                            self.ctx.write_to_pty(text.into_bytes());
+                        let blocked = self.ctx.display.secure_input
+                            && self.ctx.config.terminal.secure_input.block_clipboard_read;
+                        if self.ctx.terminal.is_focused && !blocked {
+                            let text = self.ctx.clipboard.load(clipboard_type);
+                            let protection = &self.ctx.config.terminal.paste_protection;
+                            let warnings = paste::warnings(protection, &text, true);
+                            if warnings.is_empty() {
+                                self.ctx.write_to_pty(format(&text).into_bytes());
+                            } else {
+                                let target = PasteTarget::ClipboardLoad(format);
+                                let preview = PastePreview::new(text, target, warnings);
+                                self.ctx.display.paste_preview = Some(preview);
+                                *self.ctx.dirty = true;
+                            }
                         }
                     },
                     TerminalEvent::ColorRequest(index, format) => {
@@ -1353,21 +2048,42 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                         let text = format(self.ctx.size_info().into());
                         self.ctx.write_to_pty(text.into_bytes());
                     },
+                    TerminalEvent::EscapeBlocked(_) => self.ctx.escape_blocked(),
+                    TerminalEvent::UserVar(..) => {
+                        if !self.ctx.preserve_title {
+                            let user_vars = self.ctx.terminal.user_vars();
+                            self.ctx.display.update_title(self.ctx.config, user_vars);
+                        }
+                    },
                     TerminalEvent::PtyWrite(text) => self.ctx.write_to_pty(text.into_bytes()),
                     TerminalEvent::MouseCursorDirty => self.reset_mouse_cursor(),
                     TerminalEvent::CursorBlinkingChange => self.ctx.update_cursor_blinking(),
                     TerminalEvent::Exit | TerminalEvent::ChildExit(_) | TerminalEvent::Wakeup => (),
                 },
                 #[cfg(unix)]
+                EventType::IpcExport(ipc_export) => {
+                    let IpcExport { path, range, format, .. } = ipc_export;
+                    if let Err(err) = self.ctx.export(&path, range, format) {
+                        error!("Unable to export terminal content to {path:?}: {err}");
+                    }
+                },
+                #[cfg(unix)]
+                EventType::IpcGetUserVar(ipc_get_user_var, reply) => {
+                    let value = self.ctx.terminal.user_vars().get(&ipc_get_user_var.name);
+                    let _ = reply.send(value.cloned());
+                },
+                #[cfg(unix)]
                 EventType::IpcConfig(_) => (),
                 EventType::Message(_)
                 | EventType::ConfigReload(_)
                 | EventType::CreateWindow(_)
+                | EventType::RestartShell
+                | EventType::PollSecureInput
                 | EventType::Frame => (),
             },
             WinitEvent::WindowEvent { event, .. } => {
                 match event {
-                    WindowEvent::CloseRequested => self.ctx.terminal.exit(),
+                    WindowEvent::CloseRequested => self.ctx.request_close(),
                     WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                         let old_scale_factor =
                             mem::replace(&mut self.ctx.window().scale_factor, scale_factor);
@@ -1416,9 +2132,16 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                             *self.ctx.dirty = true;
                         }
 
-                        // Reset the urgency hint when gaining focus.
+                        // Reset the urgency hint and bell indicator when gaining focus.
                         if is_focused {
                             self.ctx.window().set_urgent(false);
+
+                            if mem::take(&mut self.ctx.display.title_state.bell)
+                                && !self.ctx.preserve_title
+                            {
+                                let user_vars = self.ctx.terminal.user_vars();
+                                self.ctx.display.update_title(self.ctx.config, user_vars);
+                            }
                         }
 
                         self.ctx.update_cursor_blinking();
@@ -1429,7 +2152,7 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                     },
                     WindowEvent::DroppedFile(path) => {
                         let path: String = path.to_string_lossy().into();
-                        self.ctx.paste(&(path + " "), true);
+                        self.ctx.guarded_paste(&(path + " "));
                     },
                     WindowEvent::CursorLeft { .. } => {
                         self.ctx.mouse.inside_text_area = false;
@@ -1500,6 +2223,7 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
 /// triggered.
 pub struct Processor {
     windows: HashMap<WindowId, WindowContext, RandomState>,
+    reactor: Reactor,
     gl_display: Option<GlutinDisplay>,
     #[cfg(unix)]
     global_ipc_options: ParsedOptions,
@@ -1515,15 +2239,19 @@ impl Processor {
         config: UiConfig,
         cli_options: CliOptions,
         _event_loop: &EventLoop<Event>,
-    ) -> Processor {
-        Processor {
+    ) -> Result<Processor, Box<dyn Error>> {
+        let reactor =
+            Reactor::new().map_err(|err| format!("Failed to start PTY reactor: {err}"))?;
+
+        Ok(Processor {
+            reactor,
             cli_options,
             gl_display: None,
             config: Rc::new(config),
             windows: Default::default(),
             #[cfg(unix)]
             global_ipc_options: Default::default(),
-        }
+        })
     }
 
     /// Create initial window and load GL platform.
@@ -1537,7 +2265,7 @@ impl Processor {
         options: WindowOptions,
     ) -> Result<(), Box<dyn Error>> {
         let window_context =
-            WindowContext::initial(event_loop, proxy, self.config.clone(), options)?;
+            WindowContext::initial(event_loop, &self.reactor, proxy, self.config.clone(), options)?;
 
         self.gl_display = Some(window_context.display.gl_context().display());
         self.windows.insert(window_context.id(), window_context);
@@ -1562,8 +2290,14 @@ impl Processor {
         config = config_overrides.override_config_rc(config);
 
         #[allow(unused_mut)]
-        let mut window_context =
-            window.additional(event_loop, proxy, config, options, config_overrides)?;
+        let mut window_context = window.additional(
+            event_loop,
+            &self.reactor,
+            proxy,
+            config,
+            options,
+            config_overrides,
+        )?;
 
         self.windows.insert(window_context.id(), window_context);
         Ok(())
@@ -1590,7 +2324,7 @@ impl Processor {
         // which is done by `move` into event loop.
         let mut clipboard = unsafe { Clipboard::new(event_loop.raw_display_handle()) };
         let result = event_loop.run(move |event, event_loop| {
-            if self.config.debug.print_events {
+            if self.config.debug.print_events && !self.is_secure_input(&event) {
                 info!("winit event: {:?}", event);
             }
 
@@ -1645,6 +2379,9 @@ impl Processor {
                     payload: EventType::Terminal(TerminalEvent::Wakeup),
                 }) => {
                     if let Some(window_context) = self.windows.get_mut(&window_id) {
+                        window_context.update_secure_input(&mut scheduler);
+                        window_context.invalidate_search_matches(&mut scheduler);
+                        window_context.run_triggers();
                         window_context.dirty = true;
                         if window_context.display.window.has_frame {
                             window_context.display.window.request_redraw();
@@ -1663,6 +2400,31 @@ impl Processor {
                         }
                     }
                 },
+                // Apply the exit policy once the shell is gone.
+                WinitEvent::UserEvent(Event {
+                    window_id: Some(window_id),
+                    payload: EventType::Terminal(TerminalEvent::ChildExit(status)),
+                }) => {
+                    if let Some(window_context) = self.windows.get_mut(&window_id) {
+                        window_context.child_exit(&self.reactor, status);
+                    }
+                },
+                WinitEvent::UserEvent(Event {
+                    window_id: Some(window_id),
+                    payload: EventType::RestartShell,
+                }) => {
+                    if let Some(window_context) = self.windows.get_mut(&window_id) {
+                        window_context.restart_shell(&self.reactor);
+                    }
+                },
+                WinitEvent::UserEvent(Event {
+                    window_id: Some(window_id),
+                    payload: EventType::PollSecureInput,
+                }) => {
+                    if let Some(window_context) = self.windows.get_mut(&window_id) {
+                        window_context.update_secure_input(&mut scheduler);
+                    }
+                },
                 // Check for shutdown.
                 WinitEvent::UserEvent(Event {
                     window_id: Some(window_id),
@@ -1677,6 +2439,9 @@ impl Processor {
                     // Unschedule pending events.
                     scheduler.unschedule_window(window_context.id());
 
+                    // Write the clipboard history, since its timer might have been removed.
+                    clipboard.history.save();
+
                     // Shutdown if no more terminals are open.
                     if self.windows.is_empty() {
                         // Write ref tests of last window to disk.
@@ -1826,6 +2591,17 @@ impl Processor {
         }
     }
 
+    /// Check if an event contains keyboard input for a password prompt.
+    fn is_secure_input(&self, event: &WinitEvent<Event>) -> bool {
+        match event {
+            WinitEvent::WindowEvent {
+                window_id,
+                event: WindowEvent::KeyboardInput { .. } | WindowEvent::Ime(_),
+            } => self.windows.get(window_id).is_some_and(|window| window.display.secure_input),
+            _ => false,
+        }
+    }
+
     /// Check if an event is irrelevant and can be skipped.
     fn skip_event(event: &WinitEvent<Event>) -> bool {
         match event {
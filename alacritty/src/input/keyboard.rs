@@ -9,11 +9,14 @@ use winit::keyboard::{Key, KeyLocation, ModifiersState, NamedKey};
 use winit::platform::macos::OptionAsAlt;
 
 use alacritty_terminal::event::EventListener;
+use alacritty_terminal::grid::Dimensions;
 use alacritty_terminal::term::TermMode;
 use winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
 
 use crate::config::{Action, BindingKey, BindingMode};
-use crate::event::TYPING_SEARCH_DELAY;
+use crate::display::PASTE_PREVIEW_HEADER_LINES;
+use crate::event::{CLOSE_CONFIRMATION_TARGET, ESCAPE_PROMPT_TARGET, TYPING_SEARCH_DELAY};
+use crate::input::vi::ViInput;
 use crate::input::{ActionContext, Execute, Processor};
 use crate::scheduler::{TimerId, Topic};
 
@@ -43,6 +46,52 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             return;
         }
 
+        // Paste preview captures all keys until the paste is confirmed or cancelled.
+        if self.ctx.display().paste_preview.is_some() {
+            self.paste_preview_input(&key, text);
+            return;
+        }
+
+        // Clipboard history picker captures all keys until it is closed.
+        if self.ctx.display().clipboard_picker.is_some() {
+            self.clipboard_picker_input(&key, text, mods);
+            return;
+        }
+
+        // Fuzzy search captures all keys until it is closed.
+        if self.ctx.display().fuzzy_search.is_some() {
+            self.fuzzy_search_input(&key, text);
+            return;
+        }
+
+        // Pending confirmation prompts capture the next key.
+        let prompt = self.ctx.message().and_then(|message| message.target()).cloned();
+        match prompt.as_deref() {
+            Some(CLOSE_CONFIRMATION_TARGET) => {
+                match text {
+                    "y" | "Y" => self.ctx.terminal_mut().exit(),
+                    // Ignore modifiers, which do not produce any text.
+                    "" => (),
+                    _ => self.ctx.pop_message(),
+                }
+                return;
+            },
+            // Only explicit answers are captured, so typing isn't swallowed by the prompt.
+            Some(ESCAPE_PROMPT_TARGET) => {
+                let allow = match (text, &key.logical_key) {
+                    ("y" | "Y", _) => Some(true),
+                    ("n" | "N", _) | (_, Key::Named(NamedKey::Escape)) => Some(false),
+                    _ => None,
+                };
+
+                if let Some(allow) = allow {
+                    self.ctx.answer_escape_prompt(allow);
+                    return;
+                }
+            },
+            _ => (),
+        }
+
         // First key after inline search is captured.
         let inline_state = self.ctx.inline_search_state();
         if mem::take(&mut inline_state.char_pending) {
@@ -57,6 +106,34 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             return;
         }
 
+        // Counts and text objects of vi commands are captured.
+        let vi_input = mode.contains(TermMode::VI)
+            && !self.ctx.search_active()
+            && !mods
+                .intersects(ModifiersState::CONTROL | ModifiersState::ALT | ModifiersState::SUPER);
+        let mut chars = text.chars();
+        if let (true, Some(c), None) = (vi_input, chars.next(), chars.next()) {
+            let visual = !self.ctx.selection_is_empty();
+            match self.ctx.vi_command_state().input(c, visual) {
+                ViInput::Ignored => (),
+                ViInput::Consumed => {
+                    self.ctx.mark_dirty();
+                    return;
+                },
+                ViInput::Command(command) => {
+                    Action::vi_command(&mut self.ctx, command);
+                    return;
+                },
+                ViInput::SetMark(name) => {
+                    let terminal = self.ctx.terminal_mut();
+                    let point = terminal.vi_mode_cursor.point;
+                    terminal.marks_mut().set(name, point);
+                    self.ctx.mark_dirty();
+                    return;
+                },
+            }
+        }
+
         // Reset search delay when the user is still typing.
         self.reset_search_delay();
 
@@ -75,6 +152,11 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
 
         // Vi mode on its own doesn't have any input, the search input was done before.
         if mode.contains(TermMode::VI) {
+            // Keys without binding cancel pending vi commands.
+            if !text.is_empty() && self.ctx.vi_command_state().reset() {
+                self.ctx.mark_dirty();
+            }
+
             return;
         }
 
@@ -197,7 +279,9 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
                 },
             };
 
-            if binding.is_triggered_by(mode, mods, &key) {
+            if binding.is_triggered_by(mode, mods, &key)
+                && binding.user_var_matches(self.ctx.terminal().user_vars())
+            {
                 // Pass through the key if any of the bindings has the `ReceiveChar` action.
                 *suppress_chars.get_or_insert(true) &= binding.action != Action::ReceiveChar;
 
@@ -209,6 +293,84 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         suppress_chars.unwrap_or(false)
     }
 
+    /// Handle key input while a paste is waiting for confirmation.
+    fn paste_preview_input(&mut self, key: &KeyEvent, text: &str) {
+        let size_info = self.ctx.size_info();
+        let height = size_info.screen_lines().saturating_sub(PASTE_PREVIEW_HEADER_LINES);
+        let delta = match key.logical_key.as_ref() {
+            Key::Named(NamedKey::Enter) => return self.ctx.confirm_paste(false),
+            Key::Named(NamedKey::Escape) => return self.ctx.cancel_paste(),
+            Key::Named(NamedKey::ArrowUp) => -1,
+            Key::Named(NamedKey::ArrowDown) => 1,
+            Key::Named(NamedKey::PageUp) => -(height as isize),
+            Key::Named(NamedKey::PageDown) => height as isize,
+            _ if text.eq_ignore_ascii_case("s") => return self.ctx.confirm_paste(true),
+            _ => return,
+        };
+
+        if let Some(preview) = &mut self.ctx.display().paste_preview {
+            preview.scroll(delta, height, size_info.columns());
+            self.ctx.mark_dirty();
+        }
+    }
+
+    /// Handle key input while the clipboard history picker is open.
+    fn clipboard_picker_input(&mut self, key: &KeyEvent, text: &str, mods: ModifiersState) {
+        let picker = match &mut self.ctx.display().clipboard_picker {
+            Some(picker) => picker,
+            None => return,
+        };
+
+        match key.logical_key.as_ref() {
+            Key::Named(NamedKey::Enter) => {
+                return self.ctx.accept_clipboard_entry(mods.shift_key());
+            },
+            Key::Named(NamedKey::Escape) => self.ctx.display().clipboard_picker = None,
+            Key::Named(NamedKey::Backspace) => picker.pop(),
+            Key::Named(NamedKey::ArrowUp) => picker.select(1),
+            Key::Named(NamedKey::ArrowDown) => picker.select(-1),
+            _ => {
+                let mut chars = text.chars().filter(|c| !c.is_control()).peekable();
+                if chars.peek().is_none() {
+                    return;
+                }
+                chars.for_each(|c| picker.push(c));
+            },
+        }
+
+        self.ctx.mark_dirty();
+    }
+
+    /// Handle key input while the fuzzy search picker is open.
+    fn fuzzy_search_input(&mut self, key: &KeyEvent, text: &str) {
+        let fuzzy_search = match &mut self.ctx.display().fuzzy_search {
+            Some(fuzzy_search) => fuzzy_search,
+            None => return,
+        };
+
+        match key.logical_key.as_ref() {
+            Key::Named(NamedKey::Enter) => return self.ctx.confirm_fuzzy_search(),
+            Key::Named(NamedKey::Escape) => self.ctx.display().fuzzy_search = None,
+            Key::Named(NamedKey::Backspace) => {
+                fuzzy_search.pop();
+
+                // Score the first lines for the new query right away.
+                return self.ctx.continue_fuzzy_search();
+            },
+            Key::Named(NamedKey::ArrowUp) => fuzzy_search.select(1),
+            Key::Named(NamedKey::ArrowDown) => fuzzy_search.select(-1),
+            _ => {
+                let mut chars = text.chars().filter(|c| !c.is_control()).peekable();
+                if chars.peek().is_none() {
+                    return;
+                }
+                chars.for_each(|c| fuzzy_search.push(c));
+            },
+        }
+
+        self.ctx.mark_dirty();
+    }
+
     /// Handle key release.
     fn key_release(&mut self, key: KeyEvent, mode: TermMode, mods: ModifiersState) {
         if !mode.contains(TermMode::REPORT_EVENT_TYPES)
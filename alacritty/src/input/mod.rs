@@ -14,7 +14,7 @@ use std::marker::PhantomData;
 use std::mem;
 use std::time::{Duration, Instant};
 
-use log::debug;
+use log::{debug, warn};
 use winit::dpi::PhysicalPosition;
 use winit::event::{
     ElementState, Modifiers, MouseButton, MouseScrollDelta, Touch as TouchEvent, TouchPhase,
@@ -30,12 +30,13 @@ use alacritty_terminal::event::EventListener;
 use alacritty_terminal::grid::{Dimensions, Scroll};
 use alacritty_terminal::index::{Boundary, Column, Direction, Point, Side};
 use alacritty_terminal::selection::SelectionType;
-use alacritty_terminal::term::search::Match;
+use alacritty_terminal::term::search::SearchMode;
 use alacritty_terminal::term::{ClipboardType, Term, TermMode};
 use alacritty_terminal::vi_mode::ViMotion;
 use alacritty_terminal::vte::ansi::{ClearMode, Handler};
 
 use crate::clipboard::Clipboard;
+use crate::config::trigger::TriggerAction;
 use crate::config::{Action, BindingMode, MouseAction, SearchAction, UiConfig, ViAction};
 use crate::display::hint::HintMatch;
 use crate::display::window::Window;
@@ -45,8 +46,12 @@ use crate::event::{
 };
 use crate::message_bar::{self, Message};
 use crate::scheduler::{Scheduler, TimerId, Topic};
+use crate::trigger::{self, TriggeredAction};
 
 pub mod keyboard;
+pub mod vi;
+
+use vi::{ViCommand, ViCommandState, ViMarkAction, ViOperator, ViTarget};
 
 /// Font size change interval in px.
 pub const FONT_SIZE_STEP: f32 = 1.;
@@ -80,6 +85,8 @@ pub trait ActionContext<T: EventListener> {
     fn mark_dirty(&mut self) {}
     fn size_info(&self) -> SizeInfo;
     fn copy_selection(&mut self, _ty: ClipboardType) {}
+    fn copy_selection_as_html(&mut self) {}
+    fn save_scrollback(&mut self) {}
     fn start_selection(&mut self, _ty: SelectionType, _point: Point, _side: Side) {}
     fn toggle_selection(&mut self, _ty: SelectionType, _point: Point, _side: Side) {}
     fn update_selection(&mut self, _point: Point, _side: Side) {}
@@ -95,6 +102,10 @@ pub trait ActionContext<T: EventListener> {
     fn terminal(&self) -> &Term<T>;
     fn terminal_mut(&mut self) -> &mut Term<T>;
     fn spawn_new_instance(&mut self) {}
+    fn restart_shell(&mut self) {}
+    fn toggle_untrusted(&mut self) {}
+    fn answer_escape_prompt(&mut self, _allow: bool) {}
+    fn request_close(&mut self) {}
     #[cfg(target_os = "macos")]
     fn create_new_window(&mut self, _tabbing_id: Option<String>) {}
     #[cfg(not(target_os = "macos"))]
@@ -114,15 +125,18 @@ pub trait ActionContext<T: EventListener> {
     fn cancel_search(&mut self) {}
     fn search_input(&mut self, _c: char) {}
     fn search_pop_word(&mut self) {}
+    fn search_toggle_mode(&mut self, _mode: SearchMode) {}
+    fn copy_all_search_matches(&mut self) {}
     fn search_history_previous(&mut self) {}
     fn search_history_next(&mut self) {}
-    fn search_next(&mut self, origin: Point, direction: Direction, side: Side) -> Option<Match>;
+    fn vi_search(&mut self, _origin: Point, _direction: Direction, _side: Side) {}
     fn advance_search_origin(&mut self, _direction: Direction) {}
     fn search_direction(&self) -> Direction;
     fn search_active(&self) -> bool;
     fn on_typing_start(&mut self) {}
     fn toggle_vi_mode(&mut self) {}
     fn inline_search_state(&mut self) -> &mut InlineSearchState;
+    fn vi_command_state(&mut self) -> &mut ViCommandState;
     fn start_inline_search(&mut self, _direction: Direction, _stop_short: bool) {}
     fn inline_search_next(&mut self) {}
     fn inline_search_previous(&mut self) {}
@@ -131,6 +145,18 @@ pub trait ActionContext<T: EventListener> {
     fn expand_selection(&mut self) {}
     fn on_terminal_input_start(&mut self) {}
     fn paste(&mut self, _text: &str, _bracketed: bool) {}
+    fn guarded_paste(&mut self, _text: &str) {}
+    fn confirm_paste(&mut self, _single_line: bool) {}
+    fn cancel_paste(&mut self) {}
+    fn paste_pending(&self) -> bool {
+        false
+    }
+    fn show_clipboard_history(&mut self) {}
+    fn accept_clipboard_entry(&mut self, _copy: bool) {}
+    fn start_fuzzy_search(&mut self) {}
+    fn continue_fuzzy_search(&mut self) {}
+    fn confirm_fuzzy_search(&mut self) {}
+    fn ring_bell(&mut self) {}
     fn spawn_daemon<I, S>(&self, _program: &str, _args: I)
     where
         I: IntoIterator<Item = S> + Debug + Copy,
@@ -152,6 +178,103 @@ impl Action {
             selection.include_all();
         }
     }
+
+    /// Record the vi cursor position in the jump list before a large motion.
+    fn record_jump<T, A>(ctx: &mut A)
+    where
+        A: ActionContext<T>,
+        T: EventListener,
+    {
+        let terminal = ctx.terminal_mut();
+        if terminal.mode().contains(TermMode::VI) {
+            let point = terminal.vi_mode_cursor.point;
+            terminal.marks_mut().record_jump(point);
+        }
+    }
+
+    /// Move the vi cursor to a position from the jump list.
+    fn vi_jump<T, A>(ctx: &mut A, point: Option<Point>)
+    where
+        A: ActionContext<T>,
+        T: EventListener,
+    {
+        if let Some(point) = point {
+            ctx.on_typing_start();
+            ctx.terminal_mut().vi_goto_point(point);
+            ctx.mark_dirty();
+        }
+    }
+
+    /// Execute a parsed vi command.
+    fn vi_command<T, A>(ctx: &mut A, command: ViCommand)
+    where
+        A: ActionContext<T>,
+        T: EventListener,
+    {
+        ctx.on_typing_start();
+        ctx.mark_dirty();
+
+        match command.operator {
+            Some(ViOperator::Yank) => {
+                let (selection, cursor) = match command.selection(ctx.terminal_mut()) {
+                    Some(selection) => selection,
+                    None => return,
+                };
+                ctx.terminal_mut().selection = Some(selection);
+                ctx.copy_selection(ClipboardType::Clipboard);
+                ctx.clear_selection();
+
+                // Like vi, move the cursor to the start of the yanked text.
+                ctx.terminal_mut().vi_goto_point(cursor);
+            },
+            None => match command.target {
+                ViTarget::Motion(motion) => {
+                    if matches!(
+                        motion,
+                        ViMotion::High
+                            | ViMotion::Middle
+                            | ViMotion::Low
+                            | ViMotion::Bracket
+                            | ViMotion::ParagraphUp
+                            | ViMotion::ParagraphDown
+                            | ViMotion::SentenceLeft
+                            | ViMotion::SentenceRight
+                            | ViMotion::Top
+                            | ViMotion::Bottom
+                    ) {
+                        Self::record_jump(ctx);
+                    }
+
+                    ctx.terminal_mut().vi_motion_count(motion, command.count)
+                },
+                ViTarget::TextObject(object) => {
+                    let terminal = ctx.terminal_mut();
+                    let selection = object.selection(terminal, terminal.vi_mode_cursor.point);
+                    let range = selection.as_ref().and_then(|s| s.to_range(terminal));
+                    let (selection, range) = match selection.zip(range) {
+                        Some(selection) => selection,
+                        None => return,
+                    };
+
+                    // Move the cursor to the end of the object before replacing the selection,
+                    // since cursor motion updates the selection.
+                    terminal.selection = None;
+                    terminal.vi_goto_point(range.end);
+                    terminal.selection = Some(selection);
+                    ctx.copy_selection(ClipboardType::Selection);
+                },
+                ViTarget::Mark(name) => {
+                    let mark = match ctx.terminal().marks().get(name) {
+                        Some(mark) => mark,
+                        None => return,
+                    };
+                    Self::record_jump(ctx);
+                    ctx.terminal_mut().vi_goto_point(mark);
+                },
+                ViTarget::Lines => (),
+            },
+        }
+    }
 }
 
 trait Execute<T: EventListener> {
@@ -161,6 +284,18 @@ trait Execute<T: EventListener> {
 impl<T: EventListener> Execute<T> for Action {
     #[inline]
     fn execute<A: ActionContext<T>>(&self, ctx: &mut A) {
+        // Pending vi commands are cancelled by all actions which are not part of the command.
+        let command_action = matches!(
+            self,
+            Action::ViMotion(_)
+                | Action::Vi(
+                    ViAction::Yank | ViAction::Repeat | ViAction::Goto | ViAction::JumpToMark
+                )
+        );
+        if !command_action && ctx.vi_command_state().reset() {
+            ctx.mark_dirty();
+        }
+
         match self {
             Action::Esc(s) => ctx.paste(s, false),
             Action::Command(program) => ctx.spawn_daemon(program.program(), program.args()),
@@ -178,10 +313,45 @@ impl<T: EventListener> Execute<T> for Action {
                 debug!("Ignoring {action:?}: Vi mode inactive");
             },
             Action::ViMotion(motion) => {
-                ctx.on_typing_start();
-                ctx.terminal_mut().vi_motion(*motion);
+                let command = ctx.vi_command_state().motion(*motion);
+                Self::vi_command(ctx, command);
+            },
+            Action::Vi(ViAction::Yank) if !ctx.selection_is_empty() => {
+                ctx.vi_command_state().reset();
+                ctx.copy_selection(ClipboardType::Clipboard);
+                ctx.clear_selection();
+            },
+            Action::Vi(ViAction::Yank) => match ctx.vi_command_state().operator(ViOperator::Yank) {
+                Some(command) => Self::vi_command(ctx, command),
+                None => ctx.mark_dirty(),
+            },
+            Action::Vi(ViAction::Repeat) => {
+                if let Some(command) = ctx.vi_command_state().repeat() {
+                    Self::vi_command(ctx, command);
+                }
+            },
+            Action::Vi(ViAction::Goto) => {
+                ctx.vi_command_state().goto();
+                ctx.mark_dirty();
+            },
+            Action::Vi(ViAction::SetMark) => {
+                ctx.vi_command_state().mark(ViMarkAction::Set);
+                ctx.mark_dirty();
+            },
+            Action::Vi(ViAction::JumpToMark) => {
+                ctx.vi_command_state().mark(ViMarkAction::Jump);
                 ctx.mark_dirty();
             },
+            Action::Vi(ViAction::JumpBack) => {
+                let terminal = ctx.terminal_mut();
+                let point = terminal.vi_mode_cursor.point;
+                let jump = terminal.marks_mut().jump_back(point);
+                Self::vi_jump(ctx, jump);
+            },
+            Action::Vi(ViAction::JumpForward) => {
+                let jump = ctx.terminal_mut().marks_mut().jump_forward();
+                Self::vi_jump(ctx, jump);
+            },
             Action::Vi(ViAction::ToggleNormalSelection) => {
                 Self::toggle_selection(ctx, SelectionType::Simple);
             },
@@ -213,10 +383,7 @@ impl<T: EventListener> Execute<T> for Action {
                     Direction::Left => vi_point.sub(terminal, Boundary::None, 1),
                 };
 
-                if let Some(regex_match) = ctx.search_next(origin, direction, Side::Left) {
-                    ctx.terminal_mut().vi_goto_point(*regex_match.start());
-                    ctx.mark_dirty();
-                }
+                ctx.vi_search(origin, direction, Side::Left);
             },
             Action::Vi(ViAction::SearchPrevious) => {
                 ctx.on_typing_start();
@@ -229,28 +396,19 @@ impl<T: EventListener> Execute<T> for Action {
                     Direction::Left => vi_point.sub(terminal, Boundary::None, 1),
                 };
 
-                if let Some(regex_match) = ctx.search_next(origin, direction, Side::Left) {
-                    ctx.terminal_mut().vi_goto_point(*regex_match.start());
-                    ctx.mark_dirty();
-                }
+                ctx.vi_search(origin, direction, Side::Left);
             },
             Action::Vi(ViAction::SearchStart) => {
                 let terminal = ctx.terminal();
                 let origin = terminal.vi_mode_cursor.point.sub(terminal, Boundary::None, 1);
 
-                if let Some(regex_match) = ctx.search_next(origin, Direction::Left, Side::Left) {
-                    ctx.terminal_mut().vi_goto_point(*regex_match.start());
-                    ctx.mark_dirty();
-                }
+                ctx.vi_search(origin, Direction::Left, Side::Left);
             },
             Action::Vi(ViAction::SearchEnd) => {
                 let terminal = ctx.terminal();
                 let origin = terminal.vi_mode_cursor.point.add(terminal, Boundary::None, 1);
 
-                if let Some(regex_match) = ctx.search_next(origin, Direction::Right, Side::Right) {
-                    ctx.terminal_mut().vi_goto_point(*regex_match.end());
-                    ctx.mark_dirty();
-                }
+                ctx.vi_search(origin, Direction::Right, Side::Right);
             },
             Action::Vi(ViAction::CenterAroundViCursor) => {
                 let term = ctx.terminal();
@@ -293,22 +451,39 @@ impl<T: EventListener> Execute<T> for Action {
                 ctx.start_search(direction);
             },
             Action::Search(SearchAction::SearchDeleteWord) => ctx.search_pop_word(),
+            Action::Search(SearchAction::SearchToggleRegex) => {
+                ctx.search_toggle_mode(SearchMode::LITERAL)
+            },
+            Action::Search(SearchAction::SearchToggleCaseSensitive) => {
+                ctx.search_toggle_mode(SearchMode::CASE_SENSITIVE)
+            },
+            Action::Search(SearchAction::SearchToggleWholeWord) => {
+                ctx.search_toggle_mode(SearchMode::WHOLE_WORD)
+            },
+            Action::Search(SearchAction::CopyAllMatches) => ctx.copy_all_search_matches(),
             Action::Search(SearchAction::SearchHistoryPrevious) => ctx.search_history_previous(),
             Action::Search(SearchAction::SearchHistoryNext) => ctx.search_history_next(),
             Action::Mouse(MouseAction::ExpandSelection) => ctx.expand_selection(),
             Action::SearchForward => ctx.start_search(Direction::Right),
             Action::SearchBackward => ctx.start_search(Direction::Left),
             Action::Copy => ctx.copy_selection(ClipboardType::Clipboard),
+            Action::CopySelectionAsHtml => ctx.copy_selection_as_html(),
+            Action::SaveScrollbackAs => ctx.save_scrollback(),
+            Action::RestartShell => ctx.restart_shell(),
+            Action::ToggleUntrusted => ctx.toggle_untrusted(),
+            Action::ShowClipboardHistory => ctx.show_clipboard_history(),
+            Action::FuzzySearch => ctx.start_fuzzy_search(),
+            Action::Bell => ctx.ring_bell(),
             #[cfg(not(any(target_os = "macos", windows)))]
             Action::CopySelection => ctx.copy_selection(ClipboardType::Selection),
             Action::ClearSelection => ctx.clear_selection(),
             Action::Paste => {
                 let text = ctx.clipboard_mut().load(ClipboardType::Clipboard);
-                ctx.paste(&text, true);
+                ctx.guarded_paste(&text);
             },
             Action::PasteSelection => {
                 let text = ctx.clipboard_mut().load(ClipboardType::Selection);
-                ctx.paste(&text, true);
+                ctx.guarded_paste(&text);
             },
             Action::ToggleFullscreen => ctx.window().toggle_fullscreen(),
             Action::ToggleMaximized => ctx.window().toggle_maximized(),
@@ -321,7 +496,7 @@ impl<T: EventListener> Execute<T> for Action {
             #[cfg(not(target_os = "macos"))]
             Action::Hide => ctx.window().set_visible(false),
             Action::Minimize => ctx.window().set_minimized(true),
-            Action::Quit => ctx.terminal_mut().exit(),
+            Action::Quit => ctx.request_close(),
             Action::IncreaseFontSize => ctx.change_font_size(FONT_SIZE_STEP),
             Action::DecreaseFontSize => ctx.change_font_size(-FONT_SIZE_STEP),
             Action::ResetFontSize => ctx.reset_font_size(),
@@ -356,6 +531,7 @@ impl<T: EventListener> Execute<T> for Action {
             Action::ScrollLineUp => ctx.scroll(Scroll::Delta(1)),
             Action::ScrollLineDown => ctx.scroll(Scroll::Delta(-1)),
             Action::ScrollToTop => {
+                Self::record_jump(ctx);
                 ctx.scroll(Scroll::Top);
 
                 // Move vi mode cursor.
@@ -365,6 +541,7 @@ impl<T: EventListener> Execute<T> for Action {
                 ctx.mark_dirty();
             },
             Action::ScrollToBottom => {
+                Self::record_jump(ctx);
                 ctx.scroll(Scroll::Bottom);
 
                 // Move vi mode cursor.
@@ -422,8 +599,33 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         Self { ctx, _phantom: Default::default() }
     }
 
+    /// Run the action of a trigger matching new terminal output.
+    pub fn trigger(&mut self, action: TriggeredAction) {
+        match action {
+            TriggeredAction::Action(index) => {
+                let trigger = self.ctx.config().triggers.get(index);
+                if let Some(TriggerAction::Action(action)) = trigger.map(|t| t.action.clone()) {
+                    action.execute(&mut self.ctx);
+                }
+            },
+            TriggeredAction::Command(program) => {
+                self.ctx.spawn_daemon(program.program(), program.args());
+            },
+            TriggeredAction::Write(text) => self.ctx.write_to_pty(text.into_bytes()),
+            TriggeredAction::Notify(text) => match trigger::notification_command(&text) {
+                Some(program) => self.ctx.spawn_daemon(program.program(), program.args()),
+                None => warn!("Desktop notifications are not supported on this platform"),
+            },
+        }
+    }
+
     #[inline]
     pub fn mouse_moved(&mut self, position: PhysicalPosition<f64>) {
+        // Ignore the mouse while a paste is waiting for confirmation.
+        if self.ctx.paste_pending() {
+            return;
+        }
+
         let size_info = self.ctx.size_info();
 
         let (x, y) = position.into();
@@ -694,6 +896,10 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
     }
 
     pub fn mouse_wheel_input(&mut self, delta: MouseScrollDelta, phase: TouchPhase) {
+        if self.ctx.paste_pending() {
+            return;
+        }
+
         let multiplier = self.ctx.config().scrolling.multiplier;
         match delta {
             MouseScrollDelta::LineDelta(columns, lines) => {
@@ -811,6 +1017,10 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
 
     /// Handle touch input.
     pub fn touch(&mut self, touch: TouchEvent) {
+        if self.ctx.paste_pending() {
+            return;
+        }
+
         match touch.phase {
             TouchPhase::Started => self.on_touch_start(touch),
             TouchPhase::Moved => self.on_touch_motion(touch),
@@ -949,6 +1159,12 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             _ => (),
         }
 
+        // Keep track of the button state, but ignore clicks while a paste is waiting for
+        // confirmation.
+        if self.ctx.paste_pending() {
+            return;
+        }
+
         // Skip normal mouse events if the message bar has been clicked.
         if self.message_bar_cursor_state() == Some(CursorIcon::Pointer)
             && state == ElementState::Pressed
@@ -1003,7 +1219,9 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
 
         for binding in &mouse_bindings {
             // Don't trigger normal bindings in mouse mode unless Shift is pressed.
-            if binding.is_triggered_by(mode, mods, &button) && (check_fallback || !mouse_mode) {
+            let triggered = binding.is_triggered_by(mode, mods, &button)
+                && binding.user_var_matches(self.ctx.terminal().user_vars());
+            if triggered && (check_fallback || !mouse_mode) {
                 binding.action.execute(&mut self.ctx);
                 check_fallback = false;
             }
@@ -1012,7 +1230,9 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         if check_fallback {
             let fallback_mods = mods & !ModifiersState::SHIFT;
             for binding in &mouse_bindings {
-                if binding.is_triggered_by(mode, fallback_mods, &button) {
+                if binding.is_triggered_by(mode, fallback_mods, &button)
+                    && binding.user_var_matches(self.ctx.terminal().user_vars())
+                {
                     binding.action.execute(&mut self.ctx);
                 }
             }
@@ -1127,18 +1347,10 @@ mod tests {
         pub modifiers: Modifiers,
         config: &'a UiConfig,
         inline_search_state: &'a mut InlineSearchState,
+        vi_command_state: &'a mut ViCommandState,
     }
 
     impl<'a, T: EventListener> super::ActionContext<T> for ActionContext<'a, T> {
-        fn search_next(
-            &mut self,
-            _origin: Point,
-            _direction: Direction,
-            _side: Side,
-        ) -> Option<Match> {
-            None
-        }
-
         fn search_direction(&self) -> Direction {
             Direction::Right
         }
@@ -1147,6 +1359,10 @@ mod tests {
             self.inline_search_state
         }
 
+        fn vi_command_state(&mut self) -> &mut ViCommandState {
+            self.vi_command_state
+        }
+
         fn search_active(&self) -> bool {
             false
         }
@@ -1261,6 +1477,7 @@ mod tests {
                 };
 
                 let mut inline_search_state = InlineSearchState::default();
+                let mut vi_command_state = ViCommandState::default();
                 let mut message_buffer = MessageBuffer::default();
 
                 let context = ActionContext {
@@ -1271,6 +1488,7 @@ mod tests {
                     modifiers: Default::default(),
                     message_buffer: &mut message_buffer,
                     inline_search_state: &mut inline_search_state,
+                    vi_command_state: &mut vi_command_state,
                     config: &cfg,
                 };
 
@@ -1443,7 +1661,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_shiftmod_require_shift,
-        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty(), user_var: None },
         triggers: true,
         mode: BindingMode::empty(),
         mods: ModifiersState::SHIFT,
@@ -1451,7 +1669,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_nomod_require_shift,
-        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::SHIFT, action: Action::from("\x1b[1;2D"), mode: BindingMode::empty(), notmode: BindingMode::empty(), user_var: None },
         triggers: false,
         mode: BindingMode::empty(),
         mods: ModifiersState::empty(),
@@ -1459,7 +1677,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_controlmod,
-        binding: Binding { trigger: KEY, mods: ModifiersState::CONTROL, action: Action::from("\x1b[1;5D"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::CONTROL, action: Action::from("\x1b[1;5D"), mode: BindingMode::empty(), notmode: BindingMode::empty(), user_var: None },
         triggers: true,
         mode: BindingMode::empty(),
         mods: ModifiersState::CONTROL,
@@ -1467,7 +1685,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_nomod_require_not_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1b[D"), mode: BindingMode::empty(), notmode: BindingMode::APP_CURSOR },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1b[D"), mode: BindingMode::empty(), notmode: BindingMode::APP_CURSOR, user_var: None },
         triggers: true,
         mode: BindingMode::empty(),
         mods: ModifiersState::empty(),
@@ -1475,7 +1693,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_appcursormode_nomod_require_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty(), user_var: None },
         triggers: true,
         mode: BindingMode::APP_CURSOR,
         mods: ModifiersState::empty(),
@@ -1483,7 +1701,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_nomode_nomod_require_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty(), user_var: None },
         triggers: false,
         mode: BindingMode::empty(),
         mods: ModifiersState::empty(),
@@ -1491,7 +1709,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_appcursormode_appkeypadmode_nomod_require_appcursor,
-        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::empty(), action: Action::from("\x1bOD"), mode: BindingMode::APP_CURSOR, notmode: BindingMode::empty(), user_var: None },
         triggers: true,
         mode: BindingMode::APP_CURSOR | BindingMode::APP_KEYPAD,
         mods: ModifiersState::empty(),
@@ -1499,7 +1717,7 @@ mod tests {
 
     test_process_binding! {
         name: process_binding_fail_with_extra_mods,
-        binding: Binding { trigger: KEY, mods: ModifiersState::SUPER, action: Action::from("arst"), mode: BindingMode::empty(), notmode: BindingMode::empty() },
+        binding: Binding { trigger: KEY, mods: ModifiersState::SUPER, action: Action::from("arst"), mode: BindingMode::empty(), notmode: BindingMode::empty(), user_var: None },
         triggers: false,
         mode: BindingMode::empty(),
         mods: ModifiersState::ALT | ModifiersState::SUPER,
@@ -0,0 +1,500 @@
+//! Vi mode command grammar.
+//!
+//! Vi commands are made up of an optional count, an optional operator and a motion or text object,
+//! like `3w`, `yy` or `2yiw`. Motions and operators are triggered by key bindings, while counts and
+//! text objects are parsed from the raw character input. Text objects without operator replace
+//! the selection, like `viw` in vi.
+//!
+//! Marks are handled the same way, with the name of the mark following `m` or `'` parsed from the
+//! character input. Jumping to a mark can be combined with an operator, like `y'a`. The second
+//! key of `g` commands like `gg` is parsed the same way.
+
+use std::cmp::{max, min};
+use std::fmt::{self, Display, Formatter};
+use std::mem;
+
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Point, Side};
+use alacritty_terminal::selection::{Selection, SelectionType};
+use alacritty_terminal::term::Term;
+use alacritty_terminal::vi_mode::{ViMotion, ViTextObject};
+
+/// Maximum count accepted for a command.
+const MAX_COUNT: usize = 99_999;
+
+/// Operators acting on the text covered by a motion or text object.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViOperator {
+    /// Copy text to the clipboard.
+    Yank,
+}
+
+impl ViOperator {
+    /// Key starting the operator.
+    fn key(self) -> char {
+        match self {
+            Self::Yank => 'y',
+        }
+    }
+}
+
+/// Text a command acts on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViTarget {
+    /// Text between the vi cursor and the target of a motion.
+    Motion(ViMotion),
+
+    /// Text object around the vi cursor.
+    TextObject(ViTextObject),
+
+    /// Lines starting at the vi cursor, used when an operator is repeated like `yy`.
+    Lines,
+
+    /// Line of a mark.
+    Mark(char),
+}
+
+/// Mark commands waiting for the name of the mark.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViMarkAction {
+    /// Set a mark at the vi cursor.
+    Set,
+
+    /// Jump to a mark.
+    Jump,
+}
+
+impl ViMarkAction {
+    /// Key starting the mark command.
+    fn key(self) -> char {
+        match self {
+            Self::Set => 'm',
+            Self::Jump => '\'',
+        }
+    }
+}
+
+/// Completely parsed vi command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ViCommand {
+    /// Operator applied to the target, commands without operator only move the cursor.
+    pub operator: Option<ViOperator>,
+
+    /// Number of times the command is repeated.
+    pub count: usize,
+
+    /// Text the command acts on.
+    pub target: ViTarget,
+}
+
+impl ViCommand {
+    /// Selection of the text covered by the command.
+    ///
+    /// Returns the selection and the point the vi cursor is moved to after the operator is
+    /// applied. The cursor itself is not moved.
+    pub fn selection<T: EventListener>(&self, term: &mut Term<T>) -> Option<(Selection, Point)> {
+        let cursor = term.vi_mode_cursor;
+        let origin = cursor.point;
+
+        match self.target {
+            ViTarget::Motion(motion) => {
+                let target = cursor.motion_count(term, motion, self.count).point;
+                let (start, end) = (min(origin, target), max(origin, target));
+
+                let (ty, end_side) = if motion.is_linewise() {
+                    (SelectionType::Lines, Side::Right)
+                } else if motion.is_inclusive() {
+                    (SelectionType::Simple, Side::Right)
+                } else {
+                    (SelectionType::Simple, Side::Left)
+                };
+
+                let mut selection = Selection::new(ty, start, Side::Left);
+                selection.update(end, end_side);
+                Some((selection, start))
+            },
+            ViTarget::TextObject(object) => {
+                let selection = object.selection(term, origin)?;
+                let start = selection.to_range(term).map_or(origin, |range| range.start);
+                Some((selection, start))
+            },
+            ViTarget::Lines => {
+                let last_line = min(origin.line + (self.count - 1), term.bottommost_line());
+                let mut selection = Selection::new(SelectionType::Lines, origin, Side::Left);
+                selection.update(Point::new(last_line, origin.column), Side::Right);
+                Some((selection, origin))
+            },
+            ViTarget::Mark(name) => {
+                let mark = term.marks().get(name)?;
+                let (start, end) = (min(origin, mark), max(origin, mark));
+                let mut selection = Selection::new(SelectionType::Lines, start, Side::Left);
+                selection.update(end, Side::Right);
+                Some((selection, start))
+            },
+        }
+    }
+}
+
+/// Result of character input into a pending vi command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViInput {
+    /// Character is not part of the command grammar and should be handled by key bindings.
+    Ignored,
+
+    /// Character was consumed by the pending command.
+    Consumed,
+
+    /// Character completed the command.
+    Command(ViCommand),
+
+    /// Character is the name of a mark which should be set.
+    SetMark(char),
+}
+
+/// Pending vi command input.
+#[derive(Debug, Default)]
+pub struct ViCommandState {
+    /// Count typed after the operator, or before the motion without operator.
+    count: Option<usize>,
+
+    /// Pending operator with the count typed before it.
+    operator: Option<(ViOperator, Option<usize>)>,
+
+    /// Text object prefix, `i` for inner and `a` for around objects.
+    object_prefix: Option<char>,
+
+    /// Mark command waiting for the mark's name.
+    mark: Option<ViMarkAction>,
+
+    /// `g` command waiting for its second key.
+    goto: bool,
+
+    /// Last command with an operator, repeated with `.`.
+    last_command: Option<ViCommand>,
+}
+
+impl ViCommandState {
+    /// Check if a command is partially typed.
+    pub fn is_pending(&self) -> bool {
+        self.count.is_some()
+            || self.operator.is_some()
+            || self.object_prefix.is_some()
+            || self.mark.is_some()
+            || self.goto
+    }
+
+    /// Discard the pending command.
+    ///
+    /// Returns `true` if a command was pending.
+    pub fn reset(&mut self) -> bool {
+        let pending = self.is_pending();
+        self.count = None;
+        self.operator = None;
+        self.object_prefix = None;
+        self.mark = None;
+        self.goto = false;
+        pending
+    }
+
+    /// Handle character input.
+    ///
+    /// Text objects are accepted after an operator, or in `visual` mode with an active selection.
+    pub fn input(&mut self, c: char, visual: bool) -> ViInput {
+        match self.mark.take() {
+            Some(ViMarkAction::Set) => {
+                self.reset();
+                return ViInput::SetMark(c);
+            },
+            Some(ViMarkAction::Jump) => return ViInput::Command(self.complete(ViTarget::Mark(c))),
+            None => (),
+        }
+
+        if mem::take(&mut self.goto) {
+            if c == 'g' {
+                return ViInput::Command(self.complete(ViTarget::Motion(ViMotion::Top)));
+            }
+
+            self.reset();
+            return ViInput::Ignored;
+        }
+
+        if let Some(prefix) = self.object_prefix.take() {
+            return match text_object(prefix, c) {
+                Some(object) => ViInput::Command(self.complete(ViTarget::TextObject(object))),
+                None => {
+                    self.reset();
+                    ViInput::Consumed
+                },
+            };
+        }
+
+        // Leading zeroes are left to the key bindings, since `0` is a motion.
+        if let Some(digit) = c.to_digit(10).filter(|&digit| digit != 0 || self.count.is_some()) {
+            let count = self.count.unwrap_or(0).saturating_mul(10) + digit as usize;
+            self.count = Some(min(count, MAX_COUNT));
+            return ViInput::Consumed;
+        }
+
+        if (visual || self.operator.is_some()) && matches!(c, 'i' | 'a') {
+            self.object_prefix = Some(c);
+            return ViInput::Consumed;
+        }
+
+        ViInput::Ignored
+    }
+
+    /// Start an operator.
+    ///
+    /// Returns the command acting on entire lines when the same operator is already pending.
+    pub fn operator(&mut self, operator: ViOperator) -> Option<ViCommand> {
+        match self.operator {
+            Some((pending, _)) if pending == operator => Some(self.complete(ViTarget::Lines)),
+            _ => {
+                self.operator = Some((operator, self.count.take()));
+                self.object_prefix = None;
+                None
+            },
+        }
+    }
+
+    /// Wait for the name of a mark.
+    ///
+    /// Setting a mark cancels the pending operator, while jumping to a mark completes it.
+    pub fn mark(&mut self, action: ViMarkAction) {
+        if action == ViMarkAction::Set {
+            self.reset();
+        }
+
+        self.object_prefix = None;
+        self.mark = Some(action);
+    }
+
+    /// Wait for the second key of a `g` command.
+    pub fn goto(&mut self) {
+        self.object_prefix = None;
+        self.goto = true;
+    }
+
+    /// Complete the pending command with a motion.
+    pub fn motion(&mut self, motion: ViMotion) -> ViCommand {
+        self.complete(ViTarget::Motion(motion))
+    }
+
+    /// Get the last command with an operator.
+    ///
+    /// A pending count replaces the count of the last command.
+    pub fn repeat(&mut self) -> Option<ViCommand> {
+        let count = self.count.take();
+        self.reset();
+
+        let command = self.last_command.as_mut()?;
+        if let Some(count) = count {
+            command.count = count;
+        }
+
+        Some(*command)
+    }
+
+    /// Complete the pending command with its target.
+    fn complete(&mut self, target: ViTarget) -> ViCommand {
+        let (operator, operator_count) = match self.operator.take() {
+            Some((operator, count)) => (Some(operator), count),
+            None => (None, None),
+        };
+
+        let count = operator_count.unwrap_or(1).saturating_mul(self.count.take().unwrap_or(1));
+        let command = ViCommand { operator, count: min(count, MAX_COUNT), target };
+        self.object_prefix = None;
+
+        if operator.is_some() {
+            self.last_command = Some(command);
+        }
+
+        command
+    }
+}
+
+impl Display for ViCommandState {
+    /// Format the pending command like it was typed.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some((operator, count)) = self.operator {
+            if let Some(count) = count {
+                write!(f, "{count}")?;
+            }
+            write!(f, "{}", operator.key())?;
+        }
+
+        if let Some(count) = self.count {
+            write!(f, "{count}")?;
+        }
+
+        if let Some(prefix) = self.object_prefix {
+            write!(f, "{prefix}")?;
+        }
+
+        if let Some(mark) = self.mark {
+            write!(f, "{}", mark.key())?;
+        }
+
+        if self.goto {
+            write!(f, "g")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Text object for the keys following an operator.
+fn text_object(prefix: char, c: char) -> Option<ViTextObject> {
+    let inner = prefix == 'i';
+    let object = match c {
+        'w' if inner => ViTextObject::InnerSemanticWord,
+        'w' => ViTextObject::AroundSemanticWord,
+        'W' if inner => ViTextObject::InnerWord,
+        'W' => ViTextObject::AroundWord,
+        'p' if inner => ViTextObject::InnerParagraph,
+        'p' => ViTextObject::AroundParagraph,
+        '"' | '\'' | '`' if inner => ViTextObject::InnerQuote(c),
+        '"' | '\'' | '`' => ViTextObject::AroundQuote(c),
+        _ => {
+            let bracket = match c {
+                '(' | ')' | 'b' => '(',
+                '[' | ']' => '[',
+                '{' | '}' | 'B' => '{',
+                '<' | '>' => '<',
+                _ => return None,
+            };
+
+            if inner {
+                ViTextObject::InnerBracket(bracket)
+            } else {
+                ViTextObject::AroundBracket(bracket)
+            }
+        },
+    };
+    Some(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alacritty_terminal::index::{Column, Line};
+    use alacritty_terminal::term::test::mock_term;
+    use alacritty_terminal::term::TermMode;
+
+    fn input(state: &mut ViCommandState, text: &str) -> ViInput {
+        text.chars().map(|c| state.input(c, false)).last().unwrap()
+    }
+
+    #[test]
+    fn parse_commands() {
+        let mut state = ViCommandState::default();
+
+        // Counts multiply across the operator, like `2y3j`.
+        assert_eq!(input(&mut state, "2"), ViInput::Consumed);
+        assert_eq!(state.operator(ViOperator::Yank), None);
+        assert_eq!(input(&mut state, "3"), ViInput::Consumed);
+        assert_eq!(state.to_string(), "2y3");
+        let yank = ViCommand {
+            operator: Some(ViOperator::Yank),
+            count: 6,
+            target: ViTarget::Motion(ViMotion::Down),
+        };
+        assert_eq!(state.motion(ViMotion::Down), yank);
+        assert!(!state.is_pending());
+
+        // Leading zeroes are not part of the count.
+        assert_eq!(input(&mut state, "0"), ViInput::Ignored);
+        assert_eq!(input(&mut state, "10"), ViInput::Consumed);
+        let motion =
+            ViCommand { operator: None, count: 10, target: ViTarget::Motion(ViMotion::Up) };
+        assert_eq!(state.motion(ViMotion::Up), motion);
+
+        // Text objects are only parsed after an operator or with a selection.
+        assert_eq!(input(&mut state, "i"), ViInput::Ignored);
+        assert_eq!(state.input('a', true), ViInput::Consumed);
+        let target = ViTarget::TextObject(ViTextObject::AroundBracket('('));
+        let select_object = ViCommand { operator: None, count: 1, target };
+        assert_eq!(state.input(')', true), ViInput::Command(select_object));
+
+        state.operator(ViOperator::Yank);
+        let object = ViTextObject::InnerSemanticWord;
+        let target = ViTarget::TextObject(object);
+        let yank_object = ViCommand { operator: Some(ViOperator::Yank), count: 1, target };
+        assert_eq!(input(&mut state, "iw"), ViInput::Command(yank_object));
+
+        // Repeating uses the last operator, with a new count if one was typed.
+        assert_eq!(state.repeat(), Some(yank_object));
+        assert_eq!(input(&mut state, "4"), ViInput::Consumed);
+        assert_eq!(state.repeat(), Some(ViCommand { count: 4, ..yank_object }));
+        assert_eq!(state.repeat(), Some(ViCommand { count: 4, ..yank_object }));
+
+        state.operator(ViOperator::Yank);
+        let lines =
+            ViCommand { operator: Some(ViOperator::Yank), count: 1, target: ViTarget::Lines };
+        assert_eq!(state.operator(ViOperator::Yank), Some(lines));
+
+        // `gg` moves to the top, other keys cancel the pending command.
+        state.operator(ViOperator::Yank);
+        state.goto();
+        assert_eq!(state.to_string(), "yg");
+        let top = ViTarget::Motion(ViMotion::Top);
+        let yank_top = ViCommand { operator: Some(ViOperator::Yank), count: 1, target: top };
+        assert_eq!(input(&mut state, "g"), ViInput::Command(yank_top));
+        state.goto();
+        assert_eq!(input(&mut state, "j"), ViInput::Ignored);
+        assert!(!state.is_pending());
+
+        // Mark names are captured after mark commands.
+        state.mark(ViMarkAction::Set);
+        assert_eq!(state.to_string(), "m");
+        assert_eq!(input(&mut state, "a"), ViInput::SetMark('a'));
+        state.operator(ViOperator::Yank);
+        state.mark(ViMarkAction::Jump);
+        assert_eq!(state.to_string(), "y'");
+        let target = ViTarget::Mark('a');
+        let yank_mark = ViCommand { operator: Some(ViOperator::Yank), count: 1, target };
+        assert_eq!(input(&mut state, "a"), ViInput::Command(yank_mark));
+    }
+
+    #[test]
+    fn command_selection() {
+        let mut term = mock_term("one two three\r\nfour\r\nfive");
+        term.toggle_vi_mode();
+        term.vi_mode_cursor.point = Point::new(Line(0), Column(5));
+
+        let selection_text = |term: &mut Term<_>, target, count| {
+            let command = ViCommand { operator: Some(ViOperator::Yank), count, target };
+            let (selection, cursor) = command.selection(term).unwrap();
+            term.selection = Some(selection);
+            (term.selection_to_string().unwrap(), cursor)
+        };
+
+        let (text, cursor) = selection_text(&mut term, ViTarget::Motion(ViMotion::Last), 1);
+        assert_eq!(text, "wo three");
+        assert_eq!(cursor, Point::new(Line(0), Column(5)));
+
+        let (text, cursor) = selection_text(&mut term, ViTarget::Motion(ViMotion::SemanticLeft), 2);
+        assert_eq!(text, "one t");
+        assert_eq!(cursor, Point::new(Line(0), Column(0)));
+
+        let (text, _) = selection_text(&mut term, ViTarget::Lines, 2);
+        assert_eq!(text, "one two three\nfour\n");
+
+        let object = ViTextObject::AroundSemanticWord;
+        let (text, cursor) = selection_text(&mut term, ViTarget::TextObject(object), 1);
+        assert_eq!(text, "two ");
+        assert_eq!(cursor, Point::new(Line(0), Column(4)));
+
+        term.marks_mut().set('a', Point::new(Line(2), Column(1)));
+        let (text, cursor) = selection_text(&mut term, ViTarget::Mark('a'), 1);
+        assert_eq!(text, "one two three\nfour\nfive\n");
+        assert_eq!(cursor, Point::new(Line(0), Column(5)));
+
+        // Cursor is not moved by computing the selection.
+        assert!(term.mode().contains(TermMode::VI));
+        assert_eq!(term.vi_mode_cursor.point, Point::new(Line(0), Column(5)));
+    }
+}
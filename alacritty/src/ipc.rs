@@ -1,12 +1,16 @@
 //! Alacritty socket IPC.
 
 use std::ffi::OsStr;
-use std::io::{BufRead, BufReader, Error as IoError, ErrorKind, Result as IoResult, Write};
+use std::io::{BufRead, BufReader, Error as IoError, ErrorKind, Read, Result as IoResult, Write};
+use std::net::Shutdown;
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
 use std::{env, fs, process};
 
 use log::warn;
+use serde::{Deserialize, Serialize};
 use winit::event_loop::EventLoopProxy;
 use winit::window::WindowId;
 
@@ -18,6 +22,22 @@ use crate::event::{Event, EventType};
 /// Environment variable name for the IPC socket path.
 const ALACRITTY_SOCKET_ENV: &str = "ALACRITTY_SOCKET";
 
+/// Maximum time to wait for a window to reply to an IPC query.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Reply to the `get-user-var` IPC subcommand.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum UserVarReply {
+    /// Value of the user variable.
+    Value(String),
+
+    /// The user variable is not set in the window.
+    NotSet,
+
+    /// No window with the requested ID exists.
+    WindowNotFound,
+}
+
 /// Create an IPC socket.
 pub fn spawn_ipc_socket(options: &Options, event_proxy: EventLoopProxy<Event>) -> Option<PathBuf> {
     // Create the IPC socket and export its path as env variable if necessary.
@@ -71,6 +91,32 @@ pub fn spawn_ipc_socket(options: &Options, event_proxy: EventLoopProxy<Event>) -
                     let event = Event::new(EventType::IpcConfig(ipc_config), window_id);
                     let _ = event_proxy.send_event(event);
                 },
+                SocketMessage::Export(ipc_export) => {
+                    let window_id = WindowId::from(ipc_export.window_id);
+                    let event = Event::new(EventType::IpcExport(ipc_export), window_id);
+                    let _ = event_proxy.send_event(event);
+                },
+                SocketMessage::GetUserVar(ipc_get_user_var) => {
+                    let window_id = WindowId::from(ipc_get_user_var.window_id);
+                    let (reply_tx, reply_rx) = mpsc::channel();
+                    let event =
+                        Event::new(EventType::IpcGetUserVar(ipc_get_user_var, reply_tx), window_id);
+                    if event_proxy.send_event(event).is_err() {
+                        continue;
+                    }
+
+                    let reply = match reply_rx.recv_timeout(REPLY_TIMEOUT) {
+                        Ok(Some(value)) => UserVarReply::Value(value),
+                        Ok(None) => UserVarReply::NotSet,
+                        // Unknown windows drop the reply channel without responding.
+                        Err(RecvTimeoutError::Disconnected) => UserVarReply::WindowNotFound,
+                        Err(RecvTimeoutError::Timeout) => continue,
+                    };
+
+                    if let Ok(reply) = serde_json::to_string(&reply) {
+                        let _ = stream.get_mut().write_all(reply.as_bytes());
+                    }
+                },
             }
         }
     });
@@ -79,14 +125,20 @@ pub fn spawn_ipc_socket(options: &Options, event_proxy: EventLoopProxy<Event>) -
 }
 
 /// Send a message to the active Alacritty socket.
-pub fn send_message(socket: Option<PathBuf>, message: SocketMessage) -> IoResult<()> {
+///
+/// Returns the reply to the message, which is empty for messages without a response.
+pub fn send_message(socket: Option<PathBuf>, message: SocketMessage) -> IoResult<String> {
     let mut socket = find_socket(socket)?;
 
     let message = serde_json::to_string(&message)?;
     socket.write_all(message[..].as_bytes())?;
     let _ = socket.flush();
+    socket.shutdown(Shutdown::Write)?;
+
+    let mut reply = String::new();
+    socket.read_to_string(&mut reply)?;
 
-    Ok(())
+    Ok(reply)
 }
 
 /// Directory for the IPC socket file.
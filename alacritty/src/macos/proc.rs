@@ -68,12 +68,26 @@ pub fn cwd(pid: c_int) -> Result<PathBuf, Error> {
     Ok(CString::from(c_str).into_string().map(PathBuf::from)?)
 }
 
+/// Get the name of a process.
+pub fn name(pid: c_int) -> io::Result<String> {
+    let mut buffer = [0u8; sys::PROC_PIDPATHINFO_MAXSIZE];
+    let buffer_ptr = buffer.as_mut_ptr() as *mut c_void;
+
+    let len = unsafe { sys::proc_name(pid, buffer_ptr, buffer.len() as u32) };
+    if len <= 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(String::from_utf8_lossy(&buffer[..len as usize]).into_owned())
+}
+
 /// Bindings for libproc.
 #[allow(non_camel_case_types)]
 mod sys {
     use std::os::raw::{c_char, c_int, c_longlong, c_void};
 
     pub const PROC_PIDVNODEPATHINFO: c_int = 9;
+    pub const PROC_PIDPATHINFO_MAXSIZE: usize = 4096;
 
     type gid_t = c_int;
     type off_t = c_longlong;
@@ -143,6 +157,8 @@ mod sys {
             buffer: *mut c_void,
             buffersize: c_int,
         ) -> c_int;
+
+        pub fn proc_name(pid: c_int, buffer: *mut c_void, buffersize: u32) -> c_int;
     }
 }
 
@@ -156,4 +172,11 @@ mod tests {
     fn cwd_matches_current_dir() {
         assert_eq!(cwd(process::id() as i32).ok(), env::current_dir().ok());
     }
+
+    #[test]
+    fn name_matches_current_exe() {
+        let exe = env::current_exe().unwrap();
+        let name = name(process::id() as i32).unwrap();
+        assert!(exe.file_name().unwrap().to_string_lossy().starts_with(&name));
+    }
 }
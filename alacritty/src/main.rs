@@ -46,6 +46,7 @@ mod panic;
 mod renderer;
 mod scheduler;
 mod string;
+mod trigger;
 mod window_context;
 
 mod gl {
@@ -54,10 +55,12 @@ mod gl {
 }
 
 #[cfg(unix)]
-use crate::cli::MessageOptions;
+use crate::cli::{MessageOptions, SocketMessage};
 use crate::cli::{Options, Subcommands};
 use crate::config::{monitor, UiConfig};
 use crate::event::{Event, Processor};
+#[cfg(unix)]
+use crate::ipc::UserVarReply;
 #[cfg(target_os = "macos")]
 use crate::macos::locale;
 
@@ -88,8 +91,34 @@ fn main() -> Result<(), Box<dyn Error>> {
 
 /// `msg` subcommand entrypoint.
 #[cfg(unix)]
-fn msg(options: MessageOptions) -> Result<(), Box<dyn Error>> {
-    ipc::send_message(options.socket, options.message).map_err(|err| err.into())
+fn msg(mut options: MessageOptions) -> Result<(), Box<dyn Error>> {
+    // Resolve export paths relative to the client, since the socket's CWD might be different.
+    if let SocketMessage::Export(ipc_export) = &mut options.message {
+        if ipc_export.path.is_relative() {
+            ipc_export.path = env::current_dir()?.join(&ipc_export.path);
+        }
+    }
+
+    let user_var = match &options.message {
+        SocketMessage::GetUserVar(ipc_get_user_var) => Some(ipc_get_user_var.name.clone()),
+        _ => None,
+    };
+
+    let reply = ipc::send_message(options.socket, options.message)?;
+
+    if let Some(name) = user_var {
+        if reply.is_empty() {
+            return Err("no reply from Alacritty".into());
+        }
+
+        match serde_json::from_str(&reply)? {
+            UserVarReply::Value(value) => println!("{value}"),
+            UserVarReply::NotSet => return Err(format!("user variable {name:?} is not set").into()),
+            UserVarReply::WindowNotFound => return Err("window not found".into()),
+        }
+    }
+
+    Ok(())
 }
 
 /// Temporary files stored for Alacritty.
@@ -187,7 +216,7 @@ fn alacritty(mut options: Options) -> Result<(), Box<dyn Error>> {
 
     // Event processor.
     let window_options = options.window_options.clone();
-    let mut processor = Processor::new(config, options, &window_event_loop);
+    let mut processor = Processor::new(config, options, &window_event_loop)?;
 
     // Start event loop and block until shutdown.
     let result = processor.run(window_event_loop, window_options);
@@ -27,6 +27,9 @@ pub enum MessageType {
 
     /// A message represents a warning.
     Warning,
+
+    /// A message is purely informational.
+    Info,
 }
 
 impl Message {
@@ -176,6 +179,12 @@ impl MessageBuffer {
             .collect();
     }
 
+    /// Check whether a message with a specific target is queued.
+    #[inline]
+    pub fn has_target(&self, target: &str) -> bool {
+        self.messages.iter().any(|m| m.target().map(String::as_str) == Some(target))
+    }
+
     /// Add a new message to the queue.
     #[inline]
     pub fn push(&mut self, message: Message) {
@@ -317,7 +326,9 @@ mod tests {
             message_buffer.push(msg);
         }
 
+        assert!(message_buffer.has_target("target"));
         message_buffer.remove_target("target");
+        assert!(!message_buffer.has_target("target"));
 
         // Count number of messages.
         let mut num_messages = 0;
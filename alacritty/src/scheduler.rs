@@ -26,9 +26,15 @@ impl TimerId {
 pub enum Topic {
     SelectionScrolling,
     DelayedSearch,
+    SearchCount,
     BlinkCursor,
     BlinkTimeout,
     Frame,
+    SecureInput,
+    ForegroundProcess,
+    EscapeBlocked,
+    ClipboardHistory,
+    FuzzySearch,
 }
 
 /// Event scheduled to be emitted at a specific time.
@@ -0,0 +1,192 @@
+//! Actions triggered by regex matches in new terminal output.
+
+use std::time::Instant;
+
+use regex_automata::util::captures::Captures;
+
+use crate::config::trigger::{Trigger, TriggerAction};
+use crate::config::ui_config::Program;
+
+/// Title of desktop notifications.
+const NOTIFICATION_TITLE: &str = "Alacritty";
+
+/// Action of a matching trigger, with capture group references replaced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TriggeredAction {
+    /// Built-in action of the trigger with this index.
+    Action(usize),
+
+    /// Command which is spawned.
+    Command(Program),
+
+    /// Text written to the PTY.
+    Write(String),
+
+    /// Text shown as a desktop notification.
+    Notify(String),
+}
+
+/// Runtime state of all triggers.
+#[derive(Default, Debug)]
+pub struct TriggerState {
+    /// Last time each trigger was run.
+    last_runs: Vec<Option<Instant>>,
+}
+
+impl TriggerState {
+    /// Forget when triggers were last run.
+    pub fn reset(&mut self) {
+        self.last_runs.clear();
+    }
+
+    /// Actions of all triggers matching a line which are not rate limited.
+    ///
+    /// Capture group references in the actions are replaced with the matched text. The program
+    /// of commands is never modified, captures are only passed as separate arguments.
+    pub fn matches(
+        &mut self,
+        triggers: &[Trigger],
+        line: &str,
+        now: Instant,
+    ) -> Vec<TriggeredAction> {
+        self.last_runs.resize(triggers.len(), None);
+
+        let mut actions = Vec::new();
+        for (index, (trigger, last_run)) in triggers.iter().zip(&mut self.last_runs).enumerate() {
+            if last_run.is_some_and(|last_run| now.duration_since(last_run) < trigger.rate_limit())
+            {
+                continue;
+            }
+
+            let regex = trigger.regex.regex();
+            let mut captures = regex.create_captures();
+            regex.captures(line, &mut captures);
+            if !captures.is_match() {
+                continue;
+            }
+
+            *last_run = Some(now);
+            let interpolate = |text: &str| captures.interpolate_string(line, text);
+            actions.push(match &trigger.action {
+                TriggerAction::Action(_) => TriggeredAction::Action(index),
+                TriggerAction::Command(program) => TriggeredAction::Command(Program::WithArgs {
+                    program: program.program().to_owned(),
+                    args: program
+                        .args()
+                        .iter()
+                        .map(|arg| capture_argument(&captures, line, arg).unwrap_or(arg.clone()))
+                        .collect(),
+                }),
+                TriggerAction::Write(text) => TriggeredAction::Write(interpolate(text)),
+                TriggerAction::Notify(text) => TriggeredAction::Notify(interpolate(text)),
+            });
+        }
+        actions
+    }
+}
+
+/// Captured text for an argument consisting of only a capture group reference.
+///
+/// Both `$1` and `${name}` are supported, missing groups are replaced with an empty string.
+fn capture_argument(captures: &Captures, line: &str, arg: &str) -> Option<String> {
+    let reference = arg.strip_prefix('$')?;
+    let name = reference.strip_prefix('{').and_then(|name| name.strip_suffix('}'));
+    let name = name.unwrap_or(reference);
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+
+    let span = match name.parse::<usize>() {
+        Ok(index) => captures.get_group(index),
+        Err(_) => captures.get_group_by_name(name),
+    };
+    Some(span.map(|span| line[span.range()].to_owned()).unwrap_or_default())
+}
+
+/// Command showing a desktop notification.
+#[cfg(not(any(target_os = "macos", windows)))]
+pub fn notification_command(text: &str) -> Option<Program> {
+    let args = vec![String::from("--app-name"), NOTIFICATION_TITLE.into(), text.into()];
+    Some(Program::WithArgs { program: String::from("notify-send"), args })
+}
+
+/// Command showing a desktop notification.
+#[cfg(target_os = "macos")]
+pub fn notification_command(text: &str) -> Option<Program> {
+    let quote = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+    let script =
+        format!("display notification \"{}\" with title \"{}\"", quote(text), NOTIFICATION_TITLE);
+    let args = vec![String::from("-e"), script];
+    Some(Program::WithArgs { program: String::from("osascript"), args })
+}
+
+/// Command showing a desktop notification.
+#[cfg(windows)]
+pub fn notification_command(_text: &str) -> Option<Program> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    fn trigger(config: &str) -> Trigger {
+        toml::from_str(config).unwrap()
+    }
+
+    #[test]
+    fn expand_captures() {
+        let triggers = [
+            trigger("regex = 'Deployment (\\w+) complete'\nnotify = 'Deployed $1'"),
+            trigger("regex = '(?<id>REQ-\\d+)'\ncommand = { program = 'open', args = ['${id}'] }"),
+            trigger("regex = 'FAILED'\naction = 'Bell'"),
+        ];
+
+        let mut state = TriggerState::default();
+        let now = Instant::now();
+        assert_eq!(state.matches(&triggers, "Deployment api complete", now), vec![
+            TriggeredAction::Notify(String::from("Deployed api"))
+        ]);
+
+        let actions = state.matches(&triggers, "REQ-42 FAILED", now);
+        let program = Program::WithArgs { program: "open".into(), args: vec!["REQ-42".into()] };
+        assert_eq!(actions, vec![TriggeredAction::Command(program), TriggeredAction::Action(2)]);
+    }
+
+    #[test]
+    fn command_captures() {
+        let triggers = [trigger(
+            "regex = '(\\S+) (?<file>\\S+)'\ncommand = { program = '$1', args = ['-o', '$file', \
+             'x$2', '${missing}'] }",
+        )];
+
+        let mut state = TriggerState::default();
+        let actions = state.matches(&triggers, "sh ;rm", Instant::now());
+        let args = vec!["-o".into(), ";rm".into(), "x$2".into(), String::new()];
+        let program = Program::WithArgs { program: "$1".into(), args };
+        assert_eq!(actions, vec![TriggeredAction::Command(program)]);
+    }
+
+    #[test]
+    fn unknown_fields() {
+        assert!(toml::from_str::<Trigger>("regex = 'x'").is_err());
+        assert!(toml::from_str::<Trigger>("regex = 'x'\nwrite = 'a'\nnotify = 'b'").is_err());
+        assert!(toml::from_str::<Trigger>("regex = 'x'\nwrite = 'a'\ncolor = 'red'").is_ok());
+    }
+
+    #[test]
+    fn rate_limit() {
+        let triggers = [trigger("regex = 'FAILED'\nwrite = \"retry\\n\"\nrate_limit = 100")];
+
+        let mut state = TriggerState::default();
+        let now = Instant::now();
+        assert_eq!(state.matches(&triggers, "BUILD FAILED", now).len(), 1);
+        assert!(state.matches(&triggers, "BUILD FAILED", now).is_empty());
+        assert!(state.matches(&triggers, "ok", now + Duration::from_millis(200)).is_empty());
+
+        let actions = state.matches(&triggers, "FAILED", now + Duration::from_millis(100));
+        assert_eq!(actions, vec![TriggeredAction::Write(String::from("retry\n"))]);
+    }
+}
@@ -1,5 +1,6 @@
 //! Terminal window context.
 
+use std::collections::VecDeque;
 use std::error::Error;
 use std::fs::File;
 use std::io::Write;
@@ -8,41 +9,62 @@ use std::mem;
 use std::os::unix::io::{AsRawFd, RawFd};
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use glutin::config::GetGlConfig;
 use glutin::display::GetGlDisplay;
 #[cfg(all(feature = "x11", not(any(target_os = "macos", windows))))]
 use glutin::platform::x11::X11GlConfigExt;
-use log::info;
+use log::{debug, error, info, warn};
 use raw_window_handle::HasRawDisplayHandle;
 use serde_json as json;
 use winit::event::{Event as WinitEvent, Modifiers, WindowEvent};
 use winit::event_loop::{EventLoopProxy, EventLoopWindowTarget};
 use winit::window::WindowId;
 
-use alacritty_terminal::event::Event as TerminalEvent;
-use alacritty_terminal::event_loop::{EventLoop as PtyEventLoop, Msg, Notifier};
+use alacritty_terminal::event::{Event as TerminalEvent, OnResize, WindowSize};
+use alacritty_terminal::event_loop::session_log::SessionLog;
+use alacritty_terminal::event_loop::{
+    EventLoop as PtyEventLoop, EventLoopSender, Msg, Notifier, Reactor,
+};
 use alacritty_terminal::grid::{Dimensions, Scroll};
 use alacritty_terminal::index::Direction;
 use alacritty_terminal::sync::FairMutex;
 use alacritty_terminal::term::test::TermSize;
 use alacritty_terminal::term::{Term, TermMode};
-use alacritty_terminal::tty;
+#[cfg(not(windows))]
+use alacritty_terminal::tty::stream::Stream;
+#[cfg(not(windows))]
+use alacritty_terminal::tty::LocalFlags;
+use alacritty_terminal::tty::{self, EventedPty, ExitStatus};
 
-use crate::cli::{ParsedOptions, WindowOptions};
+use crate::cli::{ParsedOptions, TerminalOptions, WindowOptions};
 use crate::clipboard::Clipboard;
+use crate::config::terminal::OnExit;
 use crate::config::UiConfig;
 use crate::display::window::Window;
 use crate::display::Display;
 use crate::event::{
-    ActionContext, Event, EventProxy, InlineSearchState, Mouse, SearchState, TouchPurpose,
+    ActionContext, EscapePolicyState, Event, EventProxy, EventType, InlineSearchState, Mouse,
+    SearchState, TouchPurpose, SEARCH_COUNT_DELAY,
 };
+use crate::input::vi::ViCommandState;
 #[cfg(unix)]
 use crate::logging::LOG_TARGET_IPC_CONFIG;
-use crate::message_bar::MessageBuffer;
-use crate::scheduler::Scheduler;
+use crate::message_bar::{Message, MessageBuffer, MessageType};
+use crate::scheduler::{Scheduler, TimerId, Topic};
+use crate::trigger::{TriggerState, TriggeredAction};
 use crate::{input, renderer};
 
+/// Minimum runtime of a shell before it is restarted automatically.
+const MIN_RESTART_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Message bar target of the shell's exit status.
+const EXIT_MESSAGE_TARGET: &str = "alacritty_shell_exit";
+
+/// Interval for checking if the shell is prompting for a password.
+const SECURE_INPUT_INTERVAL: Duration = Duration::from_millis(250);
+
 /// Event context for one individual Alacritty window.
 pub struct WindowContext {
     pub message_buffer: MessageBuffer,
@@ -53,7 +75,11 @@ pub struct WindowContext {
     cursor_blink_timed_out: bool,
     modifiers: Modifiers,
     inline_search_state: InlineSearchState,
+    vi_command_state: ViCommandState,
     search_state: SearchState,
+    escape_policy: EscapePolicyState,
+    triggers: TriggerState,
+    completed_lines: VecDeque<String>,
     notifier: Notifier,
     mouse: Mouse,
     touch: TouchPurpose,
@@ -65,12 +91,17 @@ pub struct WindowContext {
     shell_pid: u32,
     window_config: ParsedOptions,
     config: Rc<UiConfig>,
+    terminal_options: TerminalOptions,
+    event_proxy: EventProxy,
+    /// Time the shell was spawned, `None` for backends which can't be restarted.
+    shell_start: Option<Instant>,
 }
 
 impl WindowContext {
     /// Create initial window context that does bootstrapping the graphics API we're going to use.
     pub fn initial(
         event_loop: &EventLoopWindowTarget<Event>,
+        reactor: &Reactor,
         proxy: EventLoopProxy<Event>,
         config: Rc<UiConfig>,
         options: WindowOptions,
@@ -114,13 +145,14 @@ impl WindowContext {
 
         let display = Display::new(window, gl_context, &config, false)?;
 
-        Self::new(display, config, options, proxy)
+        Self::new(display, reactor, config, options, proxy)
     }
 
     /// Create additional context with the graphics platform other windows are using.
     pub fn additional(
         &self,
         event_loop: &EventLoopWindowTarget<Event>,
+        reactor: &Reactor,
         proxy: EventLoopProxy<Event>,
         config: Rc<UiConfig>,
         options: WindowOptions,
@@ -161,7 +193,7 @@ impl WindowContext {
 
         let display = Display::new(window, gl_context, &config, tabbed)?;
 
-        let mut window_context = Self::new(display, config, options, proxy)?;
+        let mut window_context = Self::new(display, reactor, config, options, proxy)?;
 
         // Set the config overrides at startup.
         //
@@ -173,14 +205,12 @@ impl WindowContext {
 
     /// Create a new terminal window context.
     fn new(
-        display: Display,
+        mut display: Display,
+        reactor: &Reactor,
         config: Rc<UiConfig>,
         options: WindowOptions,
         proxy: EventLoopProxy<Event>,
     ) -> Result<Self, Box<dyn Error>> {
-        let mut pty_config = config.pty_config();
-        options.terminal_options.override_pty_config(&mut pty_config);
-
         let preserve_title = options.window_identity.title.is_some();
 
         info!(
@@ -190,53 +220,88 @@ impl WindowContext {
         );
 
         let event_proxy = EventProxy::new(proxy, display.window.id());
+        let window_id = display.window.id().into();
 
         // Create the terminal.
         //
         // This object contains all of the state about what's being displayed. It's
         // wrapped in a clonable mutex since both the I/O loop and display need to
         // access it.
-        let terminal = Term::new(config.term_options(), &display.size_info, event_proxy.clone());
+        let mut terminal =
+            Term::new(config.term_options(), &display.size_info, event_proxy.clone());
+        let escape_policy = EscapePolicyState::default();
+        escape_policy.apply(&config.terminal.policy, &mut terminal);
         let terminal = Arc::new(FairMutex::new(terminal));
 
-        // Create the PTY.
-        //
-        // The PTY forks a process to run the shell on the slave side of the
-        // pseudoterminal. A file descriptor for the master side is retained for
-        // reading/writing to the shell.
-        let pty = tty::new(&pty_config, display.size_info.into(), display.window.id().into())?;
-
-        #[cfg(not(windows))]
-        let master_fd = pty.file().as_raw_fd();
+        // Attach to an existing stream instead of spawning a shell, if requested.
         #[cfg(not(windows))]
-        let shell_pid = pty.child().id();
+        let stream = if let Some(serial) = options.terminal_options.serial(&config) {
+            let stream = tty::serial::new(&serial).map_err(|err| {
+                format!("Failed to open serial port {}: {}", serial.path.display(), err)
+            })?;
+            Some(stream)
+        } else if let Some(target) = &options.terminal_options.attach {
+            let stream = Stream::attach(target)
+                .map_err(|err| format!("Failed to attach to {}: {}", target, err))?;
+            Some(stream)
+        } else {
+            None
+        };
 
-        // Create the pseudoterminal I/O loop.
-        //
-        // PTY I/O is ran on another thread as to not occupy cycles used by the
-        // renderer and input processing. Note that access to the terminal state is
-        // synchronized since the I/O loop updates the state, and the display
-        // consumes it periodically.
-        let event_loop = PtyEventLoop::new(
-            Arc::clone(&terminal),
-            event_proxy.clone(),
-            pty,
-            pty_config.hold,
-            config.debug.ref_test,
-        )?;
+        let window_size = display.size_info.into();
+        let terminal_options = options.terminal_options;
 
-        // The event loop channel allows write requests from the event processor
-        // to be sent to the pty loop and ultimately written to the pty.
-        let loop_tx = event_loop.channel();
+        #[cfg(not(windows))]
+        let (loop_tx, master_fd, shell_pid, shell_start) = match stream {
+            Some(stream) => {
+                let master_fd = stream.file().as_raw_fd();
+                // Without child process, fall back to Alacritty's own working directory.
+                let shell_pid = std::process::id();
+
+                let log = open_session_log(&config, &terminal_options, window_id, shell_pid)?;
+                let loop_tx =
+                    spawn_io_loop(reactor, &terminal, &event_proxy, stream, log, &config)?;
+                (loop_tx, master_fd, shell_pid, None)
+            },
+            None => {
+                let shell = spawn_shell(
+                    reactor,
+                    &terminal,
+                    &event_proxy,
+                    &config,
+                    &terminal_options,
+                    window_size,
+                    window_id,
+                )?;
+                (shell.loop_tx, shell.master_fd, shell.pid, Some(Instant::now()))
+            },
+        };
 
-        // Kick off the I/O thread.
-        let _io_thread = event_loop.spawn();
+        #[cfg(windows)]
+        let (loop_tx, shell_start) = spawn_shell(
+            reactor,
+            &terminal,
+            &event_proxy,
+            &config,
+            &terminal_options,
+            window_size,
+            window_id,
+        )
+        .map(|shell| (shell.loop_tx, Some(Instant::now())))?;
 
         // Start cursor blinking, in case `Focused` isn't sent on startup.
         if config.cursor.style().blinking {
             event_proxy.send_event(TerminalEvent::CursorBlinkingChange.into());
         }
 
+        // Apply the title template and start watching the foreground process.
+        if !preserve_title {
+            display.update_title(&config, terminal.lock().user_vars());
+        }
+        if config.window.title_tracks_process() {
+            event_proxy.send_event(EventType::PollForegroundProcess);
+        }
+
         // Create context for the Alacritty window.
         Ok(WindowContext {
             preserve_title,
@@ -248,11 +313,18 @@ impl WindowContext {
             shell_pid,
             config,
             notifier: Notifier(loop_tx),
+            shell_start,
+            terminal_options,
+            event_proxy,
+            escape_policy,
             cursor_blink_timed_out: Default::default(),
             inline_search_state: Default::default(),
+            vi_command_state: Default::default(),
             message_buffer: Default::default(),
             window_config: Default::default(),
             search_state: Default::default(),
+            triggers: Default::default(),
+            completed_lines: Default::default(),
             event_queue: Default::default(),
             modifiers: Default::default(),
             occluded: Default::default(),
@@ -270,7 +342,11 @@ impl WindowContext {
         self.config = self.window_config.override_config_rc(self.config.clone());
 
         self.display.update_config(&self.config);
-        self.terminal.lock().set_options(self.config.term_options());
+        self.triggers.reset();
+        let mut terminal = self.terminal.lock();
+        terminal.set_options(self.config.term_options());
+        self.escape_policy.apply(&self.config.terminal.policy, &mut terminal);
+        drop(terminal);
 
         // Reload cursor if its thickness has changed.
         if (old_config.cursor.thickness() - self.config.cursor.thickness()).abs() > f32::EPSILON {
@@ -300,18 +376,14 @@ impl WindowContext {
             self.display.pending_update.dirty = true;
         }
 
-        // Update title on config reload according to the following table.
-        //
-        // │cli │ dynamic_title │ current_title == old_config ││ set_title │
-        // │ Y  │       _       │              _              ││     N     │
-        // │ N  │       Y       │              Y              ││     Y     │
-        // │ N  │       Y       │              N              ││     N     │
-        // │ N  │       N       │              _              ││     Y     │
-        if !self.preserve_title
-            && (!self.config.window.dynamic_title
-                || self.display.window.title() == old_config.window.identity.title)
-        {
-            self.display.window.set_title(self.config.window.identity.title.clone());
+        // Update title on config reload, unless it was set through the CLI.
+        if !self.preserve_title {
+            self.display.update_title(&self.config, self.terminal.lock().user_vars());
+        }
+
+        // Start or stop watching the foreground process for the title.
+        if old_config.window.title_tracks_process() || self.config.window.title_tracks_process() {
+            self.event_proxy.send_event(EventType::PollForegroundProcess);
         }
 
         let opaque = self.config.window_opacity() >= 1.;
@@ -393,6 +465,7 @@ impl WindowContext {
             &self.message_buffer,
             &self.config,
             &mut self.search_state,
+            &self.vi_command_state,
         );
     }
 
@@ -429,7 +502,9 @@ impl WindowContext {
             cursor_blink_timed_out: &mut self.cursor_blink_timed_out,
             message_buffer: &mut self.message_buffer,
             inline_search_state: &mut self.inline_search_state,
+            vi_command_state: &mut self.vi_command_state,
             search_state: &mut self.search_state,
+            escape_policy: &mut self.escape_policy,
             modifiers: &mut self.modifiers,
             notifier: &mut self.notifier,
             display: &mut self.display,
@@ -467,6 +542,11 @@ impl WindowContext {
                 &self.config,
             );
             self.dirty = true;
+
+            // Update size placeholders of the title template.
+            if !self.preserve_title {
+                self.display.update_title(&self.config, terminal.user_vars());
+            }
         }
 
         if self.dirty || self.mouse.hint_highlight_dirty {
@@ -490,6 +570,149 @@ impl WindowContext {
         }
     }
 
+    /// Apply the `terminal.on_exit` policy after the shell exited.
+    pub fn child_exit(&mut self, reactor: &Reactor, status: ExitStatus) {
+        let on_exit =
+            if self.terminal_options.hold { OnExit::Hold } else { self.config.terminal.on_exit };
+
+        match on_exit {
+            OnExit::Close => return self.terminal.lock().exit(),
+            OnExit::HoldOnError if status.success() => return self.terminal.lock().exit(),
+            OnExit::Restart => {
+                // Avoid respawning shells which fail right away in a loop.
+                let ran = self.shell_start.map(|start| start.elapsed());
+                if ran.is_some_and(|ran| ran >= MIN_RESTART_INTERVAL) {
+                    return self.restart_shell(reactor);
+                }
+            },
+            OnExit::Hold | OnExit::HoldOnError => (),
+        }
+
+        let mut text = format!("Process exited with {status}");
+        if on_exit == OnExit::Restart {
+            text.push_str(" right after it was started, not restarting");
+        }
+        let ty = if status.success() { MessageType::Info } else { MessageType::Error };
+
+        let mut message = Message::new(text, ty);
+        message.set_target(EXIT_MESSAGE_TARGET.into());
+        if !self.message_buffer.is_queued(&message) {
+            self.message_buffer.push(message);
+            self.display.pending_update.dirty = true;
+            self.dirty = true;
+        }
+    }
+
+    /// Spawn the shell again, keeping the terminal content.
+    pub fn restart_shell(&mut self, reactor: &Reactor) {
+        // Serial ports and attached streams have no shell which could be restarted.
+        if self.shell_start.is_none() {
+            warn!("Unable to restart shell: terminal is not connected to a shell");
+            return;
+        }
+
+        // Drop modes like the alternate screen or mouse reporting of the previous shell.
+        self.terminal.lock().reset_modes();
+
+        let window_id = self.display.window.id().into();
+        let shell = match spawn_shell(
+            reactor,
+            &self.terminal,
+            &self.event_proxy,
+            &self.config,
+            &self.terminal_options,
+            self.display.size_info.into(),
+            window_id,
+        ) {
+            Ok(shell) => shell,
+            Err(err) => {
+                error!("Unable to restart shell: {err}");
+                return;
+            },
+        };
+
+        // Shutdown the I/O loop of the previous shell.
+        let _ = self.notifier.0.send(Msg::Shutdown);
+
+        self.notifier = Notifier(shell.loop_tx);
+        self.shell_start = Some(Instant::now());
+        #[cfg(not(windows))]
+        {
+            self.master_fd = shell.master_fd;
+            self.shell_pid = shell.pid;
+        }
+
+        // Remove the exit status of the previous shell.
+        self.message_buffer.remove_target(EXIT_MESSAGE_TARGET);
+        self.display.pending_update.dirty = true;
+        self.dirty = true;
+    }
+
+    /// Check if the shell is prompting for a password.
+    ///
+    /// While secure input is enabled, this is repeated periodically, since the terminal mode can
+    /// change without any output.
+    pub fn update_secure_input(&mut self, scheduler: &mut Scheduler) {
+        let enabled = cfg!(not(windows)) && self.config.terminal.secure_input.enabled;
+
+        let timer_id = TimerId::new(Topic::SecureInput, self.id());
+        if !enabled {
+            scheduler.unschedule(timer_id);
+        } else if !scheduler.scheduled(timer_id) {
+            let event = Event::new(EventType::PollSecureInput, self.id());
+            scheduler.schedule(event, SECURE_INPUT_INTERVAL, true, timer_id);
+        }
+
+        #[cfg(not(windows))]
+        let secure_input = enabled
+            && LocalFlags::get(self.master_fd).is_ok_and(|flags| flags.is_password_prompt());
+        #[cfg(windows)]
+        let secure_input = false;
+
+        if secure_input != self.display.secure_input {
+            self.display.secure_input = secure_input;
+            self.dirty = true;
+        }
+    }
+
+    /// Recount search matches after the terminal content changed.
+    pub fn invalidate_search_matches(&mut self, scheduler: &mut Scheduler) {
+        if !self.search_state.invalidate_match_counter() {
+            return;
+        }
+
+        // Delay counting to avoid restarting it for every update while output is flooding in.
+        let timer_id = TimerId::new(Topic::SearchCount, self.id());
+        if !scheduler.scheduled(timer_id) {
+            let event = Event::new(EventType::SearchCount, self.id());
+            scheduler.schedule(event, SEARCH_COUNT_DELAY, false, timer_id);
+        }
+    }
+
+    /// Queue the actions of all triggers matching lines completed since the last update.
+    pub fn run_triggers(&mut self) {
+        if self.config.triggers.is_empty() {
+            return;
+        }
+
+        self.terminal.lock().take_completed_lines(&mut self.completed_lines);
+        let now = Instant::now();
+        for line in &self.completed_lines {
+            for action in self.triggers.matches(&self.config.triggers, line, now) {
+                // Untrusted output must not be able to run commands or write to the PTY.
+                if self.escape_policy.untrusted
+                    && matches!(action, TriggeredAction::Command(_) | TriggeredAction::Write(_))
+                {
+                    debug!("Ignoring trigger in untrusted window: {action:?}");
+                    continue;
+                }
+
+                let event = Event::new(EventType::Trigger(action), self.id());
+                self.event_queue.push(event.into());
+            }
+        }
+    }
+
     /// ID of this terminal context.
     pub fn id(&self) -> WindowId {
         self.display.window.id()
@@ -563,3 +786,104 @@ impl Drop for WindowContext {
         let _ = self.notifier.0.send(Msg::Shutdown);
     }
 }
+
+/// Register a terminal backend with the shared I/O reactor.
+///
+/// PTY I/O is ran on another thread as to not occupy cycles used by the renderer and input
+/// processing. A single thread services the backends of all windows. Note that access to the
+/// terminal state is synchronized since the I/O loop updates the state, and the display consumes
+/// it periodically.
+///
+/// The returned channel allows write requests from the event processor to be sent to the I/O
+/// loop and ultimately written to the backend.
+fn spawn_io_loop<T>(
+    reactor: &Reactor,
+    terminal: &Arc<FairMutex<Term<EventProxy>>>,
+    event_proxy: &EventProxy,
+    backend: T,
+    session_log: Option<SessionLog>,
+    config: &UiConfig,
+) -> Result<EventLoopSender, Box<dyn Error>>
+where
+    T: EventedPty + OnResize + Send + 'static,
+{
+    let mut event_loop = PtyEventLoop::new(
+        Arc::clone(terminal),
+        event_proxy.clone(),
+        backend,
+        // Child exit is handled by the window, according to `terminal.on_exit`.
+        true,
+        config.debug.ref_test,
+    )?;
+
+    if let Some(session_log) = session_log {
+        event_loop.set_session_log(session_log);
+    }
+
+    Ok(reactor.spawn(event_loop))
+}
+
+/// Spawn the shell in a new PTY and register it with the I/O reactor.
+fn spawn_shell(
+    reactor: &Reactor,
+    terminal: &Arc<FairMutex<Term<EventProxy>>>,
+    event_proxy: &EventProxy,
+    config: &UiConfig,
+    terminal_options: &TerminalOptions,
+    window_size: WindowSize,
+    window_id: u64,
+) -> Result<SpawnedShell, Box<dyn Error>> {
+    let mut pty_config = config.pty_config();
+    terminal_options.override_pty_config(&mut pty_config);
+
+    // The PTY forks a process to run the shell on the slave side of the
+    // pseudoterminal. A file descriptor for the master side is retained for
+    // reading/writing to the shell.
+    let pty = tty::new(&pty_config, window_size, window_id)?;
+    #[cfg(not(windows))]
+    let master_fd = pty.file().as_raw_fd();
+    #[cfg(not(windows))]
+    let pid = pty.child().id();
+    #[cfg(windows)]
+    let pid = pty.child_watcher().pid().map_or_else(std::process::id, |pid| pid.get());
+
+    let log = open_session_log(config, terminal_options, window_id, pid)?;
+    let loop_tx = spawn_io_loop(reactor, terminal, event_proxy, pty, log, config)?;
+
+    Ok(SpawnedShell {
+        loop_tx,
+        #[cfg(not(windows))]
+        master_fd,
+        #[cfg(not(windows))]
+        pid,
+    })
+}
+
+/// Open the session log for a new terminal, if session logging is enabled.
+fn open_session_log(
+    config: &UiConfig,
+    terminal_options: &TerminalOptions,
+    window_id: u64,
+    pid: u32,
+) -> Result<Option<SessionLog>, Box<dyn Error>> {
+    let Some(directory) = terminal_options.log_session(config) else {
+        return Ok(None);
+    };
+
+    let log_options = config.logging.session.options(directory, window_id, pid);
+    let path = log_options.path.clone();
+    let session_log = SessionLog::open(log_options)
+        .map_err(|err| format!("Failed to open session log {}: {}", path.display(), err))?;
+    info!("Logging session to {}", path.display());
+
+    Ok(Some(session_log))
+}
+
+/// Shell spawned in a new PTY.
+struct SpawnedShell {
+    loop_tx: EventLoopSender,
+    #[cfg(not(windows))]
+    master_fd: RawFd,
+    #[cfg(not(windows))]
+    pid: u32,
+}
@@ -2,7 +2,8 @@ use std::borrow::Cow;
 use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
 
-use crate::term::ClipboardType;
+use crate::term::{ClipboardType, EscapeCategories};
+use crate::tty::ExitStatus;
 use crate::vte::ansi::Rgb;
 
 /// Terminal event.
@@ -53,8 +54,17 @@ pub enum Event {
     /// Shutdown request.
     Exit,
 
-    /// Child process exited with an error code.
-    ChildExit(i32),
+    /// Child process exited.
+    ChildExit(ExitStatus),
+
+    /// User variable was set or removed.
+    UserVar(String, Option<String>),
+
+    /// Escape sequence was ignored due to the blocked escape categories.
+    ///
+    /// This is only sent for the first blocked escape sequence after the last call to
+    /// [`crate::term::Term::take_blocked_escapes`].
+    EscapeBlocked(EscapeCategories),
 }
 
 impl Debug for Event {
@@ -72,7 +82,9 @@ impl Debug for Event {
             Event::Wakeup => write!(f, "Wakeup"),
             Event::Bell => write!(f, "Bell"),
             Event::Exit => write!(f, "Exit"),
-            Event::ChildExit(code) => write!(f, "ChildExit({code})"),
+            Event::ChildExit(status) => write!(f, "ChildExit({status:?})"),
+            Event::UserVar(name, value) => write!(f, "UserVar({name}, {value:?})"),
+            Event::EscapeBlocked(category) => write!(f, "EscapeBlocked({category:?})"),
         }
     }
 }
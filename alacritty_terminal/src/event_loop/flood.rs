@@ -0,0 +1,219 @@
+//! Detection of PTY output floods.
+//!
+//! When a program writes more output than can be rendered, every byte still has to be parsed
+//! into the grid. While the terminal is flooded, redraws are throttled so more time is spent on
+//! parsing, and input is written to the PTY as soon as it arrives instead of after the pending
+//! output was processed.
+
+use std::time::{Duration, Instant};
+
+use crate::term::Term;
+
+/// Interval over which the PTY throughput is measured.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Minimum interval between redraws while the terminal is flooded.
+const FLOODED_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Flood detection state of an event loop.
+#[derive(Default)]
+pub(super) struct FloodGuard {
+    /// Start of the current throughput sample.
+    sample_start: Option<Instant>,
+
+    /// Bytes processed in the current throughput sample.
+    sample_bytes: usize,
+
+    /// Time of the last requested redraw.
+    last_frame: Option<Instant>,
+
+    /// Whether a redraw was skipped since the last requested redraw.
+    skipped: bool,
+
+    /// Whether the terminal is flooded.
+    flooded: bool,
+}
+
+impl FloodGuard {
+    /// Whether the terminal is flooded.
+    #[inline]
+    pub fn is_flooded(&self) -> bool {
+        self.flooded
+    }
+
+    /// Account for parsed PTY output.
+    pub fn sample<T>(&mut self, terminal: &mut Term<T>, bytes: usize, now: Instant) {
+        let start = *self.sample_start.get_or_insert(now);
+        self.sample_bytes += bytes;
+
+        let elapsed = now.saturating_duration_since(start);
+        if elapsed < SAMPLE_INTERVAL {
+            return;
+        }
+
+        let throughput = (self.sample_bytes as f64 / elapsed.as_secs_f64()) as usize;
+        self.sample_start = Some(now);
+        self.sample_bytes = 0;
+
+        let config = terminal.config.flood_protection;
+        let display_offset = terminal.grid().display_offset();
+        let stats = &mut terminal.flood_stats;
+        stats.throughput = throughput;
+
+        // Only intervene if the output can't be followed anyway.
+        let flooded = config.threshold > 0
+            && throughput >= config.threshold
+            && (display_offset != 0 || stats.frame_pending);
+
+        if flooded != self.flooded {
+            self.flooded = flooded;
+            stats.flooded = flooded;
+
+            if let Some(history_limit) = config.history_limit {
+                terminal.limit_history(flooded.then_some(history_limit));
+            }
+        }
+    }
+
+    /// Check if a redraw should be requested.
+    pub fn request_frame<T>(&mut self, terminal: &mut Term<T>, now: Instant) -> bool {
+        let throttled = self.flooded
+            && self
+                .last_frame
+                .is_some_and(|last| now.duration_since(last) < FLOODED_FRAME_INTERVAL);
+
+        if throttled {
+            self.skipped = true;
+            terminal.flood_stats.dropped_frames += 1;
+            return false;
+        }
+
+        self.skipped = false;
+        self.last_frame = Some(now);
+        terminal.flood_stats.frame_pending = true;
+
+        true
+    }
+
+    /// Handle an expired deadline.
+    ///
+    /// Returns `true` if a redraw should be requested.
+    pub fn timeout<T>(&mut self, terminal: &mut Term<T>, now: Instant) -> bool {
+        self.sample(terminal, 0, now);
+        self.skipped && self.request_frame(terminal, now)
+    }
+
+    /// Next time the flood state needs to be updated without any PTY output.
+    pub fn deadline(&self) -> Option<Instant> {
+        let frame =
+            self.last_frame.filter(|_| self.skipped).map(|last| last + FLOODED_FRAME_INTERVAL);
+        let sample = self
+            .sample_start
+            .filter(|_| self.flooded || self.sample_bytes > 0)
+            .map(|start| start + SAMPLE_INTERVAL);
+
+        match (frame, sample) {
+            (Some(frame), Some(sample)) => Some(frame.min(sample)),
+            (frame, sample) => frame.or(sample),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::event::VoidListener;
+    use crate::grid::Dimensions;
+    use crate::index::Line;
+    use crate::term::flood::FloodProtection;
+    use crate::term::{Config, TermSize};
+
+    fn term(flood_protection: FloodProtection) -> Term<VoidListener> {
+        let config = Config { flood_protection, ..Config::default() };
+        Term::new(config, &TermSize::new(10, 5), VoidListener)
+    }
+
+    #[test]
+    fn throttles_frames_when_renderer_is_behind() {
+        let mut term = term(FloodProtection { threshold: 1000, history_limit: None });
+        let mut guard = FloodGuard::default();
+        let start = Instant::now();
+
+        guard.sample(&mut term, 0, start);
+        assert!(guard.request_frame(&mut term, start));
+
+        // The requested frame is still pending when the next sample completes.
+        let now = start + SAMPLE_INTERVAL;
+        guard.sample(&mut term, 1000, now);
+        assert!(guard.is_flooded());
+        assert!(term.flood_stats().is_flooded());
+        assert_eq!(term.flood_stats().throughput(), 10_000);
+
+        assert!(guard.request_frame(&mut term, now));
+        assert!(!guard.request_frame(&mut term, now + Duration::from_millis(10)));
+        assert_eq!(term.flood_stats().dropped_frames(), 1);
+
+        // Skipped redraws are eventually requested without further output.
+        let deadline = guard.deadline().unwrap();
+        assert_eq!(deadline, now + FLOODED_FRAME_INTERVAL);
+        assert!(guard.timeout(&mut term, deadline));
+
+        // Without output, the flood is over.
+        assert!(!guard.is_flooded());
+        assert_eq!(term.flood_stats().throughput(), 0);
+        assert_eq!(guard.deadline(), None);
+    }
+
+    #[test]
+    fn no_flood_while_renderer_keeps_up() {
+        let mut term = term(FloodProtection { threshold: 1000, history_limit: None });
+        let mut guard = FloodGuard::default();
+        let start = Instant::now();
+
+        guard.sample(&mut term, 0, start);
+        assert!(guard.request_frame(&mut term, start));
+        term.flood_stats_mut().frame_rendered();
+
+        guard.sample(&mut term, 1000, start + SAMPLE_INTERVAL);
+        assert!(!guard.is_flooded());
+        assert!(guard.request_frame(&mut term, start + SAMPLE_INTERVAL));
+    }
+
+    #[test]
+    fn disabled() {
+        let mut term = term(FloodProtection { threshold: 0, history_limit: None });
+        let mut guard = FloodGuard::default();
+        let start = Instant::now();
+
+        guard.sample(&mut term, 0, start);
+        assert!(guard.request_frame(&mut term, start));
+        guard.sample(&mut term, usize::MAX / 2, start + SAMPLE_INTERVAL);
+        assert!(!guard.is_flooded());
+    }
+
+    #[test]
+    fn history_limit() {
+        let mut term = term(FloodProtection { threshold: 1, history_limit: Some(2) });
+        for _ in 0..10 {
+            term.grid_mut().scroll_up(&(Line(0)..Line(5)), 1);
+        }
+        assert_eq!(term.grid().history_size(), 10);
+
+        let mut guard = FloodGuard::default();
+        let start = Instant::now();
+        guard.sample(&mut term, 0, start);
+        assert!(guard.request_frame(&mut term, start));
+        guard.sample(&mut term, 1000, start + SAMPLE_INTERVAL);
+        assert!(guard.is_flooded());
+        assert_eq!(term.grid().history_size(), 2);
+
+        // The configured history is restored after the flood.
+        guard.sample(&mut term, 0, start + SAMPLE_INTERVAL * 2);
+        assert!(!guard.is_flooded());
+        for _ in 0..10 {
+            term.grid_mut().scroll_up(&(Line(0)..Line(5)), 1);
+        }
+        assert_eq!(term.grid().history_size(), 12);
+    }
+}
@@ -16,10 +16,19 @@ use polling::{Event as PollingEvent, Events, PollMode};
 
 use crate::event::{self, Event, EventListener, WindowSize};
 use crate::sync::FairMutex;
+use crate::term::user_var::UserVarParser;
 use crate::term::Term;
 use crate::vte::ansi;
 use crate::{thread, tty};
 
+pub mod flood;
+mod reactor;
+pub mod session_log;
+
+use self::flood::FloodGuard;
+pub use self::reactor::Reactor;
+use self::session_log::SessionLog;
+
 /// Max bytes to read from the PTY before forced terminal synchronization.
 pub(crate) const READ_BUFFER_SIZE: usize = 0x10_0000;
 
@@ -52,6 +61,7 @@ pub struct EventLoop<T: tty::EventedPty, U: EventListener> {
     event_proxy: U,
     hold: bool,
     ref_test: bool,
+    session_log: Option<SessionLog>,
 }
 
 impl<T, U> EventLoop<T, U>
@@ -78,9 +88,15 @@ where
             event_proxy,
             hold,
             ref_test,
+            session_log: None,
         })
     }
 
+    /// Record all PTY output in a session log.
+    pub fn set_session_log(&mut self, session_log: SessionLog) {
+        self.session_log = Some(session_log);
+    }
+
     pub fn channel(&self) -> EventLoopSender {
         EventLoopSender { sender: self.tx.clone(), poller: self.poll.clone() }
     }
@@ -100,76 +116,200 @@ where
         true
     }
 
-    #[inline]
+    /// Write PTY output to the session log.
+    fn log_output(&mut self, bytes: &[u8]) {
+        let Some(session_log) = &mut self.session_log else { return };
+
+        if let Err(err) = session_log.write(bytes) {
+            error!("Unable to write session log {:?}: {}", session_log.path(), err);
+            self.session_log = None;
+        }
+    }
+
+    /// Write buffered output to the session log file.
+    fn flush_session_log(&mut self) {
+        let Some(session_log) = &mut self.session_log else { return };
+
+        if let Err(err) = session_log.flush() {
+            error!("Unable to write session log {:?}: {}", session_log.path(), err);
+            self.session_log = None;
+        }
+    }
+
+    /// Write input which was received while the PTY output is still being processed.
+    ///
+    /// This makes sure interrupts like `^C` reach the child without waiting for a flood of
+    /// output to be parsed first.
+    fn write_priority_input(&mut self, state: &mut State) {
+        while let Some(Msg::Input(_)) = self.rx.peek() {
+            if let Some(Msg::Input(input)) = self.rx.recv() {
+                state.write_list.push_back(input);
+            }
+        }
+
+        if state.needs_write() {
+            if let Err(err) = self.pty_write(state) {
+                error!("Error writing to PTY in event loop: {}", err);
+            }
+        }
+    }
+
+    /// Handle expired synchronized update, flood protection and session log timeouts.
+    fn handle_timeouts(&mut self, state: &mut State, now: Instant) {
+        let session_log = self.session_log.as_ref().and_then(SessionLog::deadline);
+        if session_log.is_some_and(|deadline| deadline <= now) {
+            self.flush_session_log();
+        }
+
+        let sync_timeout = state.parser.sync_timeout().sync_timeout();
+        let sync_expired = sync_timeout.is_some_and(|timeout| timeout <= now);
+        let flood_expired = state.flood.deadline().is_some_and(|deadline| deadline <= now);
+        if !sync_expired && !flood_expired {
+            return;
+        }
+
+        let mut terminal = self.terminal.lock();
+
+        if sync_expired {
+            state.parser.stop_sync(&mut *terminal);
+        }
+
+        let flood_redraw = flood_expired && state.flood.timeout(&mut terminal, now);
+
+        if sync_expired || flood_redraw {
+            self.event_proxy.send_event(Event::Wakeup);
+        }
+    }
+
+    /// Next time the event loop needs to handle timeouts without any I/O.
+    fn deadline(&self, state: &State) -> Option<Instant> {
+        let sync_timeout = state.parser.sync_timeout().sync_timeout();
+        let session_log = self.session_log.as_ref().and_then(SessionLog::deadline);
+        [sync_timeout, state.flood.deadline(), session_log].into_iter().flatten().min()
+    }
+
+    /// Read from the PTY and parse its output.
+    ///
+    /// Output which can't be parsed because the terminal is locked is kept in `pending` for the
+    /// next call. With `block` set, this waits for the terminal lock instead.
     fn pty_read<X>(
         &mut self,
         state: &mut State,
-        buf: &mut [u8],
+        pending: &mut Vec<u8>,
+        block: bool,
         mut writer: Option<&mut X>,
     ) -> io::Result<()>
     where
         X: Write,
     {
-        let mut unprocessed = 0;
+        let mut readable = true;
         let mut processed = 0;
+        let mut result = Ok(());
 
         // Reserve the next terminal lock for PTY reading.
-        let _terminal_lease = Some(self.terminal.lease());
+        let term = self.terminal.clone();
+        let _terminal_lease = block.then(|| term.lease());
         let mut terminal = None;
 
-        loop {
-            // Read from the PTY.
-            match self.pty.reader().read(&mut buf[unprocessed..]) {
-                // This is received on Windows/macOS when no more data is readable from the PTY.
-                Ok(0) if unprocessed == 0 => break,
-                Ok(got) => unprocessed += got,
-                Err(err) => match err.kind() {
-                    ErrorKind::Interrupted | ErrorKind::WouldBlock => {
-                        // Go back to mio if we're caught up on parsing and the PTY would block.
-                        if unprocessed == 0 {
-                            break;
+        while processed < MAX_LOCKED_READ {
+            // Read from the PTY, unless the budget for unprocessed bytes is exhausted.
+            let unprocessed = pending.len();
+            if readable && unprocessed < READ_BUFFER_SIZE {
+                let len = unprocessed + (READ_BUFFER_SIZE - unprocessed).min(MAX_LOCKED_READ);
+                pending.resize(len, 0);
+
+                match self.pty.reader().read(&mut pending[unprocessed..]) {
+                    Ok(got) => {
+                        pending.truncate(unprocessed + got);
+                        readable = got > 0;
+                    },
+                    Err(err) => {
+                        pending.truncate(unprocessed);
+                        readable = false;
+
+                        if !matches!(err.kind(), ErrorKind::Interrupted | ErrorKind::WouldBlock) {
+                            result = Err(err);
                         }
                     },
-                    _ => return Err(err),
-                },
+                }
+            }
+
+            if pending.is_empty() {
+                break;
             }
 
-            // Attempt to lock the terminal.
+            // Leave the output for later if the terminal is locked.
             let terminal = match &mut terminal {
                 Some(terminal) => terminal,
-                None => terminal.insert(match self.terminal.try_lock_unfair() {
-                    // Force block if we are at the buffer size limit.
-                    None if unprocessed >= READ_BUFFER_SIZE => self.terminal.lock_unfair(),
-                    None => continue,
-                    Some(terminal) => terminal,
-                }),
+                None if block => terminal.insert(term.lock_unfair()),
+                None => match term.try_lock_unfair() {
+                    Some(guard) => terminal.insert(guard),
+                    None => break,
+                },
             };
 
             // Write a copy of the bytes to the ref test file.
             if let Some(writer) = &mut writer {
-                writer.write_all(&buf[..unprocessed]).unwrap();
+                writer.write_all(pending).unwrap();
             }
 
+            self.log_output(pending);
+
             // Parse the incoming bytes.
-            for byte in &buf[..unprocessed] {
+            for byte in pending.iter() {
+                state.user_var_parser.advance(&mut **terminal, *byte);
                 state.parser.advance(&mut **terminal, *byte);
             }
 
-            processed += unprocessed;
-            unprocessed = 0;
+            processed += pending.len();
+            pending.clear();
 
-            // Assure we're not blocking the terminal too long unnecessarily.
-            if processed >= MAX_LOCKED_READ {
+            if state.flood.is_flooded() {
+                self.write_priority_input(state);
+            }
+
+            if !readable {
                 break;
             }
         }
 
-        // Queue terminal redraw unless all processed bytes were synchronized.
-        if state.parser.sync_bytes_count() < processed && processed > 0 {
-            self.event_proxy.send_event(Event::Wakeup);
+        if let Some(terminal) = &mut terminal {
+            let now = Instant::now();
+            state.flood.sample(terminal, processed, now);
+
+            // Queue terminal redraw unless all processed bytes were synchronized.
+            if state.parser.sync_bytes_count() < processed
+                && processed > 0
+                && state.flood.request_frame(terminal, now)
+            {
+                self.event_proxy.send_event(Event::Wakeup);
+            }
         }
 
-        Ok(())
+        result
+    }
+
+    /// Handle termination of the PTY's child process.
+    fn child_exit<X>(
+        &mut self,
+        state: &mut State,
+        pending: &mut Vec<u8>,
+        writer: Option<&mut X>,
+        status: tty::ExitStatus,
+    ) where
+        X: Write,
+    {
+        self.event_proxy.send_event(Event::ChildExit(status));
+
+        if self.hold {
+            // With hold enabled, make sure the PTY is drained.
+            let _ = self.pty_read(state, pending, true, writer);
+        } else {
+            // Without hold, shutdown the terminal.
+            self.terminal.lock().exit();
+        }
+
+        self.event_proxy.send_event(Event::Wakeup);
     }
 
     #[inline]
@@ -207,7 +347,7 @@ where
     pub fn spawn(mut self) -> JoinHandle<(Self, State)> {
         thread::spawn_named("PTY reader", move || {
             let mut state = State::default();
-            let mut buf = [0u8; READ_BUFFER_SIZE];
+            let mut pending = Vec::new();
 
             let poll_opts = PollMode::Level;
             let mut interest = PollingEvent::readable(0);
@@ -227,10 +367,9 @@ where
             };
 
             'event_loop: loop {
-                // Wakeup the event loop when a synchronized update timeout was reached.
-                let handler = state.parser.sync_timeout();
-                let timeout =
-                    handler.sync_timeout().map(|st| st.saturating_duration_since(Instant::now()));
+                // Wakeup the event loop when the next timeout was reached.
+                let deadline = self.deadline(&state);
+                let timeout = deadline.map(|st| st.saturating_duration_since(Instant::now()));
 
                 events.clear();
                 if let Err(err) = self.poll.wait(&mut events, timeout) {
@@ -243,10 +382,9 @@ where
                     }
                 }
 
-                // Handle synchronized update timeout.
+                // Handle synchronized update, flood and session log timeouts.
                 if events.is_empty() && self.rx.peek().is_none() {
-                    state.parser.stop_sync(&mut *self.terminal.lock());
-                    self.event_proxy.send_event(Event::Wakeup);
+                    self.handle_timeouts(&mut state, Instant::now());
                     continue;
                 }
 
@@ -258,32 +396,56 @@ where
                 for event in events.iter() {
                     match event.key {
                         tty::PTY_CHILD_EVENT_TOKEN => {
-                            if let Some(tty::ChildEvent::Exited(code)) = self.pty.next_child_event()
+                            if let Some(tty::ChildEvent::Exited(status)) =
+                                self.pty.next_child_event()
                             {
-                                if let Some(code) = code {
-                                    self.event_proxy.send_event(Event::ChildExit(code));
-                                }
-                                if self.hold {
-                                    // With hold enabled, make sure the PTY is drained.
-                                    let _ = self.pty_read(&mut state, &mut buf, pipe.as_mut());
-                                } else {
-                                    // Without hold, shutdown the terminal.
-                                    self.terminal.lock().exit();
-                                }
-                                self.event_proxy.send_event(Event::Wakeup);
+                                self.child_exit(&mut state, &mut pending, pipe.as_mut(), status);
                                 break 'event_loop;
                             }
                         },
 
                         tty::PTY_READ_WRITE_TOKEN => {
                             if event.is_interrupt() {
+                                // Backends without child process report their hangup directly.
+                                if let Some(tty::ChildEvent::Exited(status)) =
+                                    self.pty.next_child_event()
+                                {
+                                    // Process output which was sent before the hangup.
+                                    let _ = self.pty_read(
+                                        &mut state,
+                                        &mut pending,
+                                        true,
+                                        pipe.as_mut(),
+                                    );
+                                    self.child_exit(
+                                        &mut state,
+                                        &mut pending,
+                                        pipe.as_mut(),
+                                        status,
+                                    );
+                                    break 'event_loop;
+                                }
+
                                 // Don't try to do I/O on a dead PTY.
                                 continue;
                             }
 
                             if event.readable {
-                                if let Err(err) = self.pty_read(&mut state, &mut buf, pipe.as_mut())
+                                if let Err(err) =
+                                    self.pty_read(&mut state, &mut pending, true, pipe.as_mut())
                                 {
+                                    if let Some(tty::ChildEvent::Exited(status)) =
+                                        self.pty.next_child_event()
+                                    {
+                                        self.child_exit(
+                                            &mut state,
+                                            &mut pending,
+                                            pipe.as_mut(),
+                                            status,
+                                        );
+                                        break 'event_loop;
+                                    }
+
                                     // On Linux, a `read` on the master side of a PTY can fail
                                     // with `EIO` if the client side hangs up.  In that case,
                                     // just loop back round for the inevitable `Exited` event.
@@ -406,6 +568,8 @@ pub struct State {
     write_list: VecDeque<Cow<'static, [u8]>>,
     writing: Option<Writing>,
     parser: ansi::Processor,
+    user_var_parser: UserVarParser,
+    flood: FloodGuard,
 }
 
 impl State {
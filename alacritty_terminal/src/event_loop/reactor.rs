@@ -0,0 +1,492 @@
+//! I/O reactor servicing multiple PTYs from a single thread.
+
+use std::fs::File;
+use std::io::{self, ErrorKind};
+use std::mem;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use log::error;
+use parking_lot::Mutex;
+use polling::{Event as PollingEvent, Events, PollMode};
+
+use crate::event::{self, EventListener};
+use crate::event_loop::{EventLoop, EventLoopSender, State, READ_BUFFER_SIZE};
+use crate::{thread, tty};
+
+/// Delay before retrying to parse PTY output, while its terminal is locked.
+const LOCKED_RETRY_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Multiplexed I/O loop for multiple terminals.
+///
+/// Instead of running a thread for every [`EventLoop`], the reactor polls all of its PTYs from a
+/// single thread. Every terminal is serviced in turn and reads a limited number of bytes before
+/// the next terminal is serviced. Output which can't be parsed because the terminal is
+/// locked is buffered, until the terminal's budget of [`READ_BUFFER_SIZE`] bytes is exhausted.
+pub struct Reactor {
+    poll: Arc<polling::Poller>,
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Reactor {
+    /// Create a reactor and start its I/O thread.
+    pub fn new() -> io::Result<Self> {
+        let poll = Arc::new(polling::Poller::new()?);
+        let shared = Arc::new(Shared::default());
+
+        let thread_poll = poll.clone();
+        let thread_shared = shared.clone();
+        let thread = thread::spawn_named("PTY reactor", move || {
+            ReactorLoop::new(thread_poll, thread_shared).run();
+        });
+
+        Ok(Self { poll, shared, thread: Some(thread) })
+    }
+
+    /// Start servicing an event loop's PTY.
+    ///
+    /// This replaces the event loop's poller, so only the returned channel can be used to send
+    /// messages to the event loop.
+    pub fn spawn<T, U>(&self, mut event_loop: EventLoop<T, U>) -> EventLoopSender
+    where
+        T: tty::EventedPty + event::OnResize + Send + 'static,
+        U: EventListener + Send + 'static,
+    {
+        event_loop.poll = self.poll.clone();
+        let sender = event_loop.channel();
+
+        self.shared.added.lock().push(Box::new(Registration::new(event_loop)));
+        let _ = self.poll.notify();
+
+        sender
+    }
+}
+
+impl Drop for Reactor {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::Relaxed);
+        let _ = self.poll.notify();
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// State shared between the reactor and its thread.
+#[derive(Default)]
+struct Shared {
+    /// Event loops waiting to be registered.
+    added: Mutex<Vec<Box<dyn Source>>>,
+
+    /// Request to terminate the reactor's thread.
+    shutdown: AtomicBool,
+}
+
+/// Type-erased event loop serviced by the reactor.
+trait Source: Send {
+    /// Register the PTY using the poller keys starting at `key`.
+    fn register(&mut self, key: usize) -> io::Result<()>;
+
+    /// Service pending messages, I/O and timeouts.
+    ///
+    /// Returns `false` when the PTY should be removed from the reactor.
+    fn service(&mut self, readiness: Readiness, now: Instant) -> bool;
+
+    /// Next time this source needs to be serviced without any I/O.
+    fn deadline(&self) -> Option<Instant>;
+
+    /// Remove the PTY from the poller.
+    fn deregister(&mut self);
+}
+
+/// Readiness of a PTY reported by the poller.
+#[derive(Default, Copy, Clone)]
+struct Readiness {
+    readable: bool,
+    writable: bool,
+    interrupt: bool,
+    child_event: bool,
+}
+
+/// Event loop registered with the reactor.
+struct Registration<T: tty::EventedPty, U: EventListener> {
+    event_loop: EventLoop<T, U>,
+    state: State,
+    interest: PollingEvent,
+    /// Output which couldn't be parsed yet, since the terminal was locked.
+    pending: Vec<u8>,
+    pipe: Option<File>,
+}
+
+impl<T, U> Registration<T, U>
+where
+    T: tty::EventedPty + event::OnResize + Send + 'static,
+    U: EventListener + Send + 'static,
+{
+    fn new(event_loop: EventLoop<T, U>) -> Self {
+        let pipe = if event_loop.ref_test {
+            Some(File::create("./alacritty.recording").expect("create alacritty recording"))
+        } else {
+            None
+        };
+
+        Self {
+            event_loop,
+            pipe,
+            state: State::default(),
+            interest: PollingEvent::readable(0),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Read from the PTY, see [`EventLoop::pty_read`].
+    fn pty_read(&mut self, block: bool) -> io::Result<()> {
+        self.event_loop.pty_read(&mut self.state, &mut self.pending, block, self.pipe.as_mut())
+    }
+
+    /// Handle termination of the PTY's child process.
+    fn child_exit(&mut self, status: tty::ExitStatus) {
+        // Make sure buffered output isn't lost.
+        let _ = self.pty_read(true);
+
+        let pipe = self.pipe.as_mut();
+        self.event_loop.child_exit(&mut self.state, &mut self.pending, pipe, status);
+    }
+
+    /// Update the PTY's poller interest.
+    fn update_interest(&mut self) -> io::Result<()> {
+        let readable = self.pending.len() < READ_BUFFER_SIZE;
+        let writable = self.state.needs_write();
+        if readable == self.interest.readable && writable == self.interest.writable {
+            return Ok(());
+        }
+
+        self.interest.readable = readable;
+        self.interest.writable = writable;
+
+        let event_loop = &mut self.event_loop;
+        event_loop.pty.reregister(&event_loop.poll, self.interest, PollMode::Level)
+    }
+}
+
+impl<T, U> Source for Registration<T, U>
+where
+    T: tty::EventedPty + event::OnResize + Send + 'static,
+    U: EventListener + Send + 'static,
+{
+    fn register(&mut self, key: usize) -> io::Result<()> {
+        self.interest.key = key;
+
+        let event_loop = &mut self.event_loop;
+        unsafe { event_loop.pty.register(&event_loop.poll, self.interest, PollMode::Level) }
+    }
+
+    fn service(&mut self, readiness: Readiness, now: Instant) -> bool {
+        // Handle channel events, if there are any.
+        if !self.event_loop.drain_recv_channel(&mut self.state) {
+            return false;
+        }
+
+        if readiness.child_event {
+            if let Some(tty::ChildEvent::Exited(status)) = self.event_loop.pty.next_child_event() {
+                self.child_exit(status);
+                return false;
+            }
+        }
+
+        if readiness.interrupt {
+            // Backends without child process report their hangup directly.
+            if let Some(tty::ChildEvent::Exited(status)) = self.event_loop.pty.next_child_event() {
+                self.child_exit(status);
+                return false;
+            }
+        } else {
+            if readiness.readable || !self.pending.is_empty() {
+                if let Err(err) = self.pty_read(false) {
+                    if let Some(tty::ChildEvent::Exited(status)) =
+                        self.event_loop.pty.next_child_event()
+                    {
+                        self.child_exit(status);
+                        return false;
+                    }
+
+                    // On Linux, a `read` on the master side of a PTY can fail with `EIO` if the
+                    // client side hangs up. In that case, just wait for the `Exited` event.
+                    #[cfg(target_os = "linux")]
+                    let hangup = err.raw_os_error() == Some(libc::EIO);
+                    #[cfg(not(target_os = "linux"))]
+                    let hangup = false;
+
+                    if !hangup {
+                        error!("Error reading from PTY in event loop: {}", err);
+                        return false;
+                    }
+                }
+            }
+
+            if readiness.writable {
+                if let Err(err) = self.event_loop.pty_write(&mut self.state) {
+                    error!("Error writing to PTY in event loop: {}", err);
+                    return false;
+                }
+            }
+        }
+
+        // Handle synchronized update, flood and session log timeouts.
+        self.event_loop.handle_timeouts(&mut self.state, now);
+
+        if let Err(err) = self.update_interest() {
+            error!("Event loop registration error: {}", err);
+            return false;
+        }
+
+        true
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        let deadline = self.event_loop.deadline(&self.state);
+        if self.pending.is_empty() {
+            deadline
+        } else {
+            let retry = Instant::now() + LOCKED_RETRY_INTERVAL;
+            Some(deadline.map_or(retry, |deadline| deadline.min(retry)))
+        }
+    }
+
+    fn deregister(&mut self) {
+        let event_loop = &mut self.event_loop;
+        let _ = event_loop.pty.deregister(&event_loop.poll);
+    }
+}
+
+/// State of the reactor's I/O thread.
+struct ReactorLoop {
+    poll: Arc<polling::Poller>,
+    shared: Arc<Shared>,
+    /// Registered PTYs, indexed by their poller key divided by [`tty::PTY_TOKEN_COUNT`].
+    sources: Vec<Option<Box<dyn Source>>>,
+    readiness: Vec<Readiness>,
+    /// Index of the first source serviced in the next iteration.
+    next_first: usize,
+}
+
+impl ReactorLoop {
+    fn new(poll: Arc<polling::Poller>, shared: Arc<Shared>) -> Self {
+        Self { poll, shared, sources: Vec::new(), readiness: Vec::new(), next_first: 0 }
+    }
+
+    fn run(&mut self) {
+        let mut events = Events::with_capacity(NonZeroUsize::new(1024).unwrap());
+
+        while !self.shared.shutdown.load(Ordering::Relaxed) {
+            // Wakeup the reactor when the earliest timeout was reached.
+            let deadline =
+                self.sources.iter().flatten().filter_map(|source| source.deadline()).min();
+            let mut timeout =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+
+            // Service new PTYs immediately, since their messages might already be queued.
+            if self.register_added() {
+                timeout = Some(Duration::ZERO);
+            }
+
+            events.clear();
+            if let Err(err) = self.poll.wait(&mut events, timeout) {
+                match err.kind() {
+                    ErrorKind::Interrupted => continue,
+                    _ => {
+                        error!("Event loop polling error: {}", err);
+                        break;
+                    },
+                }
+            }
+
+            for event in events.iter() {
+                let index = event.key / tty::PTY_TOKEN_COUNT;
+                let Some(readiness) = self.readiness.get_mut(index) else { continue };
+
+                if event.key % tty::PTY_TOKEN_COUNT == tty::PTY_CHILD_EVENT_TOKEN {
+                    readiness.child_event = true;
+                } else {
+                    readiness.readable |= event.readable;
+                    readiness.writable |= event.writable;
+                    readiness.interrupt |= event.is_interrupt();
+                }
+            }
+
+            // Service every source once, rotating the order for fairness.
+            let now = Instant::now();
+            let len = self.sources.len();
+            for offset in 0..len {
+                let index = (self.next_first + offset) % len;
+                let readiness = mem::take(&mut self.readiness[index]);
+
+                let Some(source) = &mut self.sources[index] else { continue };
+                if !source.service(readiness, now) {
+                    source.deregister();
+                    self.sources[index] = None;
+                }
+            }
+            self.next_first = self.next_first.wrapping_add(1) % len.max(1);
+        }
+
+        for source in self.sources.iter_mut().flatten() {
+            source.deregister();
+        }
+    }
+
+    /// Register all event loops which were added since the last iteration.
+    ///
+    /// Returns `true` if any event loop was added.
+    fn register_added(&mut self) -> bool {
+        let added = mem::take(&mut *self.shared.added.lock());
+        let any_added = !added.is_empty();
+        for mut source in added {
+            // Reuse the slot of a removed PTY, to keep poller keys bounded.
+            let index = match self.sources.iter().position(Option::is_none) {
+                Some(index) => index,
+                None => {
+                    self.sources.push(None);
+                    self.readiness.push(Readiness::default());
+                    self.sources.len() - 1
+                },
+            };
+
+            match source.register(index * tty::PTY_TOKEN_COUNT) {
+                Ok(()) => self.sources[index] = Some(source),
+                Err(err) => error!("Event loop registration error: {}", err),
+            }
+        }
+
+        any_added
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    use std::io::{Read, Write};
+    use std::os::unix::io::OwnedFd;
+    use std::os::unix::net::UnixStream;
+
+    use crate::event::Event;
+    use crate::event_loop::Msg;
+    use crate::grid::Dimensions;
+    use crate::index::{Column, Line, Point};
+    use crate::sync::FairMutex;
+    use crate::term::test::TermSize;
+    use crate::term::{Config, Term};
+    use crate::tty::stream::Stream;
+
+    #[derive(Clone, Default)]
+    struct Listener(Arc<Mutex<Vec<Event>>>);
+
+    impl EventListener for Listener {
+        fn send_event(&self, event: Event) {
+            self.0.lock().push(event);
+        }
+    }
+
+    struct TestTerminal {
+        term: Arc<FairMutex<Term<Listener>>>,
+        listener: Listener,
+        sender: EventLoopSender,
+        remote: UnixStream,
+    }
+
+    impl TestTerminal {
+        fn new(reactor: &Reactor) -> Self {
+            let (local, remote) = UnixStream::pair().unwrap();
+            let stream = Stream::from_fd(OwnedFd::from(local)).unwrap();
+
+            let listener = Listener::default();
+            let size = TermSize::new(20, 2);
+            let term = Term::new(Config::default(), &size, listener.clone());
+            let term = Arc::new(FairMutex::new(term));
+
+            let event_loop =
+                EventLoop::new(term.clone(), listener.clone(), stream, false, false).unwrap();
+            let sender = reactor.spawn(event_loop);
+
+            Self { term, listener, sender, remote }
+        }
+
+        fn first_line(&self) -> String {
+            let term = self.term.lock();
+            let end = Point::new(Line(0), term.last_column());
+            term.bounds_to_string(Point::new(Line(0), Column(0)), end)
+        }
+    }
+
+    fn wait_until(mut predicate: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !predicate() {
+            assert!(Instant::now() < deadline, "timed out");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn multiple_terminals() {
+        let reactor = Reactor::new().unwrap();
+        let terminals: Vec<_> = (0..3).map(|_| TestTerminal::new(&reactor)).collect();
+
+        for (i, terminal) in terminals.iter().enumerate() {
+            write!(&terminal.remote, "terminal {i}").unwrap();
+        }
+        for (i, terminal) in terminals.iter().enumerate() {
+            wait_until(|| terminal.first_line() == format!("terminal {i}"));
+        }
+
+        // Input is written to the matching PTY.
+        terminals[1].sender.send(Msg::Input(b"input"[..].into())).unwrap();
+        let mut buf = [0; 5];
+        (&terminals[1].remote).read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"input");
+
+        // Hangups only terminate the affected terminal.
+        let mut terminals = terminals.into_iter();
+        let first = terminals.next().unwrap();
+        let TestTerminal { listener, remote, .. } = first;
+        drop(remote);
+        wait_until(|| listener.0.lock().iter().any(|event| matches!(event, Event::Exit)));
+
+        let second = terminals.next().unwrap();
+        write!(&second.remote, "\rstill alive").unwrap();
+        wait_until(|| second.first_line() == "still alive");
+    }
+
+    #[test]
+    fn locked_terminal_does_not_block_others() {
+        let reactor = Reactor::new().unwrap();
+        let locked = TestTerminal::new(&reactor);
+        let other = TestTerminal::new(&reactor);
+
+        let guard = locked.term.lock();
+        write!(&locked.remote, "buffered").unwrap();
+        write!(&other.remote, "parsed").unwrap();
+        wait_until(|| other.first_line() == "parsed");
+        drop(guard);
+
+        wait_until(|| locked.first_line() == "buffered");
+    }
+
+    #[test]
+    fn shutdown_removes_terminal() {
+        let reactor = Reactor::new().unwrap();
+        let terminal = TestTerminal::new(&reactor);
+
+        terminal.sender.send(Msg::Shutdown).unwrap();
+
+        // The PTY is dropped, which closes the stream.
+        let mut buf = [0; 1];
+        terminal.remote.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        assert_eq!((&terminal.remote).read(&mut buf).unwrap(), 0);
+    }
+}
@@ -0,0 +1,515 @@
+//! Transcripts of the PTY output.
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::vte::{Parser, Perform};
+
+/// Maximum time PTY output is buffered before it is written to the log file.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Format of the session transcript.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// PTY output without any modifications.
+    #[default]
+    Raw,
+
+    /// Text without any escape sequences.
+    AnsiStripped,
+
+    /// Text without any escape sequences, with every line prefixed by a UTC timestamp.
+    TimestampedLines,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_ascii_lowercase().as_str() {
+            "raw" => Ok(Self::Raw),
+            "ansi-stripped" => Ok(Self::AnsiStripped),
+            "timestamped-lines" => Ok(Self::TimestampedLines),
+            _ => Err(format!(
+                "invalid log format {format:?}, expected one of `raw`, `ansi-stripped` or \
+                 `timestamped-lines`"
+            )),
+        }
+    }
+}
+
+impl Display for LogFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Raw => f.write_str("raw"),
+            Self::AnsiStripped => f.write_str("ansi-stripped"),
+            Self::TimestampedLines => f.write_str("timestamped-lines"),
+        }
+    }
+}
+
+/// Session log configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionLogOptions {
+    /// Path of the log file.
+    pub path: PathBuf,
+
+    /// Format of the transcript.
+    pub format: LogFormat,
+
+    /// Size in bytes after which the log file is rotated.
+    pub max_size: Option<u64>,
+
+    /// Number of rotated log files which are kept.
+    ///
+    /// When this is `0`, the log file is truncated instead of being rotated.
+    pub max_files: usize,
+}
+
+impl SessionLogOptions {
+    /// Options for a raw log without rotation.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), format: LogFormat::Raw, max_size: None, max_files: 0 }
+    }
+}
+
+/// Writer for the transcript of a terminal session.
+pub struct SessionLog {
+    options: SessionLogOptions,
+    file: BufWriter<File>,
+    size: u64,
+    text: Option<TextLog>,
+    flush_deadline: Option<Instant>,
+}
+
+impl SessionLog {
+    /// Open the log file, appending to it if it already exists.
+    pub fn open(options: SessionLogOptions) -> io::Result<Self> {
+        if let Some(parent) = options.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = open_append(&options.path)?;
+        let size = file.metadata()?.len();
+
+        let text = match options.format {
+            LogFormat::Raw => None,
+            LogFormat::AnsiStripped => Some(TextLog::new(false)),
+            LogFormat::TimestampedLines => Some(TextLog::new(true)),
+        };
+
+        Ok(Self { file: BufWriter::new(file), options, size, text, flush_deadline: None })
+    }
+
+    /// Path of the current log file.
+    pub fn path(&self) -> &Path {
+        &self.options.path
+    }
+
+    /// Record PTY output.
+    ///
+    /// The output is buffered until [`Self::flush`] is called, which should happen once the
+    /// [`Self::deadline`] is reached.
+    pub fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match self.text.as_mut().map(|text| text.advance(bytes)) {
+            Some(output) => self.write_file(&output),
+            None => self.write_file(bytes),
+        }
+    }
+
+    /// Write all buffered output to the log file.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.flush_deadline = None;
+        self.file.flush()
+    }
+
+    /// Time at which buffered output should be flushed.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.flush_deadline
+    }
+
+    /// Write to the log file, rotating it if necessary.
+    fn write_file(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        let size = self.size + bytes.len() as u64;
+        if self.size > 0 && self.options.max_size.is_some_and(|max_size| size > max_size) {
+            self.rotate()?;
+        }
+
+        self.file.write_all(bytes)?;
+        self.size += bytes.len() as u64;
+
+        self.flush_deadline.get_or_insert_with(|| Instant::now() + FLUSH_INTERVAL);
+
+        Ok(())
+    }
+
+    /// Move the current log file to `<path>.1`, shifting all previously rotated files.
+    ///
+    /// Without any rotated files, the current log file is truncated instead.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        let path = &self.options.path;
+        if self.options.max_files == 0 {
+            self.file = BufWriter::new(File::create(path)?);
+            self.size = 0;
+            return Ok(());
+        }
+
+        let rotated = |index: usize| {
+            let mut path = path.clone().into_os_string();
+            path.push(format!(".{index}"));
+            PathBuf::from(path)
+        };
+
+        let _ = fs::remove_file(rotated(self.options.max_files));
+        for index in (1..self.options.max_files).rev() {
+            let _ = fs::rename(rotated(index), rotated(index + 1));
+        }
+        fs::rename(path, rotated(1))?;
+
+        self.file = BufWriter::new(open_append(path)?);
+        self.size = 0;
+
+        Ok(())
+    }
+}
+
+impl Drop for SessionLog {
+    fn drop(&mut self) {
+        // Write the last incomplete line.
+        let line = self.text.as_mut().map(TextLog::finish).unwrap_or_default();
+        let _ = self.write_file(&line);
+        let _ = self.file.flush();
+    }
+}
+
+/// Open a file for appending.
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Conversion of PTY output to plain text lines.
+struct TextLog {
+    parser: Parser,
+    lines: LineCollector,
+}
+
+impl TextLog {
+    fn new(timestamps: bool) -> Self {
+        Self { parser: Parser::new(), lines: LineCollector { timestamps, ..Default::default() } }
+    }
+
+    /// Process PTY output, returning all completed lines.
+    fn advance(&mut self, bytes: &[u8]) -> Vec<u8> {
+        for byte in bytes {
+            self.parser.advance(&mut self.lines, *byte);
+        }
+
+        mem::take(&mut self.lines.output)
+    }
+
+    /// Complete the current line.
+    fn finish(&mut self) -> Vec<u8> {
+        if !self.lines.line.is_empty() {
+            self.lines.finish_line();
+        }
+
+        mem::take(&mut self.lines.output)
+    }
+}
+
+/// Parser callbacks collecting printable text.
+#[derive(Default)]
+struct LineCollector {
+    timestamps: bool,
+    line: Vec<char>,
+    column: usize,
+    output: Vec<u8>,
+}
+
+impl LineCollector {
+    fn finish_line(&mut self) {
+        if self.timestamps {
+            let timestamp = format_timestamp(SystemTime::now());
+            self.output.extend_from_slice(format!("[{timestamp}] ").as_bytes());
+        }
+
+        let line: String = self.line.drain(..).collect();
+        self.output.extend_from_slice(line.as_bytes());
+        self.output.push(b'\n');
+        self.column = 0;
+    }
+}
+
+impl Perform for LineCollector {
+    fn print(&mut self, c: char) {
+        // Text after a carriage return overwrites the start of the line.
+        match self.line.get_mut(self.column) {
+            Some(cell) => *cell = c,
+            None => self.line.push(c),
+        }
+        self.column += 1;
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' => self.finish_line(),
+            b'\r' => self.column = 0,
+            b'\t' => self.print('\t'),
+            // Backspace is commonly used to correct input.
+            0x08 if self.column == self.line.len() => {
+                self.line.pop();
+                self.column = self.line.len();
+            },
+            0x08 => self.column = self.column.saturating_sub(1),
+            _ => (),
+        }
+    }
+}
+
+/// Expand the placeholders in a log file name template.
+///
+/// The supported placeholders are `{window_id}`, `{pid}`, `{date}` for the UTC date as
+/// `YYYY-MM-DD` and `{time}` for the UTC time as `HH-MM-SS`.
+pub fn expand_file_name(template: &str, window_id: u64, pid: u32, time: SystemTime) -> String {
+    let DateTime { year, month, day, hour, minute, second, .. } = DateTime::from(time);
+
+    template
+        .replace("{window_id}", &window_id.to_string())
+        .replace("{pid}", &pid.to_string())
+        .replace("{date}", &format!("{year:04}-{month:02}-{day:02}"))
+        .replace("{time}", &format!("{hour:02}-{minute:02}-{second:02}"))
+}
+
+/// Format a time as RFC 3339 UTC timestamp with millisecond precision.
+fn format_timestamp(time: SystemTime) -> String {
+    let DateTime { year, month, day, hour, minute, second, millisecond } = DateTime::from(time);
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}.{millisecond:03}Z")
+}
+
+/// Calendar date and time in UTC.
+struct DateTime {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+    millisecond: u32,
+}
+
+impl From<SystemTime> for DateTime {
+    fn from(time: SystemTime) -> Self {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let seconds = since_epoch.as_secs();
+        let days = (seconds / 86400) as i64;
+        let seconds_of_day = (seconds % 86400) as u32;
+
+        // Convert days since the epoch to a civil date in the proleptic Gregorian calendar.
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year,
+            month,
+            day,
+            hour: seconds_of_day / 3600,
+            minute: seconds_of_day / 60 % 60,
+            second: seconds_of_day % 60,
+            millisecond: since_epoch.subsec_millis(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "alacritty-session-log-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn parse_format() {
+        assert_eq!("raw".parse(), Ok(LogFormat::Raw));
+        assert_eq!("ansi-stripped".parse(), Ok(LogFormat::AnsiStripped));
+        assert_eq!("Timestamped-Lines".parse(), Ok(LogFormat::TimestampedLines));
+        assert!("text".parse::<LogFormat>().is_err());
+    }
+
+    #[test]
+    fn raw() {
+        let dir = temp_dir("raw");
+        let path = dir.join("session.log");
+
+        let mut log = SessionLog::open(SessionLogOptions::new(&path)).unwrap();
+        log.write(b"\x1b[31mred\x1b[0m\r\n").unwrap();
+        drop(log);
+
+        assert_eq!(fs::read(&path).unwrap(), b"\x1b[31mred\x1b[0m\r\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn ansi_stripped() {
+        let dir = temp_dir("stripped");
+        let path = dir.join("session.log");
+
+        let options =
+            SessionLogOptions { format: LogFormat::AnsiStripped, ..SessionLogOptions::new(&path) };
+        let mut log = SessionLog::open(options).unwrap();
+        log.write(b"\x1b]0;title\x07\x1b[1mbold\x1b[0m te").unwrap();
+        log.write(b"xx\x08\x08xt\r\nprompt$ ").unwrap();
+        log.flush().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "bold text\n");
+        drop(log);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "bold text\nprompt$ \n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn carriage_return() {
+        let dir = temp_dir("carriage-return");
+        let path = dir.join("session.log");
+
+        let options =
+            SessionLogOptions { format: LogFormat::AnsiStripped, ..SessionLogOptions::new(&path) };
+        let mut log = SessionLog::open(options).unwrap();
+        log.write(b"10%\r50%\r100% done\r\nabcdef\rxy\x08\x08z\r\n").unwrap();
+        drop(log);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "100% done\nzycdef\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn buffered_writes() {
+        let dir = temp_dir("buffered");
+        let path = dir.join("session.log");
+
+        let mut log = SessionLog::open(SessionLogOptions::new(&path)).unwrap();
+        assert_eq!(log.deadline(), None);
+
+        log.write(b"output").unwrap();
+        assert!(log.deadline().is_some());
+        assert_eq!(fs::read(&path).unwrap(), b"");
+
+        log.flush().unwrap();
+        assert_eq!(log.deadline(), None);
+        assert_eq!(fs::read(&path).unwrap(), b"output");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn timestamped_lines() {
+        let dir = temp_dir("timestamped");
+        let path = dir.join("session.log");
+
+        let options = SessionLogOptions {
+            format: LogFormat::TimestampedLines,
+            ..SessionLogOptions::new(&path)
+        };
+        let mut log = SessionLog::open(options).unwrap();
+        log.write(b"first\r\nsecond\r\n").unwrap();
+        drop(log);
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, text) in lines.iter().zip(["first", "second"]) {
+            // Timestamps look like `[2024-01-01T00:00:00.000Z] `.
+            assert_eq!(line.len(), 27 + text.len());
+            assert!(line.starts_with('[') && line[1..].starts_with(|c: char| c.is_ascii_digit()));
+            assert!(line.ends_with(&format!("Z] {text}")));
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rotation() {
+        let dir = temp_dir("rotation");
+        let path = dir.join("session.log");
+
+        let options =
+            SessionLogOptions { max_size: Some(4), max_files: 2, ..SessionLogOptions::new(&path) };
+        let mut log = SessionLog::open(options).unwrap();
+        for chunk in ["aaa", "bbb", "ccc", "ddd"] {
+            log.write(chunk.as_bytes()).unwrap();
+        }
+        drop(log);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "ddd");
+        assert_eq!(fs::read_to_string(dir.join("session.log.1")).unwrap(), "ccc");
+        assert_eq!(fs::read_to_string(dir.join("session.log.2")).unwrap(), "bbb");
+        assert!(!dir.join("session.log.3").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rotation_without_files() {
+        let dir = temp_dir("rotation-without-files");
+        let path = dir.join("session.log");
+
+        let options =
+            SessionLogOptions { max_size: Some(4), max_files: 0, ..SessionLogOptions::new(&path) };
+        let mut log = SessionLog::open(options).unwrap();
+        for chunk in ["aaa", "bbb"] {
+            log.write(chunk.as_bytes()).unwrap();
+        }
+        drop(log);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "bbb");
+        assert!(!dir.join("session.log.1").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn file_name_template() {
+        // 2024-02-29T13:05:09Z.
+        let time = UNIX_EPOCH + Duration::from_secs(1_709_211_909);
+        let name = expand_file_name("{date}_{time}_{pid}_{window_id}.log", 7, 42, time);
+        assert_eq!(name, "2024-02-29_13-05-09_42_7.log");
+    }
+
+    #[test]
+    fn timestamp() {
+        let time = UNIX_EPOCH + Duration::from_millis(951_782_400_123);
+        assert_eq!(format_timestamp(time), "2000-02-29T00:00:00.123Z");
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+    }
+}
@@ -0,0 +1,585 @@
+//! Expect-style driver for programs running inside a PTY.
+//!
+//! This spawns a child process in a real pseudoterminal, which is serviced by the regular
+//! [`EventLoop`], and allows scripting it by writing input and waiting for regex matches on the
+//! terminal screen. Since no display is required, it can be used to test interactive programs in
+//! headless environments like CI.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use alacritty_terminal::expect::ExpectBuilder;
+//!
+//! let mut session = ExpectBuilder::new("/bin/sh")
+//!     .timeout(Duration::from_secs(10))
+//!     .env("PS1", "$ ")
+//!     .spawn()
+//!     .unwrap();
+//!
+//! session.expect(r"\$ ").unwrap();
+//! session.send_line("echo $((6 * 7))");
+//! let result = session.expect(r"\d\d").unwrap();
+//! assert_eq!(result.text, "42");
+//!
+//! session.send_line("exit 3");
+//! assert_eq!(session.wait().unwrap(), Some(3));
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{io, mem};
+
+use parking_lot::{Condvar, Mutex};
+use regex_automata::hybrid::BuildError;
+
+use crate::event::{Event, EventListener, WindowSize};
+use crate::event_loop::{EventLoop, EventLoopSender, Msg};
+use crate::grid::Dimensions;
+use crate::index::{Column, Direction, Line, Point};
+use crate::sync::FairMutex;
+use crate::term::search::{RegexIter, RegexSearch};
+use crate::term::{Config, Term, TermSize};
+use crate::{thread, tty};
+
+/// Default time to wait for a pattern before giving up.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default number of columns for the PTY.
+const DEFAULT_COLUMNS: u16 = 80;
+
+/// Default number of visible lines for the PTY.
+const DEFAULT_SCREEN_LINES: u16 = 24;
+
+/// Builder for an [`ExpectSession`].
+#[derive(Debug, Clone)]
+pub struct ExpectBuilder {
+    program: String,
+    args: Vec<String>,
+    working_directory: Option<PathBuf>,
+    env: HashMap<String, String>,
+    columns: u16,
+    screen_lines: u16,
+    config: Config,
+    timeout: Duration,
+}
+
+impl ExpectBuilder {
+    /// Create a builder for running `program` in an 80x24 PTY.
+    ///
+    /// The `TERM` environment variable defaults to `xterm-256color`.
+    pub fn new(program: impl Into<String>) -> Self {
+        let mut env = HashMap::new();
+        env.insert(String::from("TERM"), String::from("xterm-256color"));
+
+        Self {
+            env,
+            program: program.into(),
+            args: Vec::new(),
+            working_directory: None,
+            columns: DEFAULT_COLUMNS,
+            screen_lines: DEFAULT_SCREEN_LINES,
+            config: Config::default(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Add an argument passed to the program.
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Add multiple arguments passed to the program.
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set an environment variable for the program.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the program's working directory.
+    pub fn working_directory(mut self, working_directory: impl Into<PathBuf>) -> Self {
+        self.working_directory = Some(working_directory.into());
+        self
+    }
+
+    /// Set the number of columns.
+    pub fn columns(mut self, columns: u16) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Set the number of visible lines.
+    pub fn screen_lines(mut self, screen_lines: u16) -> Self {
+        self.screen_lines = screen_lines;
+        self
+    }
+
+    /// Replace the terminal configuration.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set the default timeout used by [`ExpectSession::expect`] and [`ExpectSession::wait`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Spawn the program and start processing its output.
+    pub fn spawn(self) -> io::Result<ExpectSession> {
+        let options = tty::Options {
+            shell: Some(tty::Shell::new(self.program, self.args)),
+            working_directory: self.working_directory,
+            env: self.env,
+            hold: true,
+        };
+
+        let window_size = window_size(self.columns, self.screen_lines);
+        let pty = tty::new(&options, window_size, 0)?;
+
+        let shared = Arc::new(Shared::default());
+        let listener = ExpectListener(shared.clone());
+        let size = TermSize::new(self.columns as usize, self.screen_lines as usize);
+        let terminal = Arc::new(FairMutex::new(Term::new(self.config, &size, listener.clone())));
+
+        // Hold the PTY open after exit, so all output is read before the loop terminates.
+        let event_loop = EventLoop::new(terminal.clone(), listener, pty, true, false)?;
+        let sender = event_loop.channel();
+        shared.state.lock().sender = Some(sender.clone());
+
+        let io_thread = event_loop.spawn();
+        let waiter = shared.clone();
+        thread::spawn_named("PTY waiter", move || {
+            // Dropping the event loop's result will also release the PTY.
+            let _ = io_thread.join();
+
+            waiter.state.lock().finished = true;
+            waiter.condvar.notify_all();
+        });
+
+        Ok(ExpectSession { terminal, sender, shared, timeout: self.timeout })
+    }
+}
+
+/// Running child process attached to a terminal.
+pub struct ExpectSession {
+    terminal: Arc<FairMutex<Term<ExpectListener>>>,
+    sender: EventLoopSender,
+    shared: Arc<Shared>,
+    timeout: Duration,
+}
+
+impl ExpectSession {
+    /// Write bytes to the PTY.
+    pub fn send(&self, bytes: impl Into<Cow<'static, [u8]>>) {
+        let bytes = bytes.into();
+        if !bytes.is_empty() {
+            let _ = self.sender.send(Msg::Input(bytes));
+        }
+    }
+
+    /// Write a line of text to the PTY, followed by a carriage return.
+    pub fn send_line(&self, text: &str) {
+        self.send(format!("{text}\r").into_bytes());
+    }
+
+    /// Wait for a regex to match on the visible screen, using the default timeout.
+    pub fn expect(&self, pattern: &str) -> Result<ExpectMatch, ExpectError> {
+        self.expect_timeout(pattern, self.timeout)
+    }
+
+    /// Wait for a regex to match on the visible screen.
+    ///
+    /// The regex is case-insensitive unless it contains an uppercase character. Lines are
+    /// matched individually, unless they are soft-wrapped, and `^`/`$` do not match at line
+    /// boundaries.
+    pub fn expect_timeout(
+        &self,
+        pattern: &str,
+        timeout: Duration,
+    ) -> Result<ExpectMatch, ExpectError> {
+        let mut regex = RegexSearch::new(pattern).map_err(ExpectError::Regex)?;
+        let result = self.wait_for(timeout, |term| {
+            let start = Point::new(Line(0), Column(0));
+            let end = Point::new(term.bottommost_line(), term.last_column());
+            let mut iter = RegexIter::new(start, end, Direction::Right, term, &mut regex);
+            iter.next().map(|m| ExpectMatch {
+                text: term.bounds_to_string(*m.start(), *m.end()),
+                start: *m.start(),
+                end: *m.end(),
+            })
+        });
+
+        result.map_err(|error| error.with_pattern(pattern))
+    }
+
+    /// Wait until `predicate` returns a value, or the timeout is reached.
+    ///
+    /// The predicate is evaluated once immediately and again every time the terminal content
+    /// changes.
+    pub fn wait_for<F, R>(&self, timeout: Duration, mut predicate: F) -> Result<R, ExpectError>
+    where
+        F: FnMut(&Term<ExpectListener>) -> Option<R>,
+    {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.state.lock();
+
+        loop {
+            // Remember the generation before checking, to not miss updates while checking.
+            let generation = state.generation;
+            let finished = state.finished;
+            drop(state);
+
+            if let Some(result) = predicate(&self.terminal.lock()) {
+                return Ok(result);
+            }
+
+            state = self.shared.state.lock();
+            while state.generation == generation && !state.finished {
+                if self.shared.condvar.wait_until(&mut state, deadline).timed_out() {
+                    drop(state);
+                    return Err(ExpectError::Timeout { pattern: None, screen: self.screen_text() });
+                }
+            }
+
+            // Check once more after the child exited, since the PTY was drained before that.
+            if finished {
+                drop(state);
+                return Err(ExpectError::Exited { pattern: None, screen: self.screen_text() });
+            }
+        }
+    }
+
+    /// Wait for the child process to exit, using the default timeout.
+    ///
+    /// Returns the exit code of the child, or [`None`] if it was terminated by a signal.
+    pub fn wait(&self) -> Result<Option<i32>, ExpectError> {
+        self.wait_timeout(self.timeout)
+    }
+
+    /// Wait for the child process to exit.
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<Option<i32>, ExpectError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.state.lock();
+
+        while !state.finished {
+            if self.shared.condvar.wait_until(&mut state, deadline).timed_out() {
+                drop(state);
+                return Err(ExpectError::Timeout { pattern: None, screen: self.screen_text() });
+            }
+        }
+
+        Ok(state.exit_code)
+    }
+
+    /// Whether the child process has exited and all its output was processed.
+    pub fn is_finished(&self) -> bool {
+        self.shared.state.lock().finished
+    }
+
+    /// Resize the terminal and the PTY.
+    pub fn resize(&self, columns: u16, screen_lines: u16) {
+        self.terminal.lock().resize(TermSize::new(columns as usize, screen_lines as usize));
+        let _ = self.sender.send(Msg::Resize(window_size(columns, screen_lines)));
+    }
+
+    /// Text of all visible lines.
+    ///
+    /// Every line which isn't soft-wrapped is terminated by a newline, without any trailing empty
+    /// cells.
+    pub fn screen_text(&self) -> String {
+        let terminal = self.terminal.lock();
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(terminal.bottommost_line(), terminal.last_column());
+        terminal.bounds_to_string(start, end) + "\n"
+    }
+
+    /// Text of a single visible line, without trailing whitespace.
+    ///
+    /// Returns `None` if the line is outside of the visible screen.
+    pub fn line_text(&self, line: Line) -> Option<String> {
+        let terminal = self.terminal.lock();
+        if line < Line(0) || line > terminal.bottommost_line() {
+            return None;
+        }
+
+        let start = Point::new(line, Column(0));
+        let end = Point::new(line, terminal.last_column());
+        Some(terminal.bounds_to_string(start, end).trim_end().to_owned())
+    }
+
+    /// Current cursor position.
+    pub fn cursor(&self) -> Point {
+        self.terminal.lock().grid().cursor.point
+    }
+
+    /// Run a closure with access to the locked terminal.
+    pub fn with_term<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&Term<ExpectListener>) -> R,
+    {
+        f(&self.terminal.lock())
+    }
+
+    /// Remove and return all events emitted since the last call.
+    ///
+    /// This excludes redraw requests and writes to the PTY, which are handled internally.
+    pub fn take_events(&self) -> Vec<Event> {
+        mem::take(&mut self.shared.state.lock().events)
+    }
+
+    /// Assert that a regex matches on the visible screen right now.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the current screen content if the regex does not match.
+    #[track_caller]
+    pub fn assert_screen_matches(&self, pattern: &str) {
+        if let Err(err) = self.expect_timeout(pattern, Duration::ZERO) {
+            panic!("{err}");
+        }
+    }
+
+    /// Assert that a visible line matches the expected text, ignoring trailing whitespace.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the current screen content if the text does not match or the line is outside
+    /// of the visible screen.
+    #[track_caller]
+    pub fn assert_line(&self, line: Line, expected: &str) {
+        match self.line_text(line) {
+            Some(text) if text == expected.trim_end() => (),
+            Some(text) => panic!(
+                "line {} is {:?}, expected {:?}; screen content:\n{}",
+                line.0,
+                text,
+                expected,
+                self.screen_text()
+            ),
+            None => panic!(
+                "line {} is outside of the screen, expected {:?}; screen content:\n{}",
+                line.0,
+                expected,
+                self.screen_text()
+            ),
+        }
+    }
+}
+
+impl Drop for ExpectSession {
+    fn drop(&mut self) {
+        // Terminate the event loop, which will hang up the PTY.
+        let _ = self.sender.send(Msg::Shutdown);
+    }
+}
+
+/// Successful regex match on the terminal screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectMatch {
+    /// Text of the match.
+    pub text: String,
+
+    /// First cell of the match.
+    pub start: Point,
+
+    /// Last cell of the match.
+    pub end: Point,
+}
+
+#[derive(Debug)]
+pub enum ExpectError {
+    /// Invalid regex.
+    Regex(Box<BuildError>),
+
+    /// Timeout was reached without a match.
+    Timeout { pattern: Option<String>, screen: String },
+
+    /// Child exited without a match.
+    Exited { pattern: Option<String>, screen: String },
+}
+
+impl ExpectError {
+    fn with_pattern(self, pattern: &str) -> Self {
+        match self {
+            Self::Timeout { screen, .. } => Self::Timeout { pattern: Some(pattern.into()), screen },
+            Self::Exited { screen, .. } => Self::Exited { pattern: Some(pattern.into()), screen },
+            error => error,
+        }
+    }
+}
+
+impl Display for ExpectError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (reason, pattern, screen) = match self {
+            ExpectError::Regex(err) => return write!(f, "invalid regex: {err}"),
+            ExpectError::Timeout { pattern, screen } => ("timed out", pattern, screen),
+            ExpectError::Exited { pattern, screen } => ("child exited", pattern, screen),
+        };
+
+        match pattern {
+            Some(pattern) => write!(f, "{reason} waiting for {pattern:?}")?,
+            None => write!(f, "{reason}")?,
+        }
+
+        write!(f, "; screen content:\n{screen}")
+    }
+}
+
+impl std::error::Error for ExpectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExpectError::Regex(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Event listener waking up sessions waiting for terminal changes.
+#[derive(Clone)]
+pub struct ExpectListener(Arc<Shared>);
+
+impl EventListener for ExpectListener {
+    fn send_event(&self, event: Event) {
+        let mut state = self.0.state.lock();
+        match event {
+            Event::Wakeup => {
+                state.generation = state.generation.wrapping_add(1);
+                self.0.condvar.notify_all();
+            },
+            // Reply to terminal queries, since there's nobody else to do it.
+            Event::PtyWrite(text) => {
+                if let Some(sender) = &state.sender {
+                    let _ = sender.send(Msg::Input(text.into_bytes().into()));
+                }
+            },
+            Event::ChildExit(status) => {
+                state.exit_code = status.code();
+                state.events.push(event);
+            },
+            Event::MouseCursorDirty => (),
+            event => state.events.push(event),
+        }
+    }
+}
+
+/// State shared between the session, the event loop and the waiter thread.
+#[derive(Default)]
+struct Shared {
+    state: Mutex<SharedState>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct SharedState {
+    /// Counter incremented on every terminal update.
+    generation: u64,
+
+    /// Whether the event loop has terminated.
+    finished: bool,
+
+    exit_code: Option<i32>,
+    sender: Option<EventLoopSender>,
+    events: Vec<Event>,
+}
+
+fn window_size(columns: u16, screen_lines: u16) -> WindowSize {
+    WindowSize { num_lines: screen_lines, num_cols: columns, cell_width: 1, cell_height: 1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell() -> ExpectBuilder {
+        ExpectBuilder::new("/bin/sh").env("PS1", "$ ").timeout(Duration::from_secs(10))
+    }
+
+    #[test]
+    fn expect_output() {
+        let session = shell().spawn().unwrap();
+        session.expect(r"\$ ").unwrap();
+
+        session.send_line("printf 'a%sc\\n' b");
+        let result = session.expect("abc").unwrap();
+        assert_eq!(result.text, "abc");
+        assert_eq!(result.start.column, Column(0));
+
+        session.assert_screen_matches("abc");
+        session.assert_line(result.start.line, "abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "line 50 is outside of the screen")]
+    fn assert_line_outside_screen() {
+        let session = shell().spawn().unwrap();
+        session.expect(r"\$ ").unwrap();
+        session.assert_line(Line(50), "");
+    }
+
+    #[test]
+    fn exit_code() {
+        let session = shell().spawn().unwrap();
+        session.send_line("exit 7");
+        assert_eq!(session.wait().unwrap(), Some(7));
+        assert!(session.is_finished());
+    }
+
+    #[test]
+    fn output_drained_on_exit() {
+        let session = ExpectBuilder::new("/bin/echo").arg("goodbye").spawn().unwrap();
+        assert_eq!(session.wait().unwrap(), Some(0));
+        session.assert_screen_matches("goodbye");
+
+        // Waiting for missing content after exit fails immediately.
+        assert!(matches!(session.expect("missing"), Err(ExpectError::Exited { .. })));
+    }
+
+    #[test]
+    fn timeout() {
+        let session = shell().spawn().unwrap();
+        let result = session.expect_timeout("never printed", Duration::from_millis(100));
+        match result {
+            Err(ExpectError::Timeout { pattern, .. }) => {
+                assert_eq!(pattern.as_deref(), Some("never printed"));
+            },
+            result => panic!("expected timeout, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn resize() {
+        let session = shell().columns(20).spawn().unwrap();
+        session.expect(r"\$ ").unwrap();
+
+        session.resize(33, 10);
+        session.send_line("stty size");
+        session.expect("10 33").unwrap();
+    }
+
+    #[test]
+    fn answers_terminal_queries() {
+        let session = shell().spawn().unwrap();
+        session.expect(r"\$ ").unwrap();
+
+        // Cursor position report is answered, so `dd` receives the escape.
+        session.send_line(r"stty raw -echo; printf '\033[6n'; dd bs=1 count=1 2>/dev/null | od -c");
+        session.expect("033").unwrap();
+    }
+}
@@ -151,6 +151,8 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
     }
 
     /// Update the size of the scrollback history.
+    ///
+    /// Shrinking the history permanently drops the oldest lines beyond the new size.
     pub fn update_history(&mut self, history_size: usize) {
         let current_history_size = self.history_size();
         if current_history_size > history_size {
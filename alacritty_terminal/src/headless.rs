@@ -0,0 +1,295 @@
+//! Headless terminal for embedding and integration testing.
+//!
+//! This provides a [`Term`] without any PTY or renderer attached, which is driven by feeding it
+//! bytes directly. It is intended for testing TUI applications against a real terminal emulator,
+//! by comparing the resulting screen content, cursor and cell attributes.
+//!
+//! The API in this module is considered stable and follows semantic versioning.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use alacritty_terminal::event::Event;
+//! use alacritty_terminal::headless::HeadlessBuilder;
+//! use alacritty_terminal::index::{Column, Line, Point};
+//! use alacritty_terminal::term::cell::Flags;
+//!
+//! let mut terminal = HeadlessBuilder::new().columns(20).screen_lines(2).build();
+//! terminal.feed(b"\x1b]0;hello\x07plain \x1b[1mbold\x1b[0m\r\nline");
+//!
+//! assert_eq!(terminal.screen_text(), "plain bold\nline\n");
+//! assert_eq!(terminal.cursor(), Point::new(Line(1), Column(4)));
+//! assert!(terminal.cell(Point::new(Line(0), Column(6))).flags.contains(Flags::BOLD));
+//! assert!(matches!(&terminal.take_events()[..], [Event::Title(title)] if title == "hello"));
+//! ```
+
+use std::mem;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::event::{Event, EventListener};
+use crate::grid::Dimensions;
+use crate::index::{Column, Line, Point};
+use crate::term::cell::Cell;
+use crate::term::user_var::UserVarParser;
+use crate::term::{Config, Term, TermMode, TermSize};
+use crate::vte::ansi::{self, CursorStyle};
+
+/// Default number of columns for headless terminals.
+const DEFAULT_COLUMNS: usize = 80;
+
+/// Default number of visible lines for headless terminals.
+const DEFAULT_SCREEN_LINES: usize = 24;
+
+/// Builder for a [`Headless`] terminal.
+#[derive(Debug, Clone)]
+pub struct HeadlessBuilder {
+    columns: usize,
+    screen_lines: usize,
+    config: Config,
+}
+
+impl Default for HeadlessBuilder {
+    fn default() -> Self {
+        Self {
+            columns: DEFAULT_COLUMNS,
+            screen_lines: DEFAULT_SCREEN_LINES,
+            config: Config::default(),
+        }
+    }
+}
+
+impl HeadlessBuilder {
+    /// Create a builder for an 80x24 terminal with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of columns.
+    pub fn columns(mut self, columns: usize) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Set the number of visible lines.
+    pub fn screen_lines(mut self, screen_lines: usize) -> Self {
+        self.screen_lines = screen_lines;
+        self
+    }
+
+    /// Set the maximum number of lines in the scrollback history.
+    pub fn scrolling_history(mut self, scrolling_history: usize) -> Self {
+        self.config.scrolling_history = scrolling_history;
+        self
+    }
+
+    /// Replace the terminal configuration.
+    ///
+    /// This will overwrite any previously set scrolling history.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Create the headless terminal.
+    pub fn build(self) -> Headless {
+        let size = TermSize::new(self.columns, self.screen_lines);
+        let listener = EventCollector::default();
+        let term = Term::new(self.config, &size, listener.clone());
+
+        Headless {
+            term,
+            parser: ansi::Processor::new(),
+            user_var_parser: UserVarParser::default(),
+            events: listener,
+        }
+    }
+}
+
+/// Terminal emulator without PTY or display.
+pub struct Headless {
+    term: Term<EventCollector>,
+    parser: ansi::Processor,
+    user_var_parser: UserVarParser,
+    events: EventCollector,
+}
+
+impl Headless {
+    /// Parse bytes as if they were written to the terminal by a child process.
+    ///
+    /// Content inside a synchronized update is only applied once the update is completed, or
+    /// [`Headless::flush`] is called.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.user_var_parser.advance(&mut self.term, *byte);
+            self.parser.advance(&mut self.term, *byte);
+        }
+    }
+
+    /// Apply all content buffered by a pending synchronized update.
+    pub fn flush(&mut self) {
+        if self.parser.sync_bytes_count() > 0 {
+            self.parser.stop_sync(&mut self.term);
+        }
+    }
+
+    /// Resize the terminal.
+    pub fn resize(&mut self, columns: usize, screen_lines: usize) {
+        self.term.resize(TermSize::new(columns, screen_lines));
+    }
+
+    /// Text of all visible lines.
+    ///
+    /// Every line which isn't soft-wrapped is terminated by a newline, without any trailing empty
+    /// cells.
+    pub fn screen_text(&self) -> String {
+        self.lines_text(Line(0), self.term.bottommost_line())
+    }
+
+    /// Text of the scrollback history and all visible lines.
+    pub fn scrollback_text(&self) -> String {
+        self.lines_text(self.term.topmost_line(), self.term.bottommost_line())
+    }
+
+    /// Text of a single line, without trailing whitespace.
+    ///
+    /// Negative lines refer to the scrollback history. Returns `None` if the line is outside of
+    /// the terminal grid.
+    pub fn line_text(&self, line: Line) -> Option<String> {
+        if line < self.term.topmost_line() || line > self.term.bottommost_line() {
+            return None;
+        }
+
+        let text = self.lines_text(line, line);
+        Some(text.trim_end().to_owned())
+    }
+
+    /// Current cursor position.
+    pub fn cursor(&self) -> Point {
+        self.term.grid().cursor.point
+    }
+
+    /// Whether the cursor is visible.
+    pub fn cursor_visible(&self) -> bool {
+        self.term.mode().contains(TermMode::SHOW_CURSOR)
+    }
+
+    /// Current cursor shape and blinking state.
+    pub fn cursor_style(&self) -> CursorStyle {
+        self.term.cursor_style()
+    }
+
+    /// Cell at the specified point, including all its attributes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the point is outside of the terminal grid.
+    pub fn cell(&self, point: Point) -> &Cell {
+        &self.term.grid()[point]
+    }
+
+    /// Active terminal modes.
+    pub fn mode(&self) -> TermMode {
+        *self.term.mode()
+    }
+
+    /// Remove and return all events emitted since the last call.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        mem::take(&mut *self.events.0.lock())
+    }
+
+    /// Underlying terminal.
+    pub fn term(&self) -> &Term<EventCollector> {
+        &self.term
+    }
+
+    /// Mutable access to the underlying terminal.
+    pub fn term_mut(&mut self) -> &mut Term<EventCollector> {
+        &mut self.term
+    }
+
+    /// Text of all lines between `start` and `end`, inclusive.
+    fn lines_text(&self, start: Line, end: Line) -> String {
+        let start = Point::new(start, Column(0));
+        let end = Point::new(end, self.term.last_column());
+        self.term.bounds_to_string(start, end) + "\n"
+    }
+}
+
+/// Event listener storing all terminal events.
+#[derive(Clone, Default)]
+pub struct EventCollector(Arc<Mutex<Vec<Event>>>);
+
+impl EventListener for EventCollector {
+    fn send_event(&self, event: Event) {
+        // Redraw requests are irrelevant without a display.
+        if !matches!(event, Event::Wakeup | Event::MouseCursorDirty) {
+            self.0.lock().push(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::term::cell::Flags;
+    use crate::vte::ansi::{Color, NamedColor};
+
+    #[test]
+    fn feed_and_query() {
+        let mut terminal = HeadlessBuilder::new().columns(10).screen_lines(2).build();
+        terminal.feed(b"a\x1b[31mb\x1b[0m\r\nc");
+
+        assert_eq!(terminal.screen_text(), "ab\nc\n");
+        assert_eq!(terminal.line_text(Line(1)).as_deref(), Some("c"));
+        assert_eq!(terminal.line_text(Line(2)), None);
+        assert_eq!(terminal.cursor(), Point::new(Line(1), Column(1)));
+
+        let cell = terminal.cell(Point::new(Line(0), Column(1)));
+        assert_eq!(cell.fg, Color::Named(NamedColor::Red));
+    }
+
+    #[test]
+    fn scrollback() {
+        let mut terminal =
+            HeadlessBuilder::new().columns(5).screen_lines(2).scrolling_history(10).build();
+        terminal.feed(b"1\r\n2\r\n3\r\n4");
+
+        assert_eq!(terminal.screen_text(), "3\n4\n");
+        assert_eq!(terminal.scrollback_text(), "1\n2\n3\n4\n");
+        assert_eq!(terminal.line_text(Line(-2)).as_deref(), Some("1"));
+        assert_eq!(terminal.line_text(Line(-3)), None);
+    }
+
+    #[test]
+    fn wrapped_lines() {
+        let mut terminal = HeadlessBuilder::new().columns(3).screen_lines(2).build();
+        terminal.feed(b"abcde");
+
+        assert_eq!(terminal.screen_text(), "abcde\n");
+        assert!(terminal.cell(Point::new(Line(0), Column(2))).flags.contains(Flags::WRAPLINE));
+    }
+
+    #[test]
+    fn collects_events() {
+        let mut terminal = HeadlessBuilder::new().build();
+        terminal.feed(b"\x07\x1b[6n");
+
+        let events = terminal.take_events();
+        assert!(matches!(events[0], Event::Bell));
+        assert!(matches!(&events[1], Event::PtyWrite(text) if text == "\x1b[1;1R"));
+        assert!(terminal.take_events().is_empty());
+    }
+
+    #[test]
+    fn synchronized_update() {
+        let mut terminal = HeadlessBuilder::new().columns(5).screen_lines(1).build();
+        terminal.feed(b"\x1b[?2026hsync");
+        assert_eq!(terminal.screen_text(), "\n");
+
+        terminal.flush();
+        assert_eq!(terminal.screen_text(), "sync\n");
+    }
+}
@@ -6,7 +6,10 @@
 
 pub mod event;
 pub mod event_loop;
+#[cfg(unix)]
+pub mod expect;
 pub mod grid;
+pub mod headless;
 pub mod index;
 pub mod selection;
 pub mod sync;
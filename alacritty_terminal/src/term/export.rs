@@ -0,0 +1,686 @@
+//! Export of terminal content as plain text, ANSI escape sequences or HTML.
+
+use std::cmp;
+use std::fmt::Write;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::grid::Dimensions;
+use crate::index::{Column, Line, Point};
+use crate::selection::SelectionRange;
+use crate::term::cell::{Cell, Flags, Hyperlink, LineLength};
+use crate::term::color::Colors;
+use crate::term::Term;
+use crate::vte::ansi::{Color, NamedColor, Rgb};
+
+/// Factor used to derive dim colors for RGB and indexed colors.
+const DIM_FACTOR: f32 = 0.66;
+
+/// Cell flags which affect the exported styling.
+const STYLE_FLAGS: Flags = Flags::INVERSE
+    .union(Flags::BOLD)
+    .union(Flags::ITALIC)
+    .union(Flags::DIM)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT)
+    .union(Flags::ALL_UNDERLINES);
+
+/// Region of the terminal which should be exported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "lowercase"))]
+pub enum ExportRange {
+    /// Lines currently visible in the viewport.
+    #[default]
+    Viewport,
+
+    /// Active selection.
+    Selection,
+
+    /// Entire scrollback history, including the visible screen.
+    Scrollback,
+}
+
+/// Output format of an export.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "lowercase"))]
+pub enum ExportFormat {
+    /// Text without any styling.
+    #[default]
+    Plain,
+
+    /// Text with SGR and OSC 8 escape sequences.
+    Ansi,
+
+    /// Standalone HTML document with inline styles.
+    Html,
+}
+
+impl<T> Term<T> {
+    /// Content covered by an export range.
+    ///
+    /// Returns `None` when the selection is requested without an active selection.
+    pub fn export_range(&self, range: ExportRange) -> Option<SelectionRange> {
+        match range {
+            ExportRange::Viewport => {
+                let display_offset = self.grid.display_offset() as i32;
+                let start = Point::new(Line(-display_offset), Column(0));
+                let end = Point::new(self.bottommost_line() - display_offset, self.last_column());
+                Some(SelectionRange::new(start, end, false))
+            },
+            ExportRange::Selection => self.selection.as_ref().and_then(|s| s.to_range(self)),
+            ExportRange::Scrollback => Some(SelectionRange::new(
+                Point::new(self.topmost_line(), Column(0)),
+                Point::new(self.bottommost_line(), self.last_column()),
+                false,
+            )),
+        }
+    }
+
+    /// Export a terminal region in the requested format.
+    ///
+    /// The `colors` are only used for HTML output, see [`Term::range_to_html`].
+    pub fn export(
+        &self,
+        range: ExportRange,
+        format: ExportFormat,
+        colors: &Colors,
+    ) -> Option<String> {
+        // Plain selections are exported like the clipboard to respect the selection type.
+        if range == ExportRange::Selection && format == ExportFormat::Plain {
+            return self.selection_to_string();
+        }
+
+        let range = self.export_range(range)?;
+        let text = match format {
+            ExportFormat::Plain => self.bounds_to_string(range.start, range.end),
+            ExportFormat::Ansi => self.range_to_ansi(range),
+            ExportFormat::Html => self.range_to_html(range, colors),
+        };
+
+        Some(text)
+    }
+
+    /// Convert a range to text with ANSI escape sequences.
+    ///
+    /// Cell attributes are converted to SGR sequences and hyperlinks to OSC 8 sequences. All
+    /// attributes are reset at the end of every line.
+    pub fn range_to_ansi(&self, range: SelectionRange) -> String {
+        let mut res = String::new();
+        let mut style = CellStyle::default();
+
+        self.visit_range(range, |segment| match segment {
+            Segment::Cell(cell) => {
+                let cell_style = CellStyle::new(cell);
+                if cell_style != style {
+                    cell_style.write_ansi(&style, &mut res);
+                    style = cell_style;
+                }
+                push_cell_text(&mut res, cell);
+            },
+            Segment::Newline => {
+                if style != CellStyle::default() {
+                    CellStyle::default().write_ansi(&style, &mut res);
+                    style = CellStyle::default();
+                }
+                res.push('\n');
+            },
+        });
+
+        if style != CellStyle::default() {
+            CellStyle::default().write_ansi(&style, &mut res);
+        }
+
+        res
+    }
+
+    /// Convert a range to a standalone HTML document.
+    ///
+    /// Colors modified by escape sequences take precedence over `colors`, with the default xterm
+    /// palette being used for all colors which are set in neither.
+    pub fn range_to_html(&self, range: SelectionRange, colors: &Colors) -> String {
+        let palette = Palette { colors, overrides: &self.colors };
+        let foreground = palette.rgb(NamedColor::Foreground as usize);
+        let background = palette.rgb(NamedColor::Background as usize);
+
+        let mut res = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta \
+             charset=\"utf-8\">\n<title>Alacritty</title>\n</head>\n",
+        );
+        let _ = write!(
+            res,
+            "<body style=\"margin:0;background-color:{};\">\n<pre \
+             style=\"margin:0;padding:0.5em;color:{};background-color:{};font-family:monospace;\">",
+            hex(background),
+            hex(foreground),
+            hex(background),
+        );
+
+        let mut run = String::new();
+        let mut style = CellStyle::default();
+        let flush = |res: &mut String, run: &mut String, style: &CellStyle| {
+            if !run.is_empty() {
+                style.write_html(&palette, run, res);
+                run.clear();
+            }
+        };
+
+        self.visit_range(range, |segment| match segment {
+            Segment::Cell(cell) => {
+                let cell_style = CellStyle::new(cell);
+                if cell_style != style {
+                    flush(&mut res, &mut run, &style);
+                    style = cell_style;
+                }
+                push_cell_text(&mut run, cell);
+            },
+            Segment::Newline => {
+                flush(&mut res, &mut run, &style);
+                res.push('\n');
+            },
+        });
+        flush(&mut res, &mut run, &style);
+
+        res.push_str("</pre>\n</body>\n</html>\n");
+
+        res
+    }
+
+    /// Visit all cells in a range.
+    ///
+    /// This follows the same rules as [`Term::selection_to_string`], skipping wide char spacers
+    /// and cells after the end of a line, while adding newlines between unwrapped lines. Block
+    /// ranges only include the selected columns of every line.
+    fn visit_range<F>(&self, range: SelectionRange, mut f: F)
+    where
+        F: FnMut(Segment<'_>),
+    {
+        let SelectionRange { start, end, is_block } = range;
+        let start_line = cmp::max(start.line, self.topmost_line());
+        let end_line = cmp::min(end.line, self.bottommost_line());
+
+        for line in (start_line.0..=end_line.0).map(Line::from) {
+            let grid_line = &self.grid[line];
+
+            let mut start_col =
+                if is_block || line == start.line { start.column } else { Column(0) };
+            let end_col =
+                if is_block || line == end.line { end.column } else { self.last_column() };
+            let line_length = cmp::min(grid_line.line_length(), end_col + 1);
+
+            // Include wide char when trailing spacer is selected.
+            if grid_line[start_col].flags.contains(Flags::WIDE_CHAR_SPACER) {
+                start_col -= 1;
+            }
+
+            let mut tab_mode = false;
+            for column in (start_col.0..line_length.0).map(Column::from) {
+                let cell = &grid_line[column];
+
+                // Skip over cells until next tab-stop once a tab was found.
+                if tab_mode {
+                    if self.tabs[column] || cell.c != ' ' {
+                        tab_mode = false;
+                    } else {
+                        continue;
+                    }
+                }
+
+                if cell.c == '\t' {
+                    tab_mode = true;
+                }
+
+                if !cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+                {
+                    f(Segment::Cell(cell));
+                }
+            }
+
+            let wrapped =
+                line_length.0 != 0 && grid_line[line_length - 1].flags.contains(Flags::WRAPLINE);
+            if line != end_line && (is_block || (end_col >= self.last_column() && !wrapped)) {
+                f(Segment::Newline);
+            }
+        }
+    }
+}
+
+/// Element of the exported terminal content.
+enum Segment<'a> {
+    Cell(&'a Cell),
+    Newline,
+}
+
+/// Attributes of a cell relevant for export.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CellStyle {
+    fg: Color,
+    bg: Color,
+    flags: Flags,
+    underline_color: Option<Color>,
+    hyperlink: Option<Hyperlink>,
+}
+
+impl Default for CellStyle {
+    fn default() -> Self {
+        Self {
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            flags: Flags::empty(),
+            underline_color: None,
+            hyperlink: None,
+        }
+    }
+}
+
+impl CellStyle {
+    fn new(cell: &Cell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            flags: cell.flags & STYLE_FLAGS,
+            underline_color: cell.underline_color(),
+            hyperlink: cell.hyperlink(),
+        }
+    }
+
+    /// Write the escape sequences required to switch from `previous` to this style.
+    fn write_ansi(&self, previous: &CellStyle, res: &mut String) {
+        if self.hyperlink != previous.hyperlink {
+            match &self.hyperlink {
+                Some(hyperlink) => {
+                    let _ = write!(res, "\x1b]8;id={};{}\x1b\\", hyperlink.id(), hyperlink.uri());
+                },
+                None => res.push_str("\x1b]8;;\x1b\\"),
+            }
+        }
+
+        let (previous_sgr, mut sgr) = (previous.without_hyperlink(), self.without_hyperlink());
+        if previous_sgr == sgr {
+            return;
+        }
+
+        // Always reset first, to avoid tracking which attributes need to be removed.
+        res.push_str("\x1b[0");
+
+        for (flag, param) in [
+            (Flags::BOLD, "1"),
+            (Flags::DIM, "2"),
+            (Flags::ITALIC, "3"),
+            (Flags::UNDERLINE, "4"),
+            (Flags::DOUBLE_UNDERLINE, "4:2"),
+            (Flags::UNDERCURL, "4:3"),
+            (Flags::DOTTED_UNDERLINE, "4:4"),
+            (Flags::DASHED_UNDERLINE, "4:5"),
+            (Flags::INVERSE, "7"),
+            (Flags::HIDDEN, "8"),
+            (Flags::STRIKEOUT, "9"),
+        ] {
+            if sgr.flags.contains(flag) {
+                res.push(';');
+                res.push_str(param);
+            }
+        }
+        sgr.flags = Flags::empty();
+
+        write_sgr_color(res, sgr.fg, 30, 90, 38);
+        write_sgr_color(res, sgr.bg, 40, 100, 48);
+        if let Some(color) = sgr.underline_color {
+            write_sgr_color(res, color, 0, 0, 58);
+        }
+
+        res.push('m');
+    }
+
+    /// Write text wrapped in an HTML element with this style.
+    fn write_html(&self, palette: &Palette<'_>, text: &str, res: &mut String) {
+        let default_style = *self == CellStyle::default();
+
+        if let Some(hyperlink) = &self.hyperlink {
+            let _ = write!(res, "<a href=\"{}\">", escape_html(hyperlink.uri()));
+        }
+
+        if !default_style {
+            let mut fg = palette.fg(self.fg, self.flags);
+            let mut bg = palette.bg(self.bg);
+            if self.flags.contains(Flags::INVERSE) {
+                std::mem::swap(&mut fg, &mut bg);
+            }
+            if self.flags.contains(Flags::HIDDEN) {
+                fg = bg;
+            }
+
+            let _ = write!(res, "<span style=\"color:{};", hex(fg));
+            if self.bg != Color::Named(NamedColor::Background)
+                || self.flags.contains(Flags::INVERSE)
+            {
+                let _ = write!(res, "background-color:{};", hex(bg));
+            }
+            if self.flags.contains(Flags::BOLD) {
+                res.push_str("font-weight:bold;");
+            }
+            if self.flags.contains(Flags::ITALIC) {
+                res.push_str("font-style:italic;");
+            }
+
+            let underline = self.flags.intersects(Flags::ALL_UNDERLINES);
+            match (underline, self.flags.contains(Flags::STRIKEOUT)) {
+                (true, true) => res.push_str("text-decoration-line:underline line-through;"),
+                (true, false) => res.push_str("text-decoration-line:underline;"),
+                (false, true) => res.push_str("text-decoration-line:line-through;"),
+                (false, false) => (),
+            }
+
+            let decoration_style = if self.flags.contains(Flags::DOUBLE_UNDERLINE) {
+                Some("double")
+            } else if self.flags.contains(Flags::UNDERCURL) {
+                Some("wavy")
+            } else if self.flags.contains(Flags::DOTTED_UNDERLINE) {
+                Some("dotted")
+            } else if self.flags.contains(Flags::DASHED_UNDERLINE) {
+                Some("dashed")
+            } else {
+                None
+            };
+            if let Some(decoration_style) = decoration_style {
+                let _ = write!(res, "text-decoration-style:{decoration_style};");
+            }
+
+            if let Some(color) = self.underline_color.filter(|_| underline) {
+                let _ =
+                    write!(res, "text-decoration-color:{};", hex(palette.fg(color, self.flags)));
+            }
+
+            res.push_str("\">");
+        }
+
+        res.push_str(&escape_html(text));
+
+        if !default_style {
+            res.push_str("</span>");
+        }
+
+        if self.hyperlink.is_some() {
+            res.push_str("</a>");
+        }
+    }
+
+    fn without_hyperlink(&self) -> Self {
+        Self { hyperlink: None, ..self.clone() }
+    }
+}
+
+/// Color lookup for HTML export.
+struct Palette<'a> {
+    colors: &'a Colors,
+    overrides: &'a Colors,
+}
+
+impl Palette<'_> {
+    /// Get the RGB value for a color index.
+    fn rgb(&self, index: usize) -> Rgb {
+        self.overrides[index].or(self.colors[index]).unwrap_or_else(|| default_color(index))
+    }
+
+    /// Get the RGB value for a foreground color, respecting the dim flag.
+    fn fg(&self, color: Color, flags: Flags) -> Rgb {
+        let dim = flags.contains(Flags::DIM);
+        match color {
+            Color::Spec(rgb) if dim => rgb * DIM_FACTOR,
+            Color::Spec(rgb) => rgb,
+            Color::Named(named) if dim => self.rgb(named.to_dim() as usize),
+            Color::Named(named) => self.rgb(named as usize),
+            Color::Indexed(index @ 0..=7) if dim => {
+                self.rgb(NamedColor::DimBlack as usize + index as usize)
+            },
+            Color::Indexed(index) if dim => self.rgb(index as usize) * DIM_FACTOR,
+            Color::Indexed(index) => self.rgb(index as usize),
+        }
+    }
+
+    /// Get the RGB value for a background color.
+    fn bg(&self, color: Color) -> Rgb {
+        match color {
+            Color::Spec(rgb) => rgb,
+            Color::Named(named) => self.rgb(named as usize),
+            Color::Indexed(index) => self.rgb(index as usize),
+        }
+    }
+}
+
+/// Default xterm color for a color index.
+fn default_color(index: usize) -> Rgb {
+    const NAMED: [(u8, u8, u8); 16] = [
+        (0x00, 0x00, 0x00),
+        (0xcd, 0x00, 0x00),
+        (0x00, 0xcd, 0x00),
+        (0xcd, 0xcd, 0x00),
+        (0x00, 0x00, 0xee),
+        (0xcd, 0x00, 0xcd),
+        (0x00, 0xcd, 0xcd),
+        (0xe5, 0xe5, 0xe5),
+        (0x7f, 0x7f, 0x7f),
+        (0xff, 0x00, 0x00),
+        (0x00, 0xff, 0x00),
+        (0xff, 0xff, 0x00),
+        (0x5c, 0x5c, 0xff),
+        (0xff, 0x00, 0xff),
+        (0x00, 0xff, 0xff),
+        (0xff, 0xff, 0xff),
+    ];
+
+    let cube_component = |value: usize| if value == 0 { 0 } else { value as u8 * 40 + 55 };
+
+    match index {
+        0..=15 => {
+            let (r, g, b) = NAMED[index];
+            Rgb { r, g, b }
+        },
+        16..=231 => {
+            let index = index - 16;
+            Rgb {
+                r: cube_component(index / 36),
+                g: cube_component((index / 6) % 6),
+                b: cube_component(index % 6),
+            }
+        },
+        232..=255 => {
+            let value = (index - 232) as u8 * 10 + 8;
+            Rgb { r: value, g: value, b: value }
+        },
+        _ if index == NamedColor::Background as usize => Rgb { r: 0, g: 0, b: 0 },
+        _ if index == NamedColor::DimForeground as usize => default_color(7) * DIM_FACTOR,
+        _ if index >= NamedColor::DimBlack as usize && index <= NamedColor::DimWhite as usize => {
+            default_color(index - NamedColor::DimBlack as usize) * DIM_FACTOR
+        },
+        _ if index == NamedColor::BrightForeground as usize => default_color(15),
+        _ => default_color(7),
+    }
+}
+
+/// Write the SGR parameters for a color.
+///
+/// Named colors are only written for the base 16 colors, with all other named colors being
+/// represented by the terminal's default.
+fn write_sgr_color(res: &mut String, color: Color, normal: u8, bright: u8, extended: u8) {
+    let index = match color {
+        Color::Spec(Rgb { r, g, b }) => {
+            let _ = write!(res, ";{extended}:2::{r}:{g}:{b}");
+            return;
+        },
+        Color::Indexed(index) => index,
+        Color::Named(named) if named <= NamedColor::BrightWhite => named as u8,
+        // Dim colors map to their normal variant, since dim is a separate attribute.
+        Color::Named(named) if (NamedColor::DimBlack..=NamedColor::DimWhite).contains(&named) => {
+            (named as usize - NamedColor::DimBlack as usize) as u8
+        },
+        Color::Named(_) => return,
+    };
+
+    if normal != 0 && index < 8 {
+        let _ = write!(res, ";{}", normal + index);
+    } else if bright != 0 && index < 16 {
+        let _ = write!(res, ";{}", bright + index - 8);
+    } else {
+        let _ = write!(res, ";{extended}:5:{index}");
+    }
+}
+
+/// Push a cell's characters, including its zerowidth characters.
+fn push_cell_text(res: &mut String, cell: &Cell) {
+    res.push(cell.c);
+    for c in cell.zerowidth().into_iter().flatten() {
+        res.push(*c);
+    }
+}
+
+/// Hex notation of an RGB color.
+fn hex(rgb: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb.r, rgb.g, rgb.b)
+}
+
+/// Escape text for use in HTML.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::index::Side;
+    use crate::selection::{Selection, SelectionType};
+    use crate::term::test::mock_term;
+
+    #[test]
+    fn plain_viewport() {
+        #[rustfmt::skip]
+        let term = mock_term("\
+            hello\r\n\
+            world\
+        ");
+
+        let text = term.export(ExportRange::Viewport, ExportFormat::Plain, &Colors::default());
+        assert_eq!(text.as_deref(), Some("hello\nworld"));
+    }
+
+    #[test]
+    fn selection_without_selection() {
+        let term = mock_term("hello");
+        let colors = Colors::default();
+        assert_eq!(term.export(ExportRange::Selection, ExportFormat::Ansi, &colors), None);
+    }
+
+    #[test]
+    fn block_selection() {
+        let mut term = mock_term("abcd\r\nefgh\r\nijkl");
+        term.grid[Line(1)][Column(2)].flags.insert(Flags::BOLD);
+
+        let start = Point::new(Line(0), Column(1));
+        let end = Point::new(Line(2), Column(2));
+        let mut selection = Selection::new(SelectionType::Block, start, Side::Left);
+        selection.update(end, Side::Right);
+        term.selection = Some(selection);
+
+        let colors = Colors::default();
+        let text = term.export(ExportRange::Selection, ExportFormat::Ansi, &colors);
+        assert_eq!(text.as_deref(), Some("bc\nf\x1b[0;1mg\x1b[0m\njk"));
+
+        let html = term.export(ExportRange::Selection, ExportFormat::Html, &colors).unwrap();
+        assert!(html.contains(">bc\nf<span "), "{html}");
+        assert!(html.contains(">g</span>\njk</pre>"), "{html}");
+    }
+
+    #[test]
+    fn ansi_attributes() {
+        let mut term = mock_term("abc\r\ndef");
+        term.grid[Line(0)][Column(1)].fg = Color::Named(NamedColor::Red);
+        term.grid[Line(0)][Column(1)].flags.insert(Flags::BOLD);
+        term.grid[Line(1)][Column(0)].bg = Color::Indexed(200);
+        term.grid[Line(1)][Column(1)].fg = Color::Spec(Rgb { r: 1, g: 2, b: 3 });
+
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(1), Column(2));
+        assert_eq!(
+            term.range_to_ansi(SelectionRange::new(start, end, false)),
+            "a\x1b[0;1;31mb\x1b[0mc\n\x1b[0;48:5:200md\x1b[0;38:2::1:2:3me\x1b[0mf"
+        );
+    }
+
+    #[test]
+    fn ansi_hyperlink() {
+        let mut term = mock_term("link");
+        let hyperlink = Hyperlink::new(Some("1"), String::from("https://alacritty.org"));
+        term.grid[Line(0)][Column(1)].set_hyperlink(Some(hyperlink.clone()));
+        term.grid[Line(0)][Column(2)].set_hyperlink(Some(hyperlink));
+
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(3));
+        assert_eq!(
+            term.range_to_ansi(SelectionRange::new(start, end, false)),
+            "l\x1b]8;id=1;https://alacritty.org\x1b\\in\x1b]8;;\x1b\\k"
+        );
+    }
+
+    #[test]
+    fn ansi_wrapped_line() {
+        let mut term = mock_term("hel\nlo\r\nabc");
+        term.grid[Line(1)][Column(0)].flags.insert(Flags::ITALIC);
+
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(2), Column(2));
+        assert_eq!(
+            term.range_to_ansi(SelectionRange::new(start, end, false)),
+            "hel\x1b[0;3ml\x1b[0mo\nabc"
+        );
+    }
+
+    #[test]
+    fn html_escapes_and_styles() {
+        let mut term = mock_term("<a&b>");
+        term.grid[Line(0)][Column(1)].fg = Color::Named(NamedColor::Green);
+        term.grid[Line(0)][Column(1)].flags.insert(Flags::UNDERLINE);
+
+        let mut colors = Colors::default();
+        colors[NamedColor::Green] = Some(Rgb { r: 0, g: 0xff, b: 0 });
+
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(4));
+        let html = term.range_to_html(SelectionRange::new(start, end, false), &colors);
+
+        let expected = "&lt;<span style=\"color:#00ff00;text-decoration-line:underline;\">a</\
+                        span>&amp;b&gt;</pre>";
+        assert!(html.contains(expected), "{html}");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+    }
+
+    #[test]
+    fn html_inverse_and_dim() {
+        let mut term = mock_term("ab");
+        term.grid[Line(0)][Column(0)].flags.insert(Flags::INVERSE);
+        term.grid[Line(0)][Column(1)].fg = Color::Spec(Rgb { r: 100, g: 100, b: 100 });
+        term.grid[Line(0)][Column(1)].flags.insert(Flags::DIM);
+
+        let mut colors = Colors::default();
+        colors[NamedColor::Foreground] = Some(Rgb { r: 0xff, g: 0xff, b: 0xff });
+        colors[NamedColor::Background] = Some(Rgb { r: 0x10, g: 0x10, b: 0x10 });
+
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(1));
+        let html = term.range_to_html(SelectionRange::new(start, end, false), &colors);
+
+        assert!(html.contains("<span style=\"color:#101010;background-color:#ffffff;\">a</span>"));
+        assert!(html.contains("<span style=\"color:#424242;\">b</span>"));
+    }
+}
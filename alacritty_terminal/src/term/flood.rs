@@ -0,0 +1,70 @@
+//! Flood protection configuration and statistics.
+//!
+//! The flood detection itself is performed by the event loop, see
+//! [`crate::event_loop::flood`].
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Default throughput in bytes per second above which output is considered a flood.
+const DEFAULT_THRESHOLD: usize = 16 * 1024 * 1024;
+
+/// Flood protection configuration.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FloodProtection {
+    /// PTY throughput in bytes per second above which output is considered a flood.
+    ///
+    /// Flood protection is disabled when this is `0`.
+    pub threshold: usize,
+
+    /// Maximum number of scrollback lines kept while the terminal is flooded.
+    ///
+    /// Lines beyond this limit are dropped when the flood starts and are not restored afterwards,
+    /// only the history capacity returns to its configured size.
+    pub history_limit: Option<usize>,
+}
+
+impl Default for FloodProtection {
+    fn default() -> Self {
+        Self { threshold: DEFAULT_THRESHOLD, history_limit: None }
+    }
+}
+
+/// Throughput and redraw statistics of the PTY output.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct FloodStats {
+    pub(crate) throughput: usize,
+    pub(crate) dropped_frames: u64,
+    pub(crate) frame_pending: bool,
+    pub(crate) flooded: bool,
+}
+
+impl FloodStats {
+    /// PTY throughput in bytes per second.
+    #[inline]
+    pub fn throughput(&self) -> usize {
+        self.throughput
+    }
+
+    /// Number of redraws which were skipped due to output floods.
+    #[inline]
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
+    }
+
+    /// Whether the terminal is currently flooded.
+    #[inline]
+    pub fn is_flooded(&self) -> bool {
+        self.flooded
+    }
+
+    /// Mark the last requested redraw as rendered.
+    ///
+    /// Frames which are requested but not rendered before the next PTY read indicate that the
+    /// renderer can't keep up with the output.
+    #[inline]
+    pub fn frame_rendered(&mut self) {
+        self.frame_pending = false;
+    }
+}
@@ -0,0 +1,209 @@
+//! Vi mode marks and jump list.
+//!
+//! Marks are anchored to grid lines, so they move with the content while it scrolls and are
+//! removed once their line is cleared or rotated out of the scrollback history.
+
+use std::ops::Range;
+
+use crate::index::{Line, Point};
+
+/// Maximum number of positions in the jump list.
+const MAX_JUMPS: usize = 100;
+
+/// Named marks and the jump list of one grid.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct Marks {
+    /// Marks `a` to `z`.
+    named: [Option<Point>; 26],
+
+    /// Positions before large motions, oldest first.
+    jumps: Vec<Point>,
+
+    /// Current position in the jump list, equal to its length while not walking it.
+    jump_index: usize,
+}
+
+impl Marks {
+    /// Set a mark.
+    ///
+    /// Returns `false` if `name` is not a valid mark name.
+    pub fn set(&mut self, name: char, point: Point) -> bool {
+        match Self::index(name) {
+            Some(index) => {
+                self.named[index] = Some(point);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Get the position of a mark.
+    pub fn get(&self, name: char) -> Option<Point> {
+        self.named[Self::index(name)?]
+    }
+
+    /// Iterate over all set marks.
+    pub fn iter(&self) -> impl Iterator<Item = (char, Point)> + '_ {
+        let names = ('a'..='z').zip(&self.named);
+        names.filter_map(|(name, point)| point.map(|point| (name, point)))
+    }
+
+    /// Record the position before a large motion.
+    ///
+    /// All positions after the current position in the jump list are discarded.
+    pub fn record_jump(&mut self, point: Point) {
+        self.jumps.truncate(self.jump_index + 1);
+
+        // Avoid consecutive jumps from the same line.
+        if self.jumps.last().map_or(true, |last| last.line != point.line) {
+            self.jumps.push(point);
+        }
+
+        if self.jumps.len() > MAX_JUMPS {
+            self.jumps.remove(0);
+        }
+
+        self.jump_index = self.jumps.len();
+    }
+
+    /// Move back in the jump list.
+    ///
+    /// The `current` position is recorded when starting to walk the jump list, so it is possible
+    /// to get back to it with [`Self::jump_forward`].
+    pub fn jump_back(&mut self, current: Point) -> Option<Point> {
+        if self.jump_index == 0 {
+            return None;
+        }
+
+        if self.jump_index == self.jumps.len() {
+            self.jumps.push(current);
+        }
+
+        self.jump_index -= 1;
+        Some(self.jumps[self.jump_index])
+    }
+
+    /// Move forward in the jump list.
+    pub fn jump_forward(&mut self) -> Option<Point> {
+        if self.jump_index + 1 >= self.jumps.len() {
+            return None;
+        }
+
+        self.jump_index += 1;
+        Some(self.jumps[self.jump_index])
+    }
+
+    /// Remove all marks and jumps.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Move all positions in a region by `delta` lines up.
+    ///
+    /// Positions which leave the region are removed. A region starting at the top of the screen
+    /// includes the scrollback history, in which case positions above `topmost_line` are removed.
+    pub(crate) fn rotate(&mut self, region: &Range<Line>, delta: i32, topmost_line: Line) {
+        let in_region =
+            |line: Line| (line >= region.start || region.start == 0) && line < region.end;
+
+        self.retain(|point| {
+            if !in_region(point.line) {
+                return true;
+            }
+
+            point.line -= delta;
+
+            let top = if region.start == 0 { topmost_line } else { region.start };
+            point.line >= top && point.line < region.end
+        });
+    }
+
+    /// Remove all positions within a range of lines.
+    pub(crate) fn remove_lines(&mut self, lines: Range<Line>) {
+        self.retain(|point| !lines.contains(&point.line));
+    }
+
+    /// Update every position, removing all positions for which `f` returns `false`.
+    fn retain<F: FnMut(&mut Point) -> bool>(&mut self, mut f: F) {
+        for mark in &mut self.named {
+            if mark.as_mut().is_some_and(|point| !f(point)) {
+                *mark = None;
+            }
+        }
+
+        let mut index = 0;
+        let jump_index = self.jump_index;
+        self.jumps.retain_mut(|point| {
+            let retain = f(point);
+            if !retain && index < jump_index {
+                self.jump_index -= 1;
+            }
+            index += 1;
+            retain
+        });
+        self.jump_index = self.jump_index.min(self.jumps.len());
+    }
+
+    /// Index of a named mark.
+    fn index(name: char) -> Option<usize> {
+        name.is_ascii_lowercase().then(|| name as usize - 'a' as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::index::Column;
+
+    #[test]
+    fn rotate_marks() {
+        let mut marks = Marks::default();
+        marks.set('a', Point::new(Line(-5), Column(0)));
+        marks.set('b', Point::new(Line(2), Column(3)));
+        marks.set('c', Point::new(Line(8), Column(0)));
+        assert!(!marks.set('A', Point::new(Line(0), Column(0))));
+
+        // Scrolling the entire screen moves marks into the history.
+        marks.rotate(&(Line(0)..Line(10)), 3, Line(-6));
+        assert_eq!(marks.get('a'), None);
+        assert_eq!(marks.get('b'), Some(Point::new(Line(-1), Column(3))));
+        assert_eq!(marks.get('c'), Some(Point::new(Line(5), Column(0))));
+
+        // Scrolling a region removes marks leaving it.
+        marks.rotate(&(Line(4)..Line(10)), -5, Line(-6));
+        assert_eq!(marks.get('b'), Some(Point::new(Line(-1), Column(3))));
+        assert_eq!(marks.get('c'), None);
+
+        assert_eq!(marks.iter().collect::<Vec<_>>(), vec![('b', Point::new(Line(-1), Column(3)))]);
+    }
+
+    #[test]
+    fn jump_list() {
+        let point = |line| Point::new(Line(line), Column(0));
+
+        let mut marks = Marks::default();
+        marks.record_jump(point(1));
+        marks.record_jump(point(2));
+
+        assert_eq!(marks.jump_back(point(3)), Some(point(2)));
+        assert_eq!(marks.jump_back(point(2)), Some(point(1)));
+        assert_eq!(marks.jump_back(point(1)), None);
+        assert_eq!(marks.jump_forward(), Some(point(2)));
+        assert_eq!(marks.jump_forward(), Some(point(3)));
+        assert_eq!(marks.jump_forward(), None);
+
+        // Removed jumps keep the position in the jump list.
+        marks.jump_back(point(3));
+        marks.remove_lines(Line(1)..Line(2));
+        assert_eq!(marks.jump_back(point(2)), None);
+        assert_eq!(marks.jump_forward(), Some(point(3)));
+
+        // Recording a jump discards the forward history.
+        marks.jump_back(point(3));
+        marks.record_jump(point(4));
+        assert_eq!(marks.jump_forward(), None);
+        assert_eq!(marks.jump_back(point(5)), Some(point(4)));
+        assert_eq!(marks.jump_back(point(4)), Some(point(2)));
+    }
+}
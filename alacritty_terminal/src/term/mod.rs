@@ -1,5 +1,6 @@
 //! Exports the `Term` type which is a high-level API for the Grid.
 
+use std::collections::{HashMap, VecDeque};
 use std::ops::{Index, IndexMut, Range};
 use std::sync::Arc;
 use std::{cmp, mem, ptr, slice, str};
@@ -19,6 +20,8 @@ use crate::index::{self, Boundary, Column, Direction, Line, Point, Side};
 use crate::selection::{Selection, SelectionRange, SelectionType};
 use crate::term::cell::{Cell, Flags, LineLength};
 use crate::term::color::Colors;
+use crate::term::flood::{FloodProtection, FloodStats};
+use crate::term::marks::Marks;
 use crate::vi_mode::{ViModeCursor, ViMotion};
 use crate::vte::ansi::{
     self, Attr, CharsetIndex, Color, CursorShape, CursorStyle, Handler, Hyperlink, KeyboardModes,
@@ -28,7 +31,11 @@ use crate::vte::ansi::{
 
 pub mod cell;
 pub mod color;
+pub mod export;
+pub mod flood;
+pub mod marks;
 pub mod search;
+pub mod user_var;
 
 /// Minimum number of columns.
 ///
@@ -44,6 +51,12 @@ const TITLE_STACK_MAX_DEPTH: usize = 4096;
 /// Default semantic escape characters.
 pub const SEMANTIC_ESCAPE_CHARS: &str = ",│`|:\"' ()[]{}<>\t";
 
+/// Maximum number of query replies kept while queries are held.
+const MAX_HELD_REPLIES: usize = 64;
+
+/// Maximum number of completed lines kept until they are collected.
+const MAX_COMPLETED_LINES: usize = 1024;
+
 /// Max size of the keyboard modes.
 const KEYBOARD_MODE_STACK_MAX_DEPTH: usize = TITLE_STACK_MAX_DEPTH;
 
@@ -316,6 +329,27 @@ pub struct Term<T> {
     /// term is set.
     title_stack: Vec<Option<String>>,
 
+    /// Variables set through `OSC 1337 ; SetUserVar`.
+    user_vars: HashMap<String, String>,
+
+    /// Vi mode marks of the active grid.
+    marks: Marks,
+
+    /// Vi mode marks of the inactive grid.
+    inactive_marks: Marks,
+
+    /// Escape sequences which are ignored.
+    blocked_escapes: EscapeCategories,
+
+    /// Whether replies to blocked queries are kept until queries are allowed or denied.
+    hold_queries: bool,
+
+    /// Replies to queries which were blocked while queries were held.
+    held_replies: Vec<Event>,
+
+    /// Escape sequences blocked since the last report.
+    blocked_report: BlockedEscapes,
+
     /// The stack for the keyboard modes.
     keyboard_mode_stack: Vec<KeyboardModes>,
 
@@ -325,8 +359,17 @@ pub struct Term<T> {
     /// Information about damaged cells.
     damage: TermDamageState,
 
+    /// Number of lines scrolled out of the top of the screen, wrapping around on overflow.
+    scrolled_lines: usize,
+
+    /// Text of lines completed since they were last collected.
+    completed_lines: VecDeque<String>,
+
+    /// PTY throughput and redraw statistics.
+    pub(crate) flood_stats: FloodStats,
+
     /// Config directly for the terminal.
-    config: Config,
+    pub(crate) config: Config,
 }
 
 /// Configuration options for the [`Term`].
@@ -351,6 +394,12 @@ pub struct Config {
 
     /// OSC52 support mode.
     pub osc52: Osc52,
+
+    /// Protection against PTY output floods.
+    pub flood_protection: FloodProtection,
+
+    /// Keep the text of lines completed by a linefeed, see [`Term::take_completed_lines`].
+    pub track_completed_lines: bool,
 }
 
 impl Default for Config {
@@ -362,6 +411,8 @@ impl Default for Config {
             vi_mode_cursor_style: Default::default(),
             kitty_keyboard: Default::default(),
             osc52: Default::default(),
+            flood_protection: Default::default(),
+            track_completed_lines: Default::default(),
         }
     }
 }
@@ -384,6 +435,50 @@ pub enum Osc52 {
     CopyPaste,
 }
 
+bitflags! {
+    /// Categories of escape sequences which can be blocked.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct EscapeCategories: u8 {
+        /// Window title changes.
+        const TITLE      = 0b0000_0001;
+        /// Palette color changes.
+        const COLORS     = 0b0000_0010;
+        /// Clipboard access through OSC 52.
+        const CLIPBOARD  = 0b0000_0100;
+        /// Hyperlinks through OSC 8.
+        const HYPERLINKS = 0b0000_1000;
+        /// Reports of the terminal state, like device attributes or colors.
+        const QUERIES    = 0b0001_0000;
+        /// User variables through OSC 1337.
+        const USER_VARS  = 0b0010_0000;
+    }
+}
+
+impl EscapeCategories {
+    /// Human readable name of a single category.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::TITLE => "title",
+            Self::COLORS => "colors",
+            Self::CLIPBOARD => "clipboard",
+            Self::HYPERLINKS => "hyperlinks",
+            Self::QUERIES => "queries",
+            Self::USER_VARS => "user_vars",
+            _ => "escape sequences",
+        }
+    }
+}
+
+/// Escape sequences which were blocked since they were last reported.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BlockedEscapes {
+    /// Categories of the blocked escape sequences.
+    pub categories: EscapeCategories,
+
+    /// Number of blocked escape sequences.
+    pub count: usize,
+}
+
 impl<T> Term<T> {
     #[inline]
     pub fn scroll_display(&mut self, scroll: Scroll)
@@ -436,10 +531,20 @@ impl<T> Term<T> {
             is_focused: true,
             title: None,
             title_stack: Default::default(),
+            user_vars: Default::default(),
+            marks: Default::default(),
+            inactive_marks: Default::default(),
+            blocked_escapes: Default::default(),
+            hold_queries: Default::default(),
+            held_replies: Default::default(),
+            blocked_report: Default::default(),
             keyboard_mode_stack: Default::default(),
             inactive_keyboard_mode_stack: Default::default(),
             selection: None,
             damage,
+            scrolled_lines: 0,
+            completed_lines: Default::default(),
+            flood_stats: Default::default(),
             config: options,
         }
     }
@@ -495,6 +600,245 @@ impl<T> Term<T> {
         self.damage.full = true;
     }
 
+    /// PTY throughput and redraw statistics.
+    #[inline]
+    pub fn flood_stats(&self) -> &FloodStats {
+        &self.flood_stats
+    }
+
+    /// Mutable PTY throughput and redraw statistics.
+    #[inline]
+    pub fn flood_stats_mut(&mut self) -> &mut FloodStats {
+        &mut self.flood_stats
+    }
+
+    /// Number of lines scrolled out of the top of the screen.
+    ///
+    /// The difference between two values is the number of lines the grid content moved up in
+    /// between, which allows keeping points attached to the same content while output is written.
+    #[inline]
+    pub fn scrolled_lines(&self) -> usize {
+        self.scrolled_lines
+    }
+
+    /// Vi mode marks and jump list of the active grid.
+    #[inline]
+    pub fn marks(&self) -> &Marks {
+        &self.marks
+    }
+
+    /// Mutable vi mode marks and jump list of the active grid.
+    #[inline]
+    pub fn marks_mut(&mut self) -> &mut Marks {
+        &mut self.marks
+    }
+
+    /// Variables set by the terminal application.
+    #[inline]
+    pub fn user_vars(&self) -> &HashMap<String, String> {
+        &self.user_vars
+    }
+
+    /// Set or remove a user variable.
+    pub fn set_user_var(&mut self, name: String, value: Option<String>)
+    where
+        T: EventListener,
+    {
+        let changed = match &value {
+            Some(value) => {
+                self.user_vars.insert(name.clone(), value.clone()).as_ref() != Some(value)
+            },
+            None => self.user_vars.remove(&name).is_some(),
+        };
+
+        if changed {
+            self.event_proxy.send_event(Event::UserVar(name, value));
+        }
+    }
+
+    /// Remove all user variables.
+    fn clear_user_vars(&mut self)
+    where
+        T: EventListener,
+    {
+        for name in mem::take(&mut self.user_vars).into_keys() {
+            self.event_proxy.send_event(Event::UserVar(name, None));
+        }
+    }
+
+    /// Move the text of all lines completed since the last call into `lines`.
+    ///
+    /// The previous content of `lines` is discarded, its allocation is reused for the next lines.
+    ///
+    /// Lines are only tracked with [`Config::track_completed_lines`] enabled, and never in the
+    /// alternate screen. Only the most recent lines are kept when they are not collected.
+    pub fn take_completed_lines(&mut self, lines: &mut VecDeque<String>) {
+        lines.clear();
+        mem::swap(lines, &mut self.completed_lines);
+    }
+
+    /// Store the text of the line the cursor is leaving.
+    fn complete_line(&mut self) {
+        if !self.config.track_completed_lines || self.mode.contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+
+        let mut text = String::new();
+        let end = self.grid.cursor.point.line;
+        let start = self.line_search_left(Point::new(end, Column(0))).line;
+        for line in (start.0..=end.0).map(Line::from) {
+            self.push_line_text(&mut text, line, Column(0)..self.last_column(), line == end);
+        }
+
+        text.truncate(text.trim_end().len());
+        if text.is_empty() {
+            return;
+        }
+
+        // Drop the oldest line once the limit is reached.
+        if self.completed_lines.len() >= MAX_COMPLETED_LINES {
+            self.completed_lines.pop_front();
+        }
+        self.completed_lines.push_back(text);
+    }
+
+    /// Escape sequences which are ignored.
+    #[inline]
+    pub fn blocked_escapes(&self) -> EscapeCategories {
+        self.blocked_escapes
+    }
+
+    /// Set the escape sequences which should be ignored.
+    #[inline]
+    pub fn set_blocked_escapes(&mut self, blocked: EscapeCategories)
+    where
+        T: EventListener,
+    {
+        self.blocked_escapes = blocked;
+        self.update_held_replies();
+    }
+
+    /// Keep replies to blocked queries instead of dropping them.
+    ///
+    /// Held replies are sent once queries are no longer blocked and dropped once queries are no
+    /// longer held.
+    pub fn set_hold_queries(&mut self, hold: bool)
+    where
+        T: EventListener,
+    {
+        self.hold_queries = hold;
+        self.update_held_replies();
+    }
+
+    /// Send or drop held query replies after the escape sequence policy changed.
+    fn update_held_replies(&mut self)
+    where
+        T: EventListener,
+    {
+        if !self.blocked_escapes.contains(EscapeCategories::QUERIES) {
+            for reply in mem::take(&mut self.held_replies) {
+                self.event_proxy.send_event(reply);
+            }
+        } else if !self.hold_queries {
+            self.held_replies.clear();
+        }
+    }
+
+    /// Take all escape sequences blocked since the last call.
+    ///
+    /// After every call, the listener is notified about the next blocked escape sequence through
+    /// [`Event::EscapeBlocked`].
+    #[inline]
+    pub fn take_blocked_escapes(&mut self) -> BlockedEscapes {
+        mem::take(&mut self.blocked_report)
+    }
+
+    /// Check if an escape sequence is allowed, recording it if it is blocked.
+    fn escape_allowed(&mut self, category: EscapeCategories) -> bool
+    where
+        T: EventListener,
+    {
+        if !self.blocked_escapes.intersects(category) {
+            return true;
+        }
+
+        self.escape_blocked(category);
+
+        false
+    }
+
+    /// Check if a query should be processed, with its reply being held if queries are blocked.
+    fn query_allowed(&mut self) -> bool
+    where
+        T: EventListener,
+    {
+        if !self.blocked_escapes.contains(EscapeCategories::QUERIES) {
+            return true;
+        }
+
+        self.escape_blocked(EscapeCategories::QUERIES);
+
+        self.hold_queries
+    }
+
+    /// Send the reply to a query, keeping it while queries are held.
+    fn reply(&mut self, reply: Event)
+    where
+        T: EventListener,
+    {
+        if !self.blocked_escapes.contains(EscapeCategories::QUERIES) {
+            self.event_proxy.send_event(reply);
+        } else if self.held_replies.len() < MAX_HELD_REPLIES {
+            self.held_replies.push(reply);
+        }
+    }
+
+    /// Record a blocked escape sequence.
+    ///
+    /// Only the first blocked escape sequence since the last report is sent to the listener, so
+    /// floods of blocked sequences don't flood the listener with events.
+    fn escape_blocked(&mut self, category: EscapeCategories)
+    where
+        T: EventListener,
+    {
+        let report = &mut self.blocked_report;
+        let notify = report.count == 0;
+        report.categories.insert(category);
+        report.count += 1;
+
+        if notify {
+            debug!("Blocked {} escape sequence", category.name());
+            self.event_proxy.send_event(Event::EscapeBlocked(category));
+        }
+    }
+
+    /// Limit the scrollback history below the configured size.
+    ///
+    /// Passing `None` restores the configured history size. Lines removed by a limit are dropped
+    /// permanently, restoring the size only allows the history to grow again.
+    pub(crate) fn limit_history(&mut self, limit: Option<usize>) {
+        let history = self.config.scrolling_history;
+        let history = limit.map_or(history, |limit| limit.min(history));
+
+        if self.mode.contains(TermMode::ALT_SCREEN) {
+            self.inactive_grid.update_history(history);
+        } else {
+            self.grid.update_history(history);
+        }
+
+        self.remove_evicted_marks();
+    }
+
+    /// Remove marks on lines which are no longer part of the scrollback history.
+    fn remove_evicted_marks(&mut self) {
+        let (marks, grid) = if self.mode.contains(TermMode::ALT_SCREEN) {
+            (&mut self.inactive_marks, &self.inactive_grid)
+        } else {
+            (&mut self.marks, &self.grid)
+        };
+        marks.remove_lines(Line(i32::MIN)..grid.topmost_line());
+    }
+
     /// Set new options for the [`Term`].
     pub fn set_options(&mut self, options: Config)
     where
@@ -514,6 +858,11 @@ impl<T> Term<T> {
         } else {
             self.grid.update_history(self.config.scrolling_history);
         }
+        self.remove_evicted_marks();
+
+        if !self.config.track_completed_lines {
+            self.completed_lines = VecDeque::new();
+        }
 
         if self.config.kitty_keyboard != old_config.kitty_keyboard {
             self.keyboard_mode_stack = Vec::new();
@@ -572,11 +921,22 @@ impl<T> Term<T> {
     fn line_to_string(
         &self,
         line: Line,
-        mut cols: Range<Column>,
+        cols: Range<Column>,
         include_wrapped_wide: bool,
     ) -> String {
         let mut text = String::new();
+        self.push_line_text(&mut text, line, cols, include_wrapped_wide);
+        text
+    }
 
+    /// Append the text of a single line in the grid to `text`.
+    fn push_line_text(
+        &self,
+        text: &mut String,
+        line: Line,
+        mut cols: Range<Column>,
+        include_wrapped_wide: bool,
+    ) {
         let grid_line = &self.grid[line];
         let line_length = cmp::min(grid_line.line_length(), cols.end + 1);
 
@@ -628,8 +988,6 @@ impl<T> Term<T> {
         {
             text.push(self.grid[line - 1i32][Column(0)].c);
         }
-
-        text
     }
 
     /// Terminal content required for rendering.
@@ -677,16 +1035,19 @@ impl<T> Term<T> {
         self.grid.resize(!is_alt, num_lines, num_cols);
         self.inactive_grid.resize(is_alt, num_lines, num_cols);
 
-        // Invalidate selection and tabs only when necessary.
-        if old_cols != num_cols {
+        // Invalidate selection, marks and tabs only when necessary.
+        if old_cols == num_cols {
+            let max_lines = cmp::max(num_lines, old_lines) as i32;
+            let range = Line(0)..Line(max_lines);
+            self.selection = self.selection.take().and_then(|s| s.rotate(self, &range, -delta));
+            self.marks.rotate(&range, -delta, self.topmost_line());
+        } else {
             self.selection = None;
+            self.marks.clear();
+            self.inactive_marks.clear();
 
             // Recreate tabs list.
             self.tabs.resize(num_cols);
-        } else if let Some(selection) = self.selection.take() {
-            let max_lines = cmp::max(num_lines, old_lines) as i32;
-            let range = Line(0)..Line(max_lines);
-            self.selection = selection.rotate(self, &range, -delta);
         }
 
         // Clamp vi cursor to viewport.
@@ -721,6 +1082,7 @@ impl<T> Term<T> {
 
             // Reset alternate screen contents.
             self.inactive_grid.reset_region(..);
+            self.inactive_marks.clear();
         }
 
         mem::swap(&mut self.keyboard_mode_stack, &mut self.inactive_keyboard_mode_stack);
@@ -729,11 +1091,45 @@ impl<T> Term<T> {
         self.set_keyboard_mode(keyboard_mode, KeyboardModesApplyBehavior::Replace);
 
         mem::swap(&mut self.grid, &mut self.inactive_grid);
+        mem::swap(&mut self.marks, &mut self.inactive_marks);
         self.mode ^= TermMode::ALT_SCREEN;
         self.selection = None;
         self.mark_fully_damaged();
     }
 
+    /// Reset all modes and attributes, while keeping the terminal content.
+    ///
+    /// This leaves the alternate screen and restores the default modes, so state like mouse
+    /// reporting or bracketed paste set by a previous program does not leak into the next one.
+    pub fn reset_modes(&mut self)
+    where
+        T: EventListener,
+    {
+        if self.mode.contains(TermMode::ALT_SCREEN) {
+            self.swap_alt();
+        }
+
+        self.active_charset = Default::default();
+        self.cursor_style = None;
+        self.grid.cursor.template = Default::default();
+        self.grid.cursor.charsets = Default::default();
+        self.scroll_region = Line(0)..Line(self.screen_lines() as i32);
+        self.tabs = TabStops::new(self.columns());
+        self.title_stack = Vec::new();
+        self.title = None;
+        self.keyboard_mode_stack = Default::default();
+        self.inactive_keyboard_mode_stack = Default::default();
+        self.clear_user_vars();
+
+        // Preserve vi mode across resets.
+        self.mode &= TermMode::VI;
+        self.mode.insert(TermMode::default());
+
+        self.event_proxy.send_event(Event::ResetTitle);
+        self.event_proxy.send_event(Event::CursorBlinkingChange);
+        self.mark_fully_damaged();
+    }
+
     /// Scroll screen down.
     ///
     /// Text moves down; clear at bottom
@@ -759,6 +1155,7 @@ impl<T> Term<T> {
 
         // Scroll between origin and bottom
         self.grid.scroll_down(&region, lines);
+        self.marks.rotate(&region, -(lines as i32), self.topmost_line());
         self.mark_fully_damaged();
     }
 
@@ -778,6 +1175,11 @@ impl<T> Term<T> {
         self.selection = self.selection.take().and_then(|s| s.rotate(self, &region, lines as i32));
 
         self.grid.scroll_up(&region, lines);
+        self.marks.rotate(&region, lines as i32, self.topmost_line());
+
+        if region.start == 0 {
+            self.scrolled_lines = self.scrolled_lines.wrapping_add(lines);
+        }
 
         // Scroll vi mode cursor.
         let viewport_top = Line(-(self.grid.display_offset() as i32));
@@ -789,6 +1191,19 @@ impl<T> Term<T> {
         self.mark_fully_damaged();
     }
 
+    /// Move the cursor down, scrolling at the bottom of the scrolling region.
+    fn next_line(&mut self) {
+        let next = self.grid.cursor.point.line + 1;
+        if next == self.scroll_region.end {
+            let origin = self.scroll_region.start;
+            self.scroll_up_relative(origin, 1);
+        } else if next < self.screen_lines() {
+            self.damage_cursor();
+            self.grid.cursor.point.line += 1;
+            self.damage_cursor();
+        }
+    }
+
     fn deccolm(&mut self)
     where
         T: EventListener,
@@ -850,6 +1265,22 @@ impl<T> Term<T> {
         self.vi_mode_recompute_selection();
     }
 
+    /// Move vi mode cursor by repeating a motion.
+    #[inline]
+    pub fn vi_motion_count(&mut self, motion: ViMotion, count: usize)
+    where
+        T: EventListener,
+    {
+        // Require vi mode to be active.
+        if !self.mode.contains(TermMode::VI) {
+            return;
+        }
+
+        // Move cursor.
+        self.vi_mode_cursor = self.vi_mode_cursor.motion_count(self, motion, count);
+        self.vi_mode_recompute_selection();
+    }
+
     /// Move vi cursor to a point in the grid.
     #[inline]
     pub fn vi_goto_point(&mut self, point: Point)
@@ -963,7 +1394,8 @@ impl<T> Term<T> {
         self.grid.cursor_cell().flags.insert(Flags::WRAPLINE);
 
         if self.grid.cursor.point.line + 1 >= self.scroll_region.end {
-            self.linefeed();
+            // Wrapped lines are only completed by the linefeed at the end of the logical line.
+            self.next_line();
         } else {
             self.damage_cursor();
             self.grid.cursor.point.line += 1;
@@ -1250,17 +1682,21 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn identify_terminal(&mut self, intermediate: Option<char>) {
+        if !self.query_allowed() {
+            return;
+        }
+
         match intermediate {
             None => {
                 trace!("Reporting primary device attributes");
                 let text = String::from("\x1b[?6c");
-                self.event_proxy.send_event(Event::PtyWrite(text));
+                self.reply(Event::PtyWrite(text));
             },
             Some('>') => {
                 trace!("Reporting secondary device attributes");
                 let version = version_number(env!("CARGO_PKG_VERSION"));
                 let text = format!("\x1b[>0;{version};1c");
-                self.event_proxy.send_event(Event::PtyWrite(text));
+                self.reply(Event::PtyWrite(text));
             },
             _ => debug!("Unsupported device attributes intermediate"),
         }
@@ -1268,6 +1704,10 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn report_keyboard_mode(&mut self) {
+        if !self.query_allowed() {
+            return;
+        }
+
         if !self.config.kitty_keyboard {
             return;
         }
@@ -1276,7 +1716,7 @@ impl<T: EventListener> Handler for Term<T> {
         let current_mode =
             self.keyboard_mode_stack.last().unwrap_or(&KeyboardModes::NO_MODE).bits();
         let text = format!("\x1b[?{current_mode}u");
-        self.event_proxy.send_event(Event::PtyWrite(text));
+        self.reply(Event::PtyWrite(text));
     }
 
     #[inline]
@@ -1325,16 +1765,20 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn device_status(&mut self, arg: usize) {
+        if !self.query_allowed() {
+            return;
+        }
+
         trace!("Reporting device status: {}", arg);
         match arg {
             5 => {
                 let text = String::from("\x1b[0n");
-                self.event_proxy.send_event(Event::PtyWrite(text));
+                self.reply(Event::PtyWrite(text));
             },
             6 => {
                 let pos = self.grid.cursor.point;
                 let text = format!("\x1b[{};{}R", pos.line + 1, pos.column + 1);
-                self.event_proxy.send_event(Event::PtyWrite(text));
+                self.reply(Event::PtyWrite(text));
             },
             _ => debug!("unknown device status query: {}", arg),
         };
@@ -1417,14 +1861,8 @@ impl<T: EventListener> Handler for Term<T> {
     #[inline]
     fn linefeed(&mut self) {
         trace!("Linefeed");
-        let next = self.grid.cursor.point.line + 1;
-        if next == self.scroll_region.end {
-            self.scroll_up(1);
-        } else if next < self.screen_lines() {
-            self.damage_cursor();
-            self.grid.cursor.point.line += 1;
-            self.damage_cursor();
-        }
+        self.complete_line();
+        self.next_line();
     }
 
     /// Set current position as a tabstop.
@@ -1629,6 +2067,10 @@ impl<T: EventListener> Handler for Term<T> {
     /// Set the indexed color value.
     #[inline]
     fn set_color(&mut self, index: usize, color: Rgb) {
+        if !self.escape_allowed(EscapeCategories::COLORS) {
+            return;
+        }
+
         trace!("Setting color[{}] = {:?}", index, color);
 
         // Damage terminal if the color changed and it's not the cursor.
@@ -1642,10 +2084,14 @@ impl<T: EventListener> Handler for Term<T> {
     /// Respond to a color query escape sequence.
     #[inline]
     fn dynamic_color_sequence(&mut self, prefix: String, index: usize, terminator: &str) {
+        if !self.query_allowed() {
+            return;
+        }
+
         trace!("Requested write of escape sequence for color code {}: color[{}]", prefix, index);
 
         let terminator = terminator.to_owned();
-        self.event_proxy.send_event(Event::ColorRequest(
+        self.reply(Event::ColorRequest(
             index,
             Arc::new(move |color| {
                 format!(
@@ -1659,6 +2105,10 @@ impl<T: EventListener> Handler for Term<T> {
     /// Reset the indexed color to original value.
     #[inline]
     fn reset_color(&mut self, index: usize) {
+        if !self.escape_allowed(EscapeCategories::COLORS) {
+            return;
+        }
+
         trace!("Resetting color[{}]", index);
 
         // Damage terminal if the color changed and it's not the cursor.
@@ -1672,6 +2122,10 @@ impl<T: EventListener> Handler for Term<T> {
     /// Store data into clipboard.
     #[inline]
     fn clipboard_store(&mut self, clipboard: u8, base64: &[u8]) {
+        if !self.escape_allowed(EscapeCategories::CLIPBOARD) {
+            return;
+        }
+
         if !matches!(self.config.osc52, Osc52::OnlyCopy | Osc52::CopyPaste) {
             debug!("Denied osc52 store");
             return;
@@ -1693,6 +2147,10 @@ impl<T: EventListener> Handler for Term<T> {
     /// Load data from clipboard.
     #[inline]
     fn clipboard_load(&mut self, clipboard: u8, terminator: &str) {
+        if !self.escape_allowed(EscapeCategories::CLIPBOARD) {
+            return;
+        }
+
         if !matches!(self.config.osc52, Osc52::OnlyPaste | Osc52::CopyPaste) {
             debug!("Denied osc52 load");
             return;
@@ -1767,8 +2225,13 @@ impl<T: EventListener> Handler for Term<T> {
 
                     self.vi_mode_cursor.point.line =
                         (self.vi_mode_cursor.point.line - lines).grid_clamp(self, Boundary::Grid);
+
+                    let screen_lines = Line(self.screen_lines() as i32);
+                    self.marks.rotate(&(Line(0)..screen_lines), lines as i32, self.topmost_line());
                 }
 
+                self.marks.remove_lines(Line(0)..Line(self.screen_lines() as i32));
+
                 self.selection = None;
             },
             ansi::ClearMode::Saved if self.history_size() > 0 => {
@@ -1778,6 +2241,7 @@ impl<T: EventListener> Handler for Term<T> {
                     self.vi_mode_cursor.point.line.grid_clamp(self, Boundary::Cursor);
 
                 self.selection = self.selection.take().filter(|s| !s.intersects_range(..Line(0)));
+                self.marks.remove_lines(Line(i32::MIN)..Line(0));
             },
             // We have no history to clear.
             ansi::ClearMode::Saved => (),
@@ -1817,6 +2281,9 @@ impl<T: EventListener> Handler for Term<T> {
         self.vi_mode_cursor = Default::default();
         self.keyboard_mode_stack = Default::default();
         self.inactive_keyboard_mode_stack = Default::default();
+        self.marks.clear();
+        self.inactive_marks.clear();
+        self.clear_user_vars();
 
         // Preserve vi mode across resets.
         self.mode &= TermMode::VI;
@@ -1841,6 +2308,10 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn set_hyperlink(&mut self, hyperlink: Option<Hyperlink>) {
+        if hyperlink.is_some() && !self.escape_allowed(EscapeCategories::HYPERLINKS) {
+            return;
+        }
+
         trace!("Setting hyperlink: {:?}", hyperlink);
         self.grid.cursor.template.set_hyperlink(hyperlink.map(|e| e.into()));
     }
@@ -2010,6 +2481,10 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn report_private_mode(&mut self, mode: PrivateMode) {
+        if !self.query_allowed() {
+            return;
+        }
+
         trace!("Reporting private mode {mode:?}");
         let state = match mode {
             PrivateMode::Named(mode) => match mode {
@@ -2053,11 +2528,7 @@ impl<T: EventListener> Handler for Term<T> {
             PrivateMode::Unknown(_) => ModeState::NotSupported,
         };
 
-        self.event_proxy.send_event(Event::PtyWrite(format!(
-            "\x1b[?{};{}$y",
-            mode.raw(),
-            state as u8,
-        )));
+        self.reply(Event::PtyWrite(format!("\x1b[?{};{}$y", mode.raw(), state as u8)));
     }
 
     #[inline]
@@ -2099,6 +2570,10 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn report_mode(&mut self, mode: ansi::Mode) {
+        if !self.query_allowed() {
+            return;
+        }
+
         trace!("Reporting mode {mode:?}");
         let state = match mode {
             ansi::Mode::Named(mode) => match mode {
@@ -2110,11 +2585,7 @@ impl<T: EventListener> Handler for Term<T> {
             ansi::Mode::Unknown(_) => ModeState::NotSupported,
         };
 
-        self.event_proxy.send_event(Event::PtyWrite(format!(
-            "\x1b[{};{}$y",
-            mode.raw(),
-            state as u8,
-        )));
+        self.reply(Event::PtyWrite(format!("\x1b[{};{}$y", mode.raw(), state as u8)));
     }
 
     #[inline]
@@ -2185,6 +2656,10 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn set_title(&mut self, title: Option<String>) {
+        if !self.escape_allowed(EscapeCategories::TITLE) {
+            return;
+        }
+
         trace!("Setting title to '{:?}'", title);
 
         self.title = title.clone();
@@ -2224,7 +2699,11 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn text_area_size_pixels(&mut self) {
-        self.event_proxy.send_event(Event::TextAreaSizeRequest(Arc::new(move |window_size| {
+        if !self.query_allowed() {
+            return;
+        }
+
+        self.reply(Event::TextAreaSizeRequest(Arc::new(move |window_size| {
             let height = window_size.num_lines * window_size.cell_height;
             let width = window_size.num_cols * window_size.cell_width;
             format!("\x1b[4;{height};{width}t")
@@ -2233,8 +2712,12 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn text_area_size_chars(&mut self) {
+        if !self.query_allowed() {
+            return;
+        }
+
         let text = format!("\x1b[8;{};{}t", self.screen_lines(), self.columns());
-        self.event_proxy.send_event(Event::PtyWrite(text));
+        self.reply(Event::PtyWrite(text));
     }
 }
 
@@ -2383,40 +2866,40 @@ impl<'a> RenderableContent<'a> {
     }
 }
 
-/// Terminal test helpers.
-pub mod test {
-    use super::*;
+/// Terminal dimensions without any pixel information.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TermSize {
+    pub columns: usize,
+    pub screen_lines: usize,
+}
 
-    #[cfg(feature = "serde")]
-    use serde::{Deserialize, Serialize};
+impl TermSize {
+    pub fn new(columns: usize, screen_lines: usize) -> Self {
+        Self { columns, screen_lines }
+    }
+}
 
-    use crate::event::VoidListener;
+impl Dimensions for TermSize {
+    fn total_lines(&self) -> usize {
+        self.screen_lines()
+    }
 
-    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-    pub struct TermSize {
-        pub columns: usize,
-        pub screen_lines: usize,
+    fn screen_lines(&self) -> usize {
+        self.screen_lines
     }
 
-    impl TermSize {
-        pub fn new(columns: usize, screen_lines: usize) -> Self {
-            Self { columns, screen_lines }
-        }
+    fn columns(&self) -> usize {
+        self.columns
     }
+}
 
-    impl Dimensions for TermSize {
-        fn total_lines(&self) -> usize {
-            self.screen_lines()
-        }
+/// Terminal test helpers.
+pub mod test {
+    use super::*;
 
-        fn screen_lines(&self) -> usize {
-            self.screen_lines
-        }
+    pub use super::TermSize;
 
-        fn columns(&self) -> usize {
-            self.columns
-        }
-    }
+    use crate::event::VoidListener;
 
     /// Construct a terminal from its content as string.
     ///
@@ -2483,12 +2966,58 @@ mod tests {
 
     use crate::event::VoidListener;
     use crate::grid::{Grid, Scroll};
+    use crate::headless::HeadlessBuilder;
     use crate::index::{Column, Point, Side};
     use crate::selection::{Selection, SelectionType};
     use crate::term::cell::{Cell, Flags};
     use crate::term::test::TermSize;
     use crate::vte::ansi::{self, CharsetIndex, Handler, StandardCharset};
 
+    #[test]
+    fn completed_lines() {
+        let size = TermSize::new(5, 10);
+        let config = Config { track_completed_lines: true, ..Config::default() };
+        let mut term = Term::new(config, &size, VoidListener);
+
+        for c in "BUILD FAILED".chars() {
+            term.input(c);
+        }
+        term.carriage_return();
+        term.linefeed();
+        term.linefeed();
+        term.input('x');
+        let mut lines = VecDeque::new();
+        term.take_completed_lines(&mut lines);
+        assert_eq!(lines, [String::from("BUILD FAILED")]);
+        term.take_completed_lines(&mut lines);
+        assert!(lines.is_empty());
+
+        // Lines in the alternate screen are ignored.
+        term.swap_alt();
+        term.input('y');
+        term.linefeed();
+        term.take_completed_lines(&mut lines);
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn completed_line_wrapped_at_bottom() {
+        let size = TermSize::new(10, 2);
+        let config = Config { track_completed_lines: true, ..Config::default() };
+        let mut term = Term::new(config, &size, VoidListener);
+
+        term.goto(1, 0);
+        for c in "BUILD FAILED HERE".chars() {
+            term.input(c);
+        }
+        term.carriage_return();
+        term.linefeed();
+
+        let mut lines = VecDeque::new();
+        term.take_completed_lines(&mut lines);
+        assert_eq!(lines, [String::from("BUILD FAILED HERE")]);
+    }
+
     #[test]
     fn scroll_display_page_up() {
         let size = TermSize::new(5, 10);
@@ -2728,6 +3257,22 @@ mod tests {
         assert_eq!(term.grid()[cursor].c, '▒');
     }
 
+    #[test]
+    fn reset_modes_keeps_content() {
+        let size = TermSize::new(7, 5);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        term.input('a');
+        term.set_private_mode(NamedPrivateMode::BracketedPaste.into());
+        term.set_private_mode(NamedPrivateMode::ReportMouseClicks.into());
+        term.set_private_mode(NamedPrivateMode::SwapScreenAndSetRestoreCursor.into());
+        term.input('b');
+
+        term.reset_modes();
+
+        assert_eq!(*term.mode(), TermMode::default());
+        assert_eq!(term.grid()[Line(0)][Column(0)].c, 'a');
+    }
+
     #[test]
     fn clearing_viewport_keeps_history_position() {
         let size = TermSize::new(10, 20);
@@ -2844,6 +3389,34 @@ mod tests {
         assert_eq!(term.grid, scrolled_grid);
     }
 
+    #[test]
+    fn marks_follow_scrollback() {
+        let size = TermSize::new(5, 3);
+        let config = Config { scrolling_history: 2, ..Config::default() };
+        let mut term = Term::new(config, &size, VoidListener);
+        term.marks_mut().set('a', Point::new(Line(1), Column(2)));
+        term.marks_mut().set('b', Point::new(Line(2), Column(0)));
+
+        // Marks move into the scrollback history with their lines.
+        term.scroll_up(2);
+        assert_eq!(term.marks().get('a'), Some(Point::new(Line(-1), Column(2))));
+        assert_eq!(term.marks().get('b'), Some(Point::new(Line(0), Column(0))));
+
+        // Marks are removed once their line is rotated out of the history.
+        term.scroll_up(2);
+        assert_eq!(term.marks().get('a'), None);
+        assert_eq!(term.marks().get('b'), Some(Point::new(Line(-2), Column(0))));
+
+        // Alternate screen has separate marks.
+        term.swap_alt();
+        assert_eq!(term.marks().get('b'), None);
+        term.swap_alt();
+        assert_eq!(term.marks().get('b'), Some(Point::new(Line(-2), Column(0))));
+
+        term.clear_screen(ansi::ClearMode::Saved);
+        assert_eq!(term.marks().get('b'), None);
+    }
+
     #[test]
     fn vi_cursor_keep_pos_on_scrollback_buffer() {
         let size = TermSize::new(5, 10);
@@ -3262,6 +3835,72 @@ mod tests {
         assert_eq!(term.title, None);
     }
 
+    #[test]
+    fn blocked_escapes() {
+        let size = TermSize::new(7, 17);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        term.set_blocked_escapes(EscapeCategories::TITLE | EscapeCategories::COLORS);
+
+        term.set_title(Some("Test".into()));
+        assert_eq!(term.title, None);
+
+        let red = Rgb { r: 0xff, g: 0, b: 0 };
+        term.set_color(1, red);
+        assert_eq!(term.colors[1], None);
+
+        // Other categories are still applied.
+        let uri = String::from("https://example.org");
+        term.set_hyperlink(Some(Hyperlink { id: None, uri }));
+        assert!(term.grid.cursor.template.hyperlink().is_some());
+
+        term.set_blocked_escapes(EscapeCategories::empty());
+        term.set_color(1, red);
+        assert_eq!(term.colors[1], Some(red));
+    }
+
+    #[test]
+    fn blocked_escapes_are_reported_once() {
+        let mut terminal = HeadlessBuilder::new().build();
+        terminal.term_mut().set_blocked_escapes(EscapeCategories::all());
+        terminal.feed(b"\x1b]2;a\x07\x1b]2;b\x07\x1b[c");
+
+        let events = terminal.take_events();
+        assert!(matches!(events[..], [Event::EscapeBlocked(EscapeCategories::TITLE)]));
+
+        let blocked = terminal.term_mut().take_blocked_escapes();
+        assert_eq!(blocked.categories, EscapeCategories::TITLE | EscapeCategories::QUERIES);
+        assert_eq!(blocked.count, 3);
+
+        // The next blocked escape is reported again.
+        terminal.feed(b"\x1b[c");
+        let events = terminal.take_events();
+        assert!(matches!(events[..], [Event::EscapeBlocked(EscapeCategories::QUERIES)]));
+    }
+
+    #[test]
+    fn held_query_replies() {
+        let mut terminal = HeadlessBuilder::new().build();
+        terminal.term_mut().set_blocked_escapes(EscapeCategories::QUERIES);
+        terminal.term_mut().set_hold_queries(true);
+        terminal.feed(b"\x1b[c");
+        terminal.take_events();
+
+        // Replies are sent once queries are allowed.
+        terminal.term_mut().set_blocked_escapes(EscapeCategories::empty());
+        terminal.term_mut().set_hold_queries(false);
+        let events = terminal.take_events();
+        assert!(matches!(&events[..], [Event::PtyWrite(reply)] if reply == "\x1b[?6c"));
+
+        // Replies are dropped once queries are denied.
+        terminal.term_mut().set_blocked_escapes(EscapeCategories::QUERIES);
+        terminal.term_mut().set_hold_queries(true);
+        terminal.feed(b"\x1b[c");
+        terminal.term_mut().set_hold_queries(false);
+        terminal.term_mut().set_blocked_escapes(EscapeCategories::empty());
+        let events = terminal.take_events();
+        assert!(events.iter().all(|event| !matches!(event, Event::PtyWrite(_))));
+    }
+
     #[test]
     fn parse_cargo_version() {
         assert!(version_number(env!("CARGO_PKG_VERSION")) >= 10_01);
@@ -3,6 +3,7 @@ use std::error::Error;
 use std::mem;
 use std::ops::RangeInclusive;
 
+use bitflags::bitflags;
 use log::{debug, warn};
 use regex_automata::hybrid::dfa::{Builder, Cache, Config, DFA};
 pub use regex_automata::hybrid::BuildError;
@@ -18,8 +19,24 @@ use crate::term::Term;
 /// Used to match equal brackets, when performing a bracket-pair selection.
 const BRACKET_PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
 
+/// Characters with a special meaning in regexes.
+const REGEX_META_CHARACTERS: &str = "\\.+*?()|[]{}^$#&-~";
+
 pub type Match = RangeInclusive<Point>;
 
+bitflags! {
+    /// Options changing how the search text is matched.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct SearchMode: u8 {
+        /// Match the search text literally instead of as a regex.
+        const LITERAL        = 0b0000_0001;
+        /// Always match case, instead of only with uppercase characters in the search text.
+        const CASE_SENSITIVE = 0b0000_0010;
+        /// Only match complete words.
+        const WHOLE_WORD     = 0b0000_0100;
+    }
+}
+
 /// Terminal regex search state.
 #[derive(Clone, Debug)]
 pub struct RegexSearch {
@@ -27,17 +44,36 @@ pub struct RegexSearch {
     left_rdfa: LazyDfa,
     right_rdfa: LazyDfa,
     right_fdfa: LazyDfa,
+
+    /// Only accept matches surrounded by Unicode word boundaries.
+    whole_word: bool,
 }
 
 impl RegexSearch {
     /// Build the forward and backward search DFAs.
+    ///
+    /// The search is case-insensitive unless the regex contains uppercase characters.
     pub fn new(search: &str) -> Result<RegexSearch, Box<BuildError>> {
+        Self::with_mode(search, SearchMode::empty())
+    }
+
+    /// Build the search DFAs, interpreting the search text according to the search mode.
+    pub fn with_mode(search: &str, mode: SearchMode) -> Result<RegexSearch, Box<BuildError>> {
+        let search =
+            if mode.contains(SearchMode::LITERAL) { escape(search) } else { search.to_owned() };
+        let search = search.as_str();
+
+        // Unicode word boundaries are not supported by lazy DFAs, so whole words are checked
+        // after a match was found instead.
+        let whole_word = mode.contains(SearchMode::WHOLE_WORD);
+
         // Setup configs for both DFA directions.
         //
         // Bounds are based on Regex's meta engine:
         // https://github.com/rust-lang/regex/blob/061ee815ef2c44101dba7b0b124600fcb03c1912/regex-automata/src/meta/wrappers.rs#L581-L599
-        let has_uppercase = search.chars().any(|c| c.is_uppercase());
-        let syntax_config = SyntaxConfig::new().case_insensitive(!has_uppercase);
+        let case_sensitive =
+            mode.contains(SearchMode::CASE_SENSITIVE) || search.chars().any(|c| c.is_uppercase());
+        let syntax_config = SyntaxConfig::new().case_insensitive(!case_sensitive);
         let config =
             Config::new().minimum_cache_clear_count(Some(3)).minimum_bytes_per_state(Some(10));
         let max_size = config.get_cache_capacity();
@@ -74,10 +110,27 @@ impl RegexSearch {
         let right_rdfa =
             LazyDfa::new(search, config, syntax_config, thompson_config, Direction::Left, true)?;
 
-        Ok(RegexSearch { left_fdfa, left_rdfa, right_fdfa, right_rdfa })
+        Ok(RegexSearch { left_fdfa, left_rdfa, right_fdfa, right_rdfa, whole_word })
     }
 }
 
+/// Check if a character is part of a word, like Unicode's `\w`.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Escape all regex meta characters in a text.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if REGEX_META_CHARACTERS.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 /// Runtime-evaluated DFA.
 #[derive(Clone, Debug)]
 struct LazyDfa {
@@ -228,14 +281,26 @@ impl<T> Term<T> {
     pub fn regex_search_left(
         &self,
         regex: &mut RegexSearch,
-        start: Point,
+        mut start: Point,
         end: Point,
     ) -> Option<Match> {
-        // Find start and end of match.
-        let match_start = self.regex_search(start, end, &mut regex.left_fdfa)?;
-        let match_end = self.regex_search(match_start, start, &mut regex.left_rdfa)?;
+        loop {
+            // Find start and end of match.
+            let match_start = self.regex_search(start, end, &mut regex.left_fdfa)?;
+            let match_end = self.regex_search(match_start, start, &mut regex.left_rdfa)?;
 
-        Some(match_start..=match_end)
+            let regex_match = match_start..=match_end;
+            if !regex.whole_word || self.is_whole_word(&regex_match) {
+                return Some(regex_match);
+            }
+
+            // Retry left of the rejected match's end.
+            let match_end = self.expand_wide(match_end, Direction::Left);
+            if match_end == end {
+                return None;
+            }
+            start = match_end.sub(self, Boundary::None, 1);
+        }
     }
 
     /// Find the next regex match to the right of the origin point.
@@ -244,14 +309,91 @@ impl<T> Term<T> {
     pub fn regex_search_right(
         &self,
         regex: &mut RegexSearch,
-        start: Point,
+        mut start: Point,
         end: Point,
     ) -> Option<Match> {
-        // Find start and end of match.
-        let match_end = self.regex_search(start, end, &mut regex.right_fdfa)?;
-        let match_start = self.regex_search(match_end, start, &mut regex.right_rdfa)?;
+        loop {
+            // Find start and end of match.
+            let match_end = self.regex_search(start, end, &mut regex.right_fdfa)?;
+            let match_start = self.regex_search(match_end, start, &mut regex.right_rdfa)?;
+
+            let regex_match = match_start..=match_end;
+            if !regex.whole_word || self.is_whole_word(&regex_match) {
+                return Some(regex_match);
+            }
+
+            // Retry right of the rejected match's start.
+            let match_start = self.expand_wide(match_start, Direction::Right);
+            if match_start == end {
+                return None;
+            }
+            start = match_start.add(self, Boundary::None, 1);
+        }
+    }
+
+    /// Check if a match starts and ends at Unicode word boundaries.
+    ///
+    /// Boundaries are only required at edges of the match which are word characters, so
+    /// punctuation like the parenthesis in `foo(` can be followed by anything.
+    fn is_whole_word(&self, regex_match: &Match) -> bool {
+        let mut last = *regex_match.end();
+        if self.grid[last].flags.contains(Flags::WIDE_CHAR_SPACER) {
+            last.column -= 1;
+        }
+
+        let first = self.grid[*regex_match.start()].c;
+        let last = self.grid[last].c;
+        let before = self.char_before(*regex_match.start());
+        let after = self.char_after(*regex_match.end());
 
-        Some(match_start..=match_end)
+        let is_boundary =
+            |a: Option<char>, b: char| !is_word_char(b) || !a.is_some_and(is_word_char);
+        is_boundary(before, first) && is_boundary(after, last)
+    }
+
+    /// Get the character preceding a point, following wrapped lines.
+    fn char_before(&self, mut point: Point) -> Option<char> {
+        loop {
+            if point.column == 0 {
+                if point.line <= self.topmost_line() {
+                    return None;
+                }
+
+                point = Point::new(point.line - 1, self.last_column());
+                if !self.grid[point].flags.contains(Flags::WRAPLINE) {
+                    return None;
+                }
+            } else {
+                point.column -= 1;
+            }
+
+            let cell = &self.grid[point];
+            if !cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER) {
+                return Some(cell.c);
+            }
+        }
+    }
+
+    /// Get the character following a point, following wrapped lines.
+    fn char_after(&self, mut point: Point) -> Option<char> {
+        loop {
+            if point.column == self.last_column() {
+                if point.line >= self.bottommost_line()
+                    || !self.grid[point].flags.contains(Flags::WRAPLINE)
+                {
+                    return None;
+                }
+
+                point = Point::new(point.line + 1, Column(0));
+            } else {
+                point.column += 1;
+            }
+
+            let cell = &self.grid[point];
+            if !cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER) {
+                return Some(cell.c);
+            }
+        }
     }
 
     /// Find the next regex match.
@@ -685,6 +827,23 @@ mod tests {
     use crate::term::test::{mock_term, TermSize};
     use crate::term::Config;
 
+    #[test]
+    fn inline_search_wrapline() {
+        let term = mock_term("abc\ndef\r\nabc");
+
+        // Inline search continues across wrapped lines.
+        let start = Point::new(Line(0), Column(0));
+        assert_eq!(term.inline_search_right(start, "e"), Ok(Point::new(Line(1), Column(1))));
+        let start = Point::new(Line(1), Column(2));
+        assert_eq!(term.inline_search_left(start, "b"), Ok(Point::new(Line(0), Column(1))));
+
+        // Inline search stops at line breaks.
+        let start = Point::new(Line(1), Column(0));
+        assert_eq!(term.inline_search_right(start, "b"), Err(Point::new(Line(1), Column(2))));
+        let start = Point::new(Line(2), Column(2));
+        assert_eq!(term.inline_search_left(start, "e"), Err(Point::new(Line(2), Column(0))));
+    }
+
     #[test]
     fn regex_right() {
         #[rustfmt::skip]
@@ -1155,4 +1314,93 @@ mod tests {
         assert_eq!(start, Point::new(Line(1), Column(0)));
         assert_eq!(end, Point::new(Line(1), Column(2)));
     }
+
+    #[test]
+    fn literal_mode() {
+        let term = mock_term("[ERROR] foo.bar( failed");
+
+        let mut regex = RegexSearch::with_mode("foo.bar(", SearchMode::LITERAL).unwrap();
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(22));
+        let match_start = Point::new(Line(0), Column(8));
+        let match_end = Point::new(Line(0), Column(15));
+        assert_eq!(term.regex_search_right(&mut regex, start, end), Some(match_start..=match_end));
+
+        let mut regex = RegexSearch::with_mode("[error]", SearchMode::LITERAL).unwrap();
+        let match_end = Point::new(Line(0), Column(6));
+        assert_eq!(term.regex_search_right(&mut regex, start, end), Some(start..=match_end));
+
+        assert!(RegexSearch::new("foo.bar(").is_err());
+    }
+
+    #[test]
+    fn case_sensitive_mode() {
+        let term = mock_term("Alacritty alacritty");
+
+        let mut regex = RegexSearch::with_mode("alacritty", SearchMode::CASE_SENSITIVE).unwrap();
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(18));
+        let match_start = Point::new(Line(0), Column(10));
+        assert_eq!(term.regex_search_right(&mut regex, start, end), Some(match_start..=end));
+
+        // Smart-case without case-sensitive mode.
+        let mut regex = RegexSearch::new("alacritty").unwrap();
+        let match_end = Point::new(Line(0), Column(8));
+        assert_eq!(term.regex_search_right(&mut regex, start, end), Some(start..=match_end));
+    }
+
+    #[test]
+    fn whole_word_mode() {
+        let term = mock_term("foobar barfoo foo.");
+
+        let mut regex = RegexSearch::with_mode("foo", SearchMode::WHOLE_WORD).unwrap();
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(17));
+        let match_start = Point::new(Line(0), Column(14));
+        let match_end = Point::new(Line(0), Column(16));
+        assert_eq!(term.regex_search_right(&mut regex, start, end), Some(match_start..=match_end));
+        assert_eq!(term.regex_search_left(&mut regex, end, start), Some(match_start..=match_end));
+
+        let mode = SearchMode::WHOLE_WORD | SearchMode::LITERAL;
+        let mut regex = RegexSearch::with_mode("bar", mode).unwrap();
+        assert_eq!(term.regex_search_right(&mut regex, start, end), None);
+    }
+
+    #[test]
+    fn whole_word_unicode() {
+        let term = mock_term("caf\u{e9} \u{e9}t\u{e9} t\u{e9} t\u{e9}");
+
+        let mut regex = RegexSearch::with_mode("t\u{e9}", SearchMode::WHOLE_WORD).unwrap();
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(13));
+        let match_start = Point::new(Line(0), Column(9));
+        let match_end = Point::new(Line(0), Column(10));
+        assert_eq!(term.regex_search_right(&mut regex, start, end), Some(match_start..=match_end));
+
+        let match_start = Point::new(Line(0), Column(12));
+        assert_eq!(term.regex_search_left(&mut regex, end, start), Some(match_start..=end));
+
+        let mut regex = RegexSearch::with_mode("caf", SearchMode::WHOLE_WORD).unwrap();
+        assert_eq!(term.regex_search_right(&mut regex, start, end), None);
+    }
+
+    #[test]
+    fn whole_word_punctuation_edge() {
+        let term = mock_term("foo() xfoo(");
+
+        let mode = SearchMode::WHOLE_WORD | SearchMode::LITERAL;
+        let mut regex = RegexSearch::with_mode("foo(", mode).unwrap();
+        let start = Point::new(Line(0), Column(0));
+        let end = Point::new(Line(0), Column(10));
+        let match_end = Point::new(Line(0), Column(3));
+        assert_eq!(term.regex_search_right(&mut regex, start, end), Some(start..=match_end));
+        assert_eq!(term.regex_search_left(&mut regex, end, start), Some(start..=match_end));
+
+        let mut regex = RegexSearch::with_mode("(", mode).unwrap();
+        let match_start = Point::new(Line(0), Column(3));
+        assert_eq!(
+            term.regex_search_right(&mut regex, start, end),
+            Some(match_start..=match_start)
+        );
+    }
 }
@@ -0,0 +1,188 @@
+//! User variables set through `OSC 1337 ; SetUserVar=NAME=BASE64 ST`.
+//!
+//! Since this escape sequence is not handled by the ANSI parser, PTY output is scanned for it
+//! separately before it is passed to the ANSI parser.
+
+use base64::engine::general_purpose::STANDARD as Base64;
+use base64::Engine;
+use log::{debug, trace};
+
+use crate::event::EventListener;
+use crate::term::{EscapeCategories, Term};
+
+/// Prefix of the OSC parameters which set a user variable.
+const SET_USER_VAR_PREFIX: &[u8] = b"1337;SetUserVar=";
+
+/// Maximum length of the OSC parameters, longer sequences are ignored.
+const MAX_OSC_LEN: usize = 64 * 1024;
+
+/// Scanner state.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+enum State {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+}
+
+/// Scanner for user variable escape sequences.
+#[derive(Default, Debug)]
+pub struct UserVarParser {
+    state: State,
+    params: Vec<u8>,
+    overflow: bool,
+}
+
+impl UserVarParser {
+    /// Process one byte of PTY output.
+    #[inline]
+    pub fn advance<T: EventListener>(&mut self, term: &mut Term<T>, byte: u8) {
+        // Skip everything but escape sequences as fast as possible.
+        if self.state == State::Ground && byte != 0x1b {
+            return;
+        }
+
+        self.advance_sequence(term, byte);
+    }
+
+    fn advance_sequence<T: EventListener>(&mut self, term: &mut Term<T>, byte: u8) {
+        self.state = match (self.state, byte) {
+            (State::Escape, b']') => {
+                self.params.clear();
+                self.overflow = false;
+                State::Osc
+            },
+            // Both BEL and ST terminate the OSC, with ST starting a new escape.
+            (State::Osc, 0x07) => {
+                self.dispatch(term);
+                State::Ground
+            },
+            (State::Osc, 0x1b) => {
+                self.dispatch(term);
+                State::Escape
+            },
+            // CAN and SUB abort the sequence.
+            (State::Osc, 0x18 | 0x1a) => State::Ground,
+            (State::Osc, byte) => {
+                if self.params.len() < MAX_OSC_LEN {
+                    self.params.push(byte);
+                } else {
+                    self.overflow = true;
+                }
+                State::Osc
+            },
+            (_, 0x1b) => State::Escape,
+            _ => State::Ground,
+        };
+    }
+
+    /// Handle a complete OSC sequence.
+    fn dispatch<T: EventListener>(&mut self, term: &mut Term<T>) {
+        let Some(var) = self.params.strip_prefix(SET_USER_VAR_PREFIX) else {
+            return;
+        };
+
+        if self.overflow {
+            debug!("Ignoring SetUserVar exceeding {MAX_OSC_LEN} bytes");
+            return;
+        }
+
+        if !term.escape_allowed(EscapeCategories::USER_VARS) {
+            return;
+        }
+
+        match parse_user_var(var) {
+            Some((name, value)) => term.set_user_var(name, value),
+            None => debug!("Invalid SetUserVar: {:?}", String::from_utf8_lossy(var)),
+        }
+    }
+}
+
+/// Parse `NAME=BASE64` into the variable name and its decoded value.
+///
+/// An empty value removes the variable.
+fn parse_user_var(var: &[u8]) -> Option<(String, Option<String>)> {
+    let separator = var.iter().position(|&b| b == b'=')?;
+    let name = std::str::from_utf8(&var[..separator]).ok().filter(|name| !name.is_empty())?;
+
+    let encoded = &var[separator + 1..];
+    if encoded.is_empty() {
+        trace!("Unsetting user var {name}");
+        return Some((name.into(), None));
+    }
+
+    let value = Base64.decode(encoded).ok()?;
+    let value = String::from_utf8(value).ok()?;
+    trace!("Setting user var {name} to {value:?}");
+
+    Some((name.into(), Some(value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::event::VoidListener;
+    use crate::term::{Config, TermSize};
+    use crate::vte::ansi::Handler;
+
+    fn feed(parser: &mut UserVarParser, term: &mut Term<VoidListener>, bytes: &[u8]) {
+        for byte in bytes {
+            parser.advance(term, *byte);
+        }
+    }
+
+    #[test]
+    fn set_user_var() {
+        let mut term = Term::new(Config::default(), &TermSize::new(10, 5), VoidListener);
+        let mut parser = UserVarParser::default();
+
+        // `cHJvZA==` is `prod`.
+        feed(&mut parser, &mut term, b"a\x1b]1337;SetUserVar=AWS_PROFILE=cHJvZA==\x07b");
+        assert_eq!(term.user_vars().get("AWS_PROFILE").map(String::as_str), Some("prod"));
+
+        // String terminator, split across multiple reads.
+        feed(&mut parser, &mut term, b"\x1b]1337;SetUser");
+        feed(&mut parser, &mut term, b"Var=branch=bWFpbg==\x1b\\");
+        assert_eq!(term.user_vars().get("branch").map(String::as_str), Some("main"));
+
+        // Empty values remove the variable.
+        feed(&mut parser, &mut term, b"\x1b]1337;SetUserVar=AWS_PROFILE=\x07");
+        assert_eq!(term.user_vars().get("AWS_PROFILE"), None);
+    }
+
+    #[test]
+    fn ignore_invalid_user_vars() {
+        let mut term = Term::new(Config::default(), &TermSize::new(10, 5), VoidListener);
+        let mut parser = UserVarParser::default();
+
+        feed(&mut parser, &mut term, b"\x1b]1337;SetUserVar=name=!invalid!\x07");
+        feed(&mut parser, &mut term, b"\x1b]1337;SetUserVar==cHJvZA==\x07");
+        feed(&mut parser, &mut term, b"\x1b]1337;SetUserVar=name=cHJv\x18ZA==\x07");
+        feed(&mut parser, &mut term, b"\x1b]2;SetUserVar=name=cHJvZA==\x07");
+        assert!(term.user_vars().is_empty());
+    }
+
+    #[test]
+    fn blocked_user_vars() {
+        let mut term = Term::new(Config::default(), &TermSize::new(10, 5), VoidListener);
+        term.set_blocked_escapes(EscapeCategories::USER_VARS);
+        let mut parser = UserVarParser::default();
+
+        feed(&mut parser, &mut term, b"\x1b]1337;SetUserVar=branch=bWFpbg==\x07");
+        assert!(term.user_vars().is_empty());
+        assert_eq!(term.take_blocked_escapes().categories, EscapeCategories::USER_VARS);
+    }
+
+    #[test]
+    fn reset_clears_user_vars() {
+        let mut term = Term::new(Config::default(), &TermSize::new(10, 5), VoidListener);
+        let mut parser = UserVarParser::default();
+
+        feed(&mut parser, &mut term, b"\x1b]1337;SetUserVar=branch=bWFpbg==\x07");
+        assert!(!term.user_vars().is_empty());
+
+        term.reset_state();
+        assert!(term.user_vars().is_empty());
+    }
+}
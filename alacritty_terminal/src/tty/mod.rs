@@ -1,6 +1,7 @@
 //! TTY related functionality.
 
 use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::{env, io};
@@ -12,6 +13,11 @@ mod unix;
 #[cfg(not(windows))]
 pub use self::unix::*;
 
+#[cfg(not(windows))]
+pub mod serial;
+#[cfg(not(windows))]
+pub mod stream;
+
 #[cfg(windows)]
 pub mod windows;
 #[cfg(windows)]
@@ -50,6 +56,12 @@ impl Shell {
     }
 }
 
+/// Number of consecutive poller keys used by a single PTY.
+///
+/// The key of the interest passed to [`EventedReadWrite::register`] is the first of these keys,
+/// which allows registering multiple PTYs with the same poller.
+pub const PTY_TOKEN_COUNT: usize = 3;
+
 /// This trait defines the behaviour needed to read and/or write to a stream.
 /// It defines an abstraction over polling's interface in order to allow either one
 /// read/write object or a separate read and write object.
@@ -57,10 +69,17 @@ pub trait EventedReadWrite {
     type Reader: io::Read;
     type Writer: io::Write;
 
+    /// Register the PTY's sources with the `Poller`.
+    ///
+    /// The key of `interest` is the base key of this PTY, each source is registered with one of
+    /// the [`PTY_TOKEN_COUNT`] keys starting at the base key.
+    ///
     /// # Safety
     ///
     /// The underlying sources must outlive their registration in the `Poller`.
     unsafe fn register(&mut self, _: &Arc<Poller>, _: Event, _: PollMode) -> io::Result<()>;
+    /// Update the registration of the PTY's sources, using the same base key as
+    /// [`EventedReadWrite::register`].
     fn reregister(&mut self, _: &Arc<Poller>, _: Event, _: PollMode) -> io::Result<()>;
     fn deregister(&mut self, _: &Arc<Poller>) -> io::Result<()>;
 
@@ -71,8 +90,63 @@ pub trait EventedReadWrite {
 /// Events concerning TTY child processes.
 #[derive(Debug, PartialEq, Eq)]
 pub enum ChildEvent {
-    /// Indicates the child has exited, with an error code if available.
-    Exited(Option<i32>),
+    /// Indicates the child has exited.
+    Exited(ExitStatus),
+}
+
+/// Termination status of a child process.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// The process exited with an exit code.
+    Code(i32),
+
+    /// The process was terminated by a signal.
+    Signal(i32),
+
+    /// The process is gone, but its status is not available.
+    Unknown,
+}
+
+impl ExitStatus {
+    /// Exit code of the process, if it exited normally.
+    pub fn code(self) -> Option<i32> {
+        match self {
+            Self::Code(code) => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Check if the process exited successfully.
+    pub fn success(self) -> bool {
+        self == Self::Code(0)
+    }
+}
+
+impl From<std::process::ExitStatus> for ExitStatus {
+    fn from(status: std::process::ExitStatus) -> Self {
+        #[cfg(not(windows))]
+        if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+            return Self::Signal(signal);
+        }
+
+        status.code().map_or(Self::Unknown, Self::Code)
+    }
+}
+
+impl Display for ExitStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Code(code) => write!(f, "exit code {code}"),
+            #[cfg(not(windows))]
+            Self::Signal(signal) => match unix::signal_name(*signal) {
+                Some(name) => write!(f, "signal {name}"),
+                None => write!(f, "signal {signal}"),
+            },
+            #[cfg(windows)]
+            Self::Signal(signal) => write!(f, "signal {signal}"),
+            Self::Unknown => write!(f, "unknown status"),
+        }
+    }
 }
 
 /// A pseudoterminal (or PTY).
@@ -0,0 +1,271 @@
+//! Serial port support.
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::OpenOptions;
+use std::io;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::OwnedFd;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use rustix_openpty::rustix::termios::{self, ControlModes, InputModes, OptionalActions};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::tty::stream::Stream;
+
+/// Default serial line speed.
+const DEFAULT_BAUD_RATE: u32 = 115_200;
+
+/// Serial port configuration.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerialOptions {
+    /// Path of the serial device.
+    pub path: PathBuf,
+
+    /// Line speed in bits per second.
+    pub baud_rate: u32,
+
+    /// Number of data bits per character, from 5 to 8.
+    pub data_bits: u8,
+
+    /// Parity checking mode.
+    pub parity: Parity,
+
+    /// Number of stop bits, either 1 or 2.
+    pub stop_bits: u8,
+
+    /// Flow control mode.
+    pub flow_control: FlowControl,
+}
+
+impl SerialOptions {
+    /// Serial options for 8N1 communication at 115200 baud without flow control.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            baud_rate: DEFAULT_BAUD_RATE,
+            data_bits: 8,
+            parity: Parity::None,
+            stop_bits: 1,
+            flow_control: FlowControl::None,
+        }
+    }
+}
+
+impl FromStr for SerialOptions {
+    type Err = String;
+
+    /// Parse serial options in the format `DEVICE[:BAUD[,FORMAT][,FLOW]]`.
+    ///
+    /// The format is specified as data bits, parity and stop bits like `8N1` or `7E2`, while the
+    /// flow control is one of `none`, `rtscts` or `xonxoff`.
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (path, settings) = match spec.rsplit_once(':') {
+            Some((path, settings)) if settings.starts_with(|c: char| c.is_ascii_digit()) => {
+                (path, Some(settings))
+            },
+            _ => (spec, None),
+        };
+
+        if path.is_empty() {
+            return Err(String::from("missing serial device"));
+        }
+
+        let mut options = SerialOptions::new(path);
+        let mut settings = settings.into_iter().flat_map(|settings| settings.split(','));
+
+        if let Some(baud_rate) = settings.next() {
+            options.baud_rate = match baud_rate.parse() {
+                Ok(baud_rate) if baud_rate > 0 => baud_rate,
+                _ => return Err(format!("invalid baud rate {baud_rate:?}")),
+            };
+        }
+
+        for setting in settings {
+            if let Ok(flow_control) = setting.parse() {
+                options.flow_control = flow_control;
+                continue;
+            }
+
+            let mut chars = setting.chars();
+            let (Some(data_bits), Some(parity), Some(stop_bits), None) =
+                (chars.next(), chars.next(), chars.next(), chars.next())
+            else {
+                return Err(format!("invalid serial setting {setting:?}"));
+            };
+
+            options.data_bits = match data_bits {
+                '5'..='8' => data_bits as u8 - b'0',
+                _ => return Err(format!("invalid number of data bits {data_bits:?}")),
+            };
+            options.parity = match parity.to_ascii_uppercase() {
+                'N' => Parity::None,
+                'E' => Parity::Even,
+                'O' => Parity::Odd,
+                _ => return Err(format!("invalid parity {parity:?}")),
+            };
+            options.stop_bits = match stop_bits {
+                '1' | '2' => stop_bits as u8 - b'0',
+                _ => return Err(format!("invalid number of stop bits {stop_bits:?}")),
+            };
+        }
+
+        Ok(options)
+    }
+}
+
+impl Display for SerialOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let parity = match self.parity {
+            Parity::None => 'N',
+            Parity::Even => 'E',
+            Parity::Odd => 'O',
+        };
+
+        write!(f, "{}:{}", self.path.display(), self.baud_rate)?;
+        write!(f, ",{}{}{},{}", self.data_bits, parity, self.stop_bits, self.flow_control)
+    }
+}
+
+/// Serial parity checking mode.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Parity {
+    None,
+    Even,
+    Odd,
+}
+
+/// Serial flow control mode.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FlowControl {
+    None,
+    /// Hardware flow control using the RTS/CTS lines.
+    RtsCts,
+    /// Software flow control using XON/XOFF characters.
+    XonXoff,
+}
+
+impl FromStr for FlowControl {
+    type Err = String;
+
+    fn from_str(flow_control: &str) -> Result<Self, Self::Err> {
+        match flow_control.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "rtscts" => Ok(Self::RtsCts),
+            "xonxoff" => Ok(Self::XonXoff),
+            _ => Err(format!("invalid flow control {flow_control:?}")),
+        }
+    }
+}
+
+impl Display for FlowControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("none"),
+            Self::RtsCts => f.write_str("rtscts"),
+            Self::XonXoff => f.write_str("xonxoff"),
+        }
+    }
+}
+
+/// Open and configure a serial port.
+pub fn new(options: &SerialOptions) -> io::Result<Stream> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(libc::O_NOCTTY | libc::O_NONBLOCK)
+        .open(&options.path)?;
+
+    // Disable all line processing, since that is the terminal's job.
+    let mut termios = termios::tcgetattr(&file)?;
+    termios.make_raw();
+    termios.set_speed(options.baud_rate)?;
+
+    let control_modes = &mut termios.control_modes;
+    control_modes.remove(ControlModes::CSIZE);
+    control_modes.insert(match options.data_bits {
+        5 => ControlModes::CS5,
+        6 => ControlModes::CS6,
+        7 => ControlModes::CS7,
+        _ => ControlModes::CS8,
+    });
+    control_modes.insert(ControlModes::CREAD | ControlModes::CLOCAL);
+    control_modes.set(ControlModes::PARENB, options.parity != Parity::None);
+    control_modes.set(ControlModes::PARODD, options.parity == Parity::Odd);
+    control_modes.set(ControlModes::CSTOPB, options.stop_bits == 2);
+    control_modes.set(ControlModes::CRTSCTS, options.flow_control == FlowControl::RtsCts);
+
+    let input_modes = &mut termios.input_modes;
+    input_modes.set(InputModes::INPCK, options.parity != Parity::None);
+    let software_flow = options.flow_control == FlowControl::XonXoff;
+    input_modes.set(InputModes::IXON | InputModes::IXOFF, software_flow);
+
+    termios::tcsetattr(&file, OptionalActions::Now, &termios)?;
+
+    Stream::from_fd(OwnedFd::from(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_device_only() {
+        let options: SerialOptions = "/dev/ttyUSB0".parse().unwrap();
+        assert_eq!(options, SerialOptions::new("/dev/ttyUSB0"));
+    }
+
+    #[test]
+    fn parse_full() {
+        let options: SerialOptions = "/dev/ttyS1:9600,7e2,rtscts".parse().unwrap();
+        assert_eq!(options.path, PathBuf::from("/dev/ttyS1"));
+        assert_eq!(options.baud_rate, 9600);
+        assert_eq!(options.data_bits, 7);
+        assert_eq!(options.parity, Parity::Even);
+        assert_eq!(options.stop_bits, 2);
+        assert_eq!(options.flow_control, FlowControl::RtsCts);
+
+        assert_eq!(options.to_string(), "/dev/ttyS1:9600,7E2,rtscts");
+        assert_eq!(options.to_string().parse(), Ok(options));
+    }
+
+    #[test]
+    fn parse_flow_control_only() {
+        let options: SerialOptions = "/dev/ttyACM0:57600,xonxoff".parse().unwrap();
+        assert_eq!(options.baud_rate, 57600);
+        assert_eq!(options.data_bits, 8);
+        assert_eq!(options.flow_control, FlowControl::XonXoff);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!("".parse::<SerialOptions>().is_err());
+        assert!(":9600".parse::<SerialOptions>().is_err());
+        assert!("/dev/ttyS0:0".parse::<SerialOptions>().is_err());
+        assert!("/dev/ttyS0:9600,9N1".parse::<SerialOptions>().is_err());
+        assert!("/dev/ttyS0:9600,8X1".parse::<SerialOptions>().is_err());
+        assert!("/dev/ttyS0:9600,8N3".parse::<SerialOptions>().is_err());
+        assert!("/dev/ttyS0:9600,cts".parse::<SerialOptions>().is_err());
+    }
+
+    #[test]
+    fn configure_pty_as_serial() {
+        // A PTY accepts the same termios settings as a serial port.
+        let pty = rustix_openpty::openpty(None, None).unwrap();
+        let path = rustix_openpty::rustix::pty::ptsname(&pty.controller, Vec::new()).unwrap();
+        let path = PathBuf::from(path.to_str().unwrap());
+
+        let mut options: SerialOptions = "/dev/null:38400,7O1".parse().unwrap();
+        options.path = path;
+        let stream = new(&options).unwrap();
+
+        let termios = termios::tcgetattr(stream.file()).unwrap();
+        assert_eq!(termios.input_speed(), 38400);
+        assert!(termios.control_modes.contains(ControlModes::CS7 | ControlModes::PARODD));
+        assert!(!termios.local_modes.contains(termios::LocalModes::ECHO));
+    }
+}
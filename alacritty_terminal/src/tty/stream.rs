@@ -0,0 +1,292 @@
+//! Terminal backend for arbitrary file descriptors.
+//!
+//! Unlike the PTY, this doesn't spawn any child process. It allows attaching the terminal to
+//! serial ports, Unix sockets or any other bidirectional stream owned by another process.
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use polling::{Event, PollMode, Poller};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::event::{OnResize, WindowSize};
+use crate::tty::{ChildEvent, EventedPty, EventedReadWrite, ExitStatus, PTY_READ_WRITE_TOKEN};
+
+/// File descriptor or socket a [`Stream`] can attach to.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    /// File descriptor inherited by this process.
+    Fd(RawFd),
+
+    /// Path of a Unix domain socket.
+    Socket(PathBuf),
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    /// Parse a numeric file descriptor or a socket path.
+    fn from_str(target: &str) -> Result<Self, Self::Err> {
+        if target.is_empty() {
+            return Err(String::from("empty attach target"));
+        }
+
+        match target.parse() {
+            Ok(fd) => Ok(Self::Fd(fd)),
+            Err(_) => Ok(Self::Socket(PathBuf::from(target))),
+        }
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fd(fd) => write!(f, "{fd}"),
+            Self::Socket(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Terminal I/O over an existing stream.
+pub struct Stream {
+    io: StreamIo,
+    exit_reported: bool,
+}
+
+impl Stream {
+    /// Attach to an existing file descriptor.
+    ///
+    /// The file descriptor will be switched to non-blocking mode.
+    pub fn from_fd(fd: OwnedFd) -> io::Result<Self> {
+        unsafe { super::unix::set_nonblocking(fd.as_raw_fd()) };
+        Ok(Self { io: StreamIo { file: File::from(fd), hung_up: false }, exit_reported: false })
+    }
+
+    /// Connect to a Unix domain socket.
+    pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let stream = UnixStream::connect(path)?;
+        Self::from_fd(OwnedFd::from(stream))
+    }
+
+    /// Attach to a file descriptor or socket.
+    ///
+    /// File descriptors are duplicated, so the original descriptor remains untouched.
+    pub fn attach(target: &Target) -> io::Result<Self> {
+        match target {
+            Target::Fd(fd) => {
+                let duplicate = unsafe { libc::fcntl(*fd, libc::F_DUPFD_CLOEXEC, 0) };
+                if duplicate < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+
+                Self::from_fd(unsafe { OwnedFd::from_raw_fd(duplicate) })
+            },
+            Target::Socket(path) => Self::connect(path),
+        }
+    }
+
+    /// Underlying file.
+    pub fn file(&self) -> &File {
+        &self.io.file
+    }
+
+    /// Check if the other side of the stream has hung up.
+    fn hung_up(&self) -> bool {
+        if self.io.hung_up {
+            return true;
+        }
+
+        let mut pollfd = libc::pollfd { fd: self.io.file.as_raw_fd(), events: 0, revents: 0 };
+        let res = unsafe { libc::poll(&mut pollfd, 1, 0) };
+        res > 0 && pollfd.revents & (libc::POLLHUP | libc::POLLERR | libc::POLLNVAL) != 0
+    }
+}
+
+impl EventedReadWrite for Stream {
+    type Reader = StreamIo;
+    type Writer = StreamIo;
+
+    #[inline]
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        mut interest: Event,
+        poll_opts: PollMode,
+    ) -> io::Result<()> {
+        interest.key += PTY_READ_WRITE_TOKEN;
+        unsafe { poll.add_with_mode(&self.io.file, interest, poll_opts) }
+    }
+
+    #[inline]
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        mut interest: Event,
+        poll_opts: PollMode,
+    ) -> io::Result<()> {
+        interest.key += PTY_READ_WRITE_TOKEN;
+        poll.modify_with_mode(&self.io.file, interest, poll_opts)
+    }
+
+    #[inline]
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        poll.delete(&self.io.file)
+    }
+
+    #[inline]
+    fn reader(&mut self) -> &mut StreamIo {
+        &mut self.io
+    }
+
+    #[inline]
+    fn writer(&mut self) -> &mut StreamIo {
+        &mut self.io
+    }
+}
+
+impl EventedPty for Stream {
+    /// Report a hangup of the stream as exit without status code.
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        if self.exit_reported || !self.hung_up() {
+            return None;
+        }
+
+        self.exit_reported = true;
+        Some(ChildEvent::Exited(ExitStatus::Unknown))
+    }
+}
+
+impl OnResize for Stream {
+    /// Streams have no concept of a window size.
+    fn on_resize(&mut self, _window_size: WindowSize) {}
+}
+
+/// Reader and writer of a [`Stream`], which keeps track of hangups.
+pub struct StreamIo {
+    file: File,
+    hung_up: bool,
+}
+
+impl Read for StreamIo {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.file.read(buf) {
+            // Reaching the end of a stream indicates that the other side is gone.
+            Ok(0) if !buf.is_empty() => {
+                self.hung_up = true;
+                Err(ErrorKind::UnexpectedEof.into())
+            },
+            Err(err) if is_hangup(&err) => {
+                self.hung_up = true;
+                Err(err)
+            },
+            res => res,
+        }
+    }
+}
+
+impl Write for StreamIo {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let res = self.file.write(buf);
+        if res.as_ref().is_err_and(is_hangup) {
+            self.hung_up = true;
+        }
+        res
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Check if an I/O error was caused by the other side hanging up.
+fn is_hangup(err: &io::Error) -> bool {
+    matches!(err.raw_os_error(), Some(libc::EIO | libc::EPIPE | libc::ECONNRESET))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use parking_lot::Mutex;
+
+    use crate::event::EventListener;
+    use crate::event_loop::EventLoop;
+    use crate::index::{Column, Line, Point};
+    use crate::sync::FairMutex;
+    use crate::term::test::TermSize;
+    use crate::term::{Config, Term};
+
+    #[test]
+    fn parse_target() {
+        assert_eq!("3".parse(), Ok(Target::Fd(3)));
+        assert_eq!("/tmp/socket".parse(), Ok(Target::Socket("/tmp/socket".into())));
+        assert!("".parse::<Target>().is_err());
+    }
+
+    #[test]
+    fn hangup_reported_once() {
+        let (local, remote) = UnixStream::pair().unwrap();
+        let mut stream = Stream::from_fd(OwnedFd::from(local)).unwrap();
+
+        (&remote).write_all(b"data").unwrap();
+        let mut buf = [0; 8];
+        assert_eq!(stream.reader().read(&mut buf).unwrap(), 4);
+        assert_eq!(stream.next_child_event(), None);
+
+        drop(remote);
+        assert_eq!(stream.reader().read(&mut buf).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(stream.next_child_event(), Some(ChildEvent::Exited(ExitStatus::Unknown)));
+        assert_eq!(stream.next_child_event(), None);
+    }
+
+    #[test]
+    fn event_loop_exits_on_hangup() {
+        #[derive(Clone, Default)]
+        struct Listener(Arc<Mutex<Vec<crate::event::Event>>>);
+
+        impl EventListener for Listener {
+            fn send_event(&self, event: crate::event::Event) {
+                self.0.lock().push(event);
+            }
+        }
+
+        let (local, remote) = UnixStream::pair().unwrap();
+        let stream = Stream::from_fd(OwnedFd::from(local)).unwrap();
+
+        let listener = Listener::default();
+        let size = TermSize::new(10, 2);
+        let term = Arc::new(FairMutex::new(Term::new(Config::default(), &size, listener.clone())));
+        let event_loop =
+            EventLoop::new(term.clone(), listener.clone(), stream, false, false).unwrap();
+        let io_thread = event_loop.spawn();
+
+        (&remote).write_all(b"hello").unwrap();
+        drop(remote);
+        io_thread.join().unwrap();
+
+        let term = term.lock();
+        let start = Point::new(Line(0), Column(0));
+        assert_eq!(term.bounds_to_string(start, Point::new(Line(0), Column(9))), "hello");
+        assert!(listener.0.lock().iter().any(|event| matches!(event, crate::event::Event::Exit)));
+    }
+
+    #[test]
+    fn attach_duplicates_fd() {
+        let (local, remote) = UnixStream::pair().unwrap();
+        let mut stream = Stream::attach(&Target::Fd(local.as_raw_fd())).unwrap();
+        drop(local);
+
+        stream.writer().write_all(b"ping").unwrap();
+        let mut buf = [0; 4];
+        (&remote).read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+    }
+}
@@ -4,10 +4,10 @@ use std::ffi::CStr;
 use std::fs::File;
 use std::io::{Error, ErrorKind, Read, Result};
 use std::mem::MaybeUninit;
-use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::os::unix::io::{AsRawFd, RawFd};
 use std::os::unix::net::UnixStream;
 use std::os::unix::process::CommandExt;
-use std::process::{Child, Command, Stdio};
+use std::process::{Child, Command};
 use std::sync::Arc;
 use std::{env, ptr};
 
@@ -111,6 +111,54 @@ impl Pty {
     pub fn file(&self) -> &File {
         &self.file
     }
+
+    /// Local mode flags of the slave's line discipline.
+    pub fn local_flags(&self) -> Result<LocalFlags> {
+        LocalFlags::get(self.file.as_raw_fd())
+    }
+}
+
+/// Local mode flags of a terminal's line discipline.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LocalFlags {
+    echo: bool,
+    canonical: bool,
+}
+
+impl LocalFlags {
+    /// Read the flags of the terminal device behind `fd`.
+    ///
+    /// For a PTY, the flags of the slave can be read through the master.
+    pub fn get(fd: RawFd) -> Result<Self> {
+        let mut termios = MaybeUninit::<libc::termios>::uninit();
+        if unsafe { libc::tcgetattr(fd, termios.as_mut_ptr()) } != 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let local_flags = unsafe { termios.assume_init() }.c_lflag;
+        Ok(Self { echo: local_flags & libc::ECHO != 0, canonical: local_flags & libc::ICANON != 0 })
+    }
+
+    /// Whether input characters are echoed.
+    #[inline]
+    pub fn echo(&self) -> bool {
+        self.echo
+    }
+
+    /// Whether input is processed line by line.
+    #[inline]
+    pub fn canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Whether the terminal is most likely prompting for a password.
+    ///
+    /// Password prompts read a line without echoing it, while full-screen applications disable
+    /// both echo and canonical mode.
+    #[inline]
+    pub fn is_password_prompt(&self) -> bool {
+        !self.echo && self.canonical
+    }
 }
 
 /// User information that is required for a new shell session.
@@ -205,12 +253,9 @@ pub fn new(config: &Options, window_size: WindowSize, window_id: u64) -> Result<
     };
 
     // Setup child stdin/stdout/stderr as slave fd of PTY.
-    // Ownership of fd is transferred to the Stdio structs and will be closed by them at the end of
-    // this scope. (It is not an issue that the fd is closed three times since File::drop ignores
-    // error on libc::close.).
-    builder.stdin(unsafe { Stdio::from_raw_fd(slave_fd) });
-    builder.stderr(unsafe { Stdio::from_raw_fd(slave_fd) });
-    builder.stdout(unsafe { Stdio::from_raw_fd(slave_fd) });
+    builder.stdin(slave.try_clone()?);
+    builder.stderr(slave.try_clone()?);
+    builder.stdout(slave);
 
     // Setup shell environment.
     let window_id = window_id.to_string();
@@ -305,7 +350,8 @@ impl EventedReadWrite for Pty {
         mut interest: Event,
         poll_opts: PollMode,
     ) -> Result<()> {
-        interest.key = PTY_READ_WRITE_TOKEN;
+        let base = interest.key;
+        interest.key = base + PTY_READ_WRITE_TOKEN;
         unsafe {
             poll.add_with_mode(&self.file, interest, poll_opts)?;
         }
@@ -313,7 +359,7 @@ impl EventedReadWrite for Pty {
         unsafe {
             poll.add_with_mode(
                 &self.signals,
-                Event::readable(PTY_CHILD_EVENT_TOKEN),
+                Event::readable(base + PTY_CHILD_EVENT_TOKEN),
                 PollMode::Level,
             )
         }
@@ -326,12 +372,13 @@ impl EventedReadWrite for Pty {
         mut interest: Event,
         poll_opts: PollMode,
     ) -> Result<()> {
-        interest.key = PTY_READ_WRITE_TOKEN;
+        let base = interest.key;
+        interest.key = base + PTY_READ_WRITE_TOKEN;
         poll.modify_with_mode(&self.file, interest, poll_opts)?;
 
         poll.modify_with_mode(
             &self.signals,
-            Event::readable(PTY_CHILD_EVENT_TOKEN),
+            Event::readable(base + PTY_CHILD_EVENT_TOKEN),
             PollMode::Level,
         )
     }
@@ -372,7 +419,7 @@ impl EventedPty for Pty {
                 None
             },
             Ok(None) => None,
-            Ok(exit_status) => Some(ChildEvent::Exited(exit_status.and_then(|s| s.code()))),
+            Ok(Some(exit_status)) => Some(ChildEvent::Exited(exit_status.into())),
         }
     }
 }
@@ -410,15 +457,81 @@ impl ToWinsize for WindowSize {
     }
 }
 
-unsafe fn set_nonblocking(fd: c_int) {
+pub(crate) unsafe fn set_nonblocking(fd: c_int) {
     use libc::{fcntl, F_GETFL, F_SETFL, O_NONBLOCK};
 
     let res = fcntl(fd, F_SETFL, fcntl(fd, F_GETFL, 0) | O_NONBLOCK);
     assert_eq!(res, 0);
 }
 
+/// Name of a signal, like `SIGTERM`.
+pub(crate) fn signal_name(signal: c_int) -> Option<&'static str> {
+    let name = match signal {
+        libc::SIGHUP => "SIGHUP",
+        libc::SIGINT => "SIGINT",
+        libc::SIGQUIT => "SIGQUIT",
+        libc::SIGILL => "SIGILL",
+        libc::SIGTRAP => "SIGTRAP",
+        libc::SIGABRT => "SIGABRT",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGKILL => "SIGKILL",
+        libc::SIGUSR1 => "SIGUSR1",
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGUSR2 => "SIGUSR2",
+        libc::SIGPIPE => "SIGPIPE",
+        libc::SIGALRM => "SIGALRM",
+        libc::SIGTERM => "SIGTERM",
+        libc::SIGSYS => "SIGSYS",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
 #[test]
 fn test_get_pw_entry() {
     let mut buf: [i8; 1024] = [0; 1024];
     let _pw = get_pw_entry(&mut buf).unwrap();
 }
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+#[test]
+fn password_prompt_flags() {
+    use rustix_openpty::rustix::termios::LocalModes;
+
+    let pty = openpty(None, None).unwrap();
+    let master_fd = pty.controller.as_raw_fd();
+
+    let mut termios = termios::tcgetattr(&pty.user).unwrap();
+    termios.local_modes.insert(LocalModes::ECHO | LocalModes::ICANON);
+    termios::tcsetattr(&pty.user, OptionalActions::Now, &termios).unwrap();
+    let flags = LocalFlags::get(master_fd).unwrap();
+    assert!(flags.echo() && flags.canonical());
+    assert!(!flags.is_password_prompt());
+
+    termios.local_modes.remove(LocalModes::ECHO);
+    termios::tcsetattr(&pty.user, OptionalActions::Now, &termios).unwrap();
+    assert!(LocalFlags::get(master_fd).unwrap().is_password_prompt());
+
+    termios.local_modes.remove(LocalModes::ICANON);
+    termios::tcsetattr(&pty.user, OptionalActions::Now, &termios).unwrap();
+    assert!(!LocalFlags::get(master_fd).unwrap().is_password_prompt());
+}
+
+#[test]
+fn exit_status_display() {
+    use std::os::unix::process::ExitStatusExt;
+
+    use crate::tty::ExitStatus;
+
+    let status = ExitStatus::from(std::process::ExitStatus::from_raw(libc::SIGKILL));
+    assert_eq!(status, ExitStatus::Signal(libc::SIGKILL));
+    assert_eq!(status.to_string(), "signal SIGKILL");
+    assert!(!status.success());
+
+    let status = ExitStatus::from(std::process::ExitStatus::from_raw(3 << 8));
+    assert_eq!(status, ExitStatus::Code(3));
+    assert_eq!(status.to_string(), "exit code 3");
+    assert_eq!(status.code(), Some(3));
+}
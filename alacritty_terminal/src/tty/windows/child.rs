@@ -13,7 +13,7 @@ use windows_sys::Win32::System::Threading::{
     WT_EXECUTEINWAITTHREAD, WT_EXECUTEONLYONCE,
 };
 
-use crate::tty::ChildEvent;
+use crate::tty::{ChildEvent, ExitStatus};
 
 struct Interest {
     poller: Arc<Poller>,
@@ -37,8 +37,9 @@ extern "system" fn child_exit_callback(ctx: *mut c_void, timed_out: BOOLEAN) {
     let mut exit_code = 0_u32;
     let child_handle = event_tx.child_handle.load(Ordering::Relaxed) as HANDLE;
     let status = unsafe { GetExitCodeProcess(child_handle, &mut exit_code) };
-    let exit_code = if status == FALSE { None } else { Some(exit_code as i32) };
-    event_tx.sender.send(ChildEvent::Exited(exit_code)).ok();
+    let exit_status =
+        if status == FALSE { ExitStatus::Unknown } else { ExitStatus::Code(exit_code as i32) };
+    event_tx.sender.send(ChildEvent::Exited(exit_status)).ok();
 
     let interest = event_tx.interest.lock().unwrap();
     if let Some(interest) = interest.as_ref() {
@@ -156,6 +157,9 @@ mod tests {
         poller.wait(&mut events, Some(WAIT_TIMEOUT)).unwrap();
         assert_eq!(events.iter().next().unwrap().key, PTY_CHILD_EVENT_TOKEN);
         // Verify that at least one `ChildEvent::Exited` was received.
-        assert_eq!(child_exit_watcher.event_rx().try_recv(), Ok(ChildEvent::Exited(Some(1))));
+        assert_eq!(
+            child_exit_watcher.event_rx().try_recv(),
+            Ok(ChildEvent::Exited(ExitStatus::Code(1)))
+        );
     }
 }
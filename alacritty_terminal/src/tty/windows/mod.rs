@@ -7,7 +7,7 @@ use std::sync::Arc;
 
 use crate::event::{OnResize, WindowSize};
 use crate::tty::windows::child::ChildExitWatcher;
-use crate::tty::{ChildEvent, EventedPty, EventedReadWrite, Options, Shell};
+use crate::tty::{ChildEvent, EventedPty, EventedReadWrite, ExitStatus, Options, Shell};
 
 mod blocking;
 mod child;
@@ -69,9 +69,10 @@ impl EventedReadWrite for Pty {
         interest: polling::Event,
         poll_opts: polling::PollMode,
     ) -> io::Result<()> {
-        self.conin.register(poll, with_key(interest, PTY_READ_WRITE_TOKEN), poll_opts);
-        self.conout.register(poll, with_key(interest, PTY_READ_WRITE_TOKEN), poll_opts);
-        self.child_watcher.register(poll, with_key(interest, PTY_CHILD_EVENT_TOKEN));
+        let base = interest.key;
+        self.conin.register(poll, with_key(interest, base + PTY_READ_WRITE_TOKEN), poll_opts);
+        self.conout.register(poll, with_key(interest, base + PTY_READ_WRITE_TOKEN), poll_opts);
+        self.child_watcher.register(poll, with_key(interest, base + PTY_CHILD_EVENT_TOKEN));
 
         Ok(())
     }
@@ -83,9 +84,10 @@ impl EventedReadWrite for Pty {
         interest: polling::Event,
         poll_opts: polling::PollMode,
     ) -> io::Result<()> {
-        self.conin.register(poll, with_key(interest, PTY_READ_WRITE_TOKEN), poll_opts);
-        self.conout.register(poll, with_key(interest, PTY_READ_WRITE_TOKEN), poll_opts);
-        self.child_watcher.register(poll, with_key(interest, PTY_CHILD_EVENT_TOKEN));
+        let base = interest.key;
+        self.conin.register(poll, with_key(interest, base + PTY_READ_WRITE_TOKEN), poll_opts);
+        self.conout.register(poll, with_key(interest, base + PTY_READ_WRITE_TOKEN), poll_opts);
+        self.child_watcher.register(poll, with_key(interest, base + PTY_CHILD_EVENT_TOKEN));
 
         Ok(())
     }
@@ -115,7 +117,7 @@ impl EventedPty for Pty {
         match self.child_watcher.event_rx().try_recv() {
             Ok(ev) => Some(ev),
             Err(TryRecvError::Empty) => None,
-            Err(TryRecvError::Disconnected) => Some(ChildEvent::Exited(None)),
+            Err(TryRecvError::Disconnected) => Some(ChildEvent::Exited(ExitStatus::Unknown)),
         }
     }
 }
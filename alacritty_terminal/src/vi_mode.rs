@@ -4,8 +4,9 @@ use std::cmp::min;
 use serde::{Deserialize, Serialize};
 
 use crate::event::EventListener;
-use crate::grid::{Dimensions, GridCell};
+use crate::grid::{BidirectionalIterator, Dimensions, GridCell, Scroll};
 use crate::index::{Boundary, Column, Direction, Line, Point, Side};
+use crate::selection::{Selection, SelectionType};
 use crate::term::cell::Flags;
 use crate::term::Term;
 
@@ -52,6 +53,171 @@ pub enum ViMotion {
     WordRightEnd,
     /// Move to opposing bracket.
     Bracket,
+    /// Move to the previous empty line before a paragraph.
+    ParagraphUp,
+    /// Move to the next empty line after a paragraph.
+    ParagraphDown,
+    /// Move to start of sentence.
+    SentenceLeft,
+    /// Move to start of next sentence.
+    SentenceRight,
+    /// Move to top of scrollback history.
+    Top,
+    /// Move to bottom of scrollback history.
+    Bottom,
+    /// Scroll up by one line, keeping the cursor on its line while it is visible.
+    ScrollUp,
+    /// Scroll down by one line, keeping the cursor on its line while it is visible.
+    ScrollDown,
+}
+
+impl ViMotion {
+    /// Whether operators using this motion act on entire lines.
+    pub fn is_linewise(self) -> bool {
+        matches!(
+            self,
+            Self::Up
+                | Self::Down
+                | Self::High
+                | Self::Middle
+                | Self::Low
+                | Self::Top
+                | Self::Bottom
+                | Self::ScrollUp
+                | Self::ScrollDown
+        )
+    }
+
+    /// Whether operators using this motion include the cell at the motion's target.
+    pub fn is_inclusive(self) -> bool {
+        matches!(
+            self,
+            Self::Last
+                | Self::SemanticLeftEnd
+                | Self::SemanticRightEnd
+                | Self::WordLeftEnd
+                | Self::WordRightEnd
+                | Self::Bracket
+        )
+    }
+}
+
+/// Vi mode text objects.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViTextObject {
+    /// Semantically separated word, without surrounding whitespace.
+    InnerSemanticWord,
+    /// Semantically separated word, including adjacent whitespace.
+    AroundSemanticWord,
+    /// Whitespace separated word, without surrounding whitespace.
+    InnerWord,
+    /// Whitespace separated word, including adjacent whitespace.
+    AroundWord,
+    /// Text between a pair of quotes on the same line.
+    InnerQuote(char),
+    /// Text between a pair of quotes, including the quotes and trailing whitespace.
+    AroundQuote(char),
+    /// Text between the opening bracket and its matching closing bracket.
+    InnerBracket(char),
+    /// Text between a pair of brackets, including the brackets.
+    AroundBracket(char),
+    /// Lines until the next empty line, or the empty lines around the cursor.
+    InnerParagraph,
+    /// Paragraph including the empty lines after it.
+    AroundParagraph,
+}
+
+impl ViTextObject {
+    /// Selection of the text object at a point.
+    pub fn selection<T>(self, term: &Term<T>, point: Point) -> Option<Selection> {
+        let point = term.expand_wide(point, Direction::Left);
+
+        let (ty, start, end) = match self {
+            Self::InnerSemanticWord | Self::AroundSemanticWord => {
+                let (start, end) = if is_space(term, point) {
+                    space_bounds(term, point)
+                } else if term.semantic_escape_chars().contains(term.grid()[point].c) {
+                    (point, point)
+                } else {
+                    (term.semantic_search_left(point), term.semantic_search_right(point))
+                };
+
+                let (start, end) = if self == Self::AroundSemanticWord {
+                    with_whitespace(term, start, end)
+                } else {
+                    (start, end)
+                };
+
+                (SelectionType::Simple, start, end)
+            },
+            Self::InnerWord | Self::AroundWord => {
+                let (start, end) = if is_space(term, point) {
+                    space_bounds(term, point)
+                } else {
+                    word_bounds(term, point)
+                };
+
+                let (start, end) = if self == Self::AroundWord {
+                    with_whitespace(term, start, end)
+                } else {
+                    (start, end)
+                };
+
+                (SelectionType::Simple, start, end)
+            },
+            Self::InnerQuote(quote) => {
+                let (start, end) = quote_bounds(term, point, quote)?;
+                let start = start.add(term, Boundary::Grid, 1);
+                let end = end.sub(term, Boundary::Grid, 1);
+
+                // Ignore empty pairs.
+                if start > end {
+                    return None;
+                }
+
+                (SelectionType::Simple, start, end)
+            },
+            Self::AroundQuote(quote) => {
+                let (start, end) = quote_bounds(term, point, quote)?;
+                (SelectionType::Simple, start, trailing_whitespace(term, end))
+            },
+            Self::InnerBracket(bracket) => {
+                let (start, end) = bracket_bounds(term, point, bracket)?;
+                let start = start.add(term, Boundary::Grid, 1);
+                let end = end.sub(term, Boundary::Grid, 1);
+
+                // Ignore empty pairs.
+                if start > end {
+                    return None;
+                }
+
+                (SelectionType::Simple, start, end)
+            },
+            Self::AroundBracket(bracket) => {
+                let (start, end) = bracket_bounds(term, point, bracket)?;
+                (SelectionType::Simple, start, end)
+            },
+            Self::InnerParagraph => {
+                let (start, end) = paragraph_bounds(term, point);
+                (SelectionType::Lines, start, end)
+            },
+            Self::AroundParagraph => {
+                let (start, mut end) = paragraph_bounds(term, point);
+
+                // Include the empty lines after the paragraph, or the paragraph after empty lines.
+                if end.line < term.bottommost_line() {
+                    let next = Point::new(end.line + 1, Column(0));
+                    end = paragraph_bounds(term, next).1;
+                }
+
+                (SelectionType::Lines, start, end)
+            },
+        };
+
+        let mut selection = Selection::new(ty, start, Side::Left);
+        selection.update(end, Side::Right);
+        Some(selection)
+    }
 }
 
 /// Cursor tracking vi mode position.
@@ -153,6 +319,29 @@ impl ViModeCursor {
                 self.point = word(term, self.point, Direction::Right, Side::Right);
             },
             ViMotion::Bracket => self.point = term.bracket_search(self.point).unwrap_or(self.point),
+            ViMotion::ParagraphUp => self.point = paragraph(term, self.point, Direction::Left),
+            ViMotion::ParagraphDown => self.point = paragraph(term, self.point, Direction::Right),
+            ViMotion::SentenceLeft => self.point = sentence_left(term, self.point),
+            ViMotion::SentenceRight => self.point = sentence_right(term, self.point),
+            ViMotion::Top => {
+                let line = term.topmost_line();
+                let start = Point::new(line, Column(0));
+                self.point = first_occupied_in_line(term, line).unwrap_or(start);
+            },
+            ViMotion::Bottom => {
+                // Move to the start of the last line, across linewraps.
+                let start = term.line_search_left(Point::new(term.bottommost_line(), Column(0)));
+                self.point = first_occupied_in_line(term, start.line).unwrap_or(start);
+            },
+            ViMotion::ScrollUp | ViMotion::ScrollDown => {
+                let delta = if motion == ViMotion::ScrollUp { 1 } else { -1 };
+                term.scroll_display(Scroll::Delta(delta));
+
+                // Clamp cursor to the viewport.
+                let viewport_start = -(term.grid().display_offset() as i32);
+                let viewport_end = viewport_start + term.bottommost_line().0;
+                self.point.line.0 = self.point.line.0.clamp(viewport_start, viewport_end);
+            },
         }
 
         term.scroll_to_point(self.point);
@@ -160,6 +349,47 @@ impl ViModeCursor {
         self
     }
 
+    /// Move vi mode cursor by repeating a motion.
+    ///
+    /// Motions which always jump to the same position are only applied once, except for
+    /// [`ViMotion::Last`] which moves to the end of the `count - 1`th line below the cursor.
+    #[must_use = "this returns the result of the operation, without modifying the original"]
+    pub fn motion_count<T: EventListener>(
+        mut self,
+        term: &mut Term<T>,
+        motion: ViMotion,
+        count: usize,
+    ) -> Self {
+        let count = match motion {
+            ViMotion::Last => {
+                self = self.motion_count(term, ViMotion::Down, count.saturating_sub(1));
+                1
+            },
+            ViMotion::First
+            | ViMotion::FirstOccupied
+            | ViMotion::High
+            | ViMotion::Middle
+            | ViMotion::Low
+            | ViMotion::Bracket
+            | ViMotion::Top
+            | ViMotion::Bottom => 1,
+            _ => count,
+        };
+
+        for _ in 0..count {
+            let point = self.point;
+            let display_offset = term.grid().display_offset();
+            self = self.motion(term, motion);
+
+            // Stop once the cursor and viewport cannot move any further.
+            if self.point == point && term.grid().display_offset() == display_offset {
+                break;
+            }
+        }
+
+        self
+    }
+
     /// Get target cursor point for vim-like page movement.
     #[must_use = "this returns the result of the operation, without modifying the original"]
     pub fn scroll<T: EventListener>(mut self, term: &Term<T>, lines: i32) -> Self {
@@ -357,6 +587,267 @@ fn advance<T>(term: &Term<T>, point: Point, direction: Direction) -> Point {
     }
 }
 
+/// Find the start and end of the whitespace separated word at a point.
+fn word_bounds<T>(term: &Term<T>, point: Point) -> (Point, Point) {
+    let start = match term.inline_search_left(point, " \t") {
+        Ok(space) => space.add(term, Boundary::Grid, 1),
+        Err(start) => start,
+    };
+    let end = match term.inline_search_right(point, " \t") {
+        Ok(space) => space.sub(term, Boundary::Grid, 1),
+        Err(end) => end,
+    };
+    (start, end)
+}
+
+/// Find the start and end of the whitespace at a point, within its terminal line.
+fn space_bounds<T>(term: &Term<T>, point: Point) -> (Point, Point) {
+    let (mut start, mut end) = (point, point);
+    while start.column > 0 && is_space(term, Point::new(start.line, start.column - 1)) {
+        start.column -= 1;
+    }
+    while end.column < term.last_column() && is_space(term, Point::new(end.line, end.column + 1)) {
+        end.column += 1;
+    }
+    (start, end)
+}
+
+/// Extend a word by its trailing whitespace, or leading whitespace if there is none.
+fn with_whitespace<T>(term: &Term<T>, mut start: Point, end: Point) -> (Point, Point) {
+    let with_trailing = trailing_whitespace(term, end);
+    if with_trailing != end {
+        return (start, with_trailing);
+    }
+
+    let before = Point::new(start.line, Column(start.column.saturating_sub(1)));
+    if start.column > 0 && is_space(term, before) {
+        start = space_bounds(term, before).0;
+    }
+
+    (start, end)
+}
+
+/// Extend a point over the whitespace following it on the same line.
+fn trailing_whitespace<T>(term: &Term<T>, point: Point) -> Point {
+    let after = Point::new(point.line, point.column + 1);
+    if point.column < term.last_column() && is_space(term, after) {
+        space_bounds(term, after).1
+    } else {
+        point
+    }
+}
+
+/// Find the pair of quotes around a point, or the first pair after it.
+///
+/// Quotes are paired from the start of the line, since the same character opens and closes them.
+fn quote_bounds<T>(term: &Term<T>, point: Point, quote: char) -> Option<(Point, Point)> {
+    let start = term.line_search_left(point);
+    let end = term.line_search_right(point);
+
+    let mut quotes = term
+        .grid()
+        .iter_from(start)
+        .take_while(|cell| cell.point <= end)
+        .filter(|cell| cell.c == quote && !cell.flags.contains(Flags::WIDE_CHAR_SPACER))
+        .map(|cell| cell.point);
+
+    // Include the starting point, since iteration starts after it.
+    let first_cell = &term.grid()[start];
+    let mut opening = (first_cell.c == quote).then_some(start);
+
+    while let Some(open) = opening.take().or_else(|| quotes.next()) {
+        let close = quotes.next()?;
+        if close >= point {
+            return Some((open, close));
+        }
+    }
+
+    None
+}
+
+/// Find the bracket pair enclosing a point.
+fn bracket_bounds<T>(term: &Term<T>, point: Point, open: char) -> Option<(Point, Point)> {
+    let close = match open {
+        '(' => ')',
+        '[' => ']',
+        '{' => '}',
+        '<' => '>',
+        _ => return None,
+    };
+
+    let open_point = match term.grid()[point].c {
+        c if c == open => point,
+        c if c == close => term.bracket_search(point)?,
+        _ => {
+            // Search backwards for the first unmatched opening bracket.
+            let mut iter = term.grid().iter_from(point);
+            let mut depth = 0;
+            loop {
+                let cell = iter.prev()?;
+                if cell.c == close {
+                    depth += 1;
+                } else if cell.c == open && depth == 0 {
+                    break cell.point;
+                } else if cell.c == open {
+                    depth -= 1;
+                }
+            }
+        },
+    };
+
+    let close_point = term.bracket_search(open_point)?;
+    (close_point >= point).then_some((open_point, close_point))
+}
+
+/// Find the first and last line of the paragraph at a point.
+///
+/// Paragraphs are separated by empty lines, when the point is on an empty line all adjacent empty
+/// lines are returned instead. Wrapped lines are always treated as a single line.
+fn paragraph_bounds<T>(term: &Term<T>, point: Point) -> (Point, Point) {
+    let is_empty = |line: Line| is_empty_line(term, line);
+    let empty = is_empty(point.line);
+
+    let mut start = term.line_search_left(point);
+    while start.line > term.topmost_line() && is_empty(start.line - 1) == empty {
+        start = term.line_search_left(Point::new(start.line - 1, Column(0)));
+    }
+
+    let mut end = term.line_search_right(point);
+    while end.line < term.bottommost_line() && is_empty(end.line + 1) == empty {
+        end = term.line_search_right(Point::new(end.line + 1, Column(0)));
+    }
+
+    (start, end)
+}
+
+/// Move by paragraph, like {/} in vi.
+///
+/// Paragraphs are separated by empty lines, wrapped lines are always treated as a single line.
+fn paragraph<T>(term: &Term<T>, mut point: Point, direction: Direction) -> Point {
+    // Skip empty lines when starting on one.
+    let mut skip_empty = is_empty_line(term, point.line);
+
+    loop {
+        point = match direction {
+            Direction::Left => {
+                let start = term.line_search_left(point);
+                if start.line <= term.topmost_line() {
+                    return start;
+                }
+
+                term.line_search_left(Point::new(start.line - 1, Column(0)))
+            },
+            Direction::Right => {
+                let end = term.line_search_right(point);
+                if end.line >= term.bottommost_line() {
+                    return last_occupied_in_line(term, end.line).unwrap_or(end);
+                }
+
+                Point::new(end.line + 1, Column(0))
+            },
+        };
+
+        let empty = is_empty_line(term, point.line);
+        if empty && !skip_empty {
+            return point;
+        }
+        skip_empty &= empty;
+    }
+}
+
+/// Move to the start of the next sentence, like ) in vi.
+///
+/// Sentences end with `.`, `!` or `?`, optionally followed by closing brackets or quotes, before
+/// whitespace or the end of a line. Empty lines also separate sentences.
+fn sentence_right<T>(term: &Term<T>, point: Point) -> Point {
+    let last_column = term.last_column();
+    let mut terminated = is_sentence_end(term.grid()[point].c);
+    let mut separated = false;
+
+    for cell in term.grid().iter_from(point) {
+        // Treat line breaks like whitespace and empty lines like the end of a sentence.
+        let line_start = Point::new(cell.point.line - 1, last_column);
+        if cell.point.column == 0 && !is_wrap(term, line_start) {
+            terminated |= is_empty_line(term, cell.point.line);
+            separated |= terminated;
+        }
+
+        if cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER) {
+            continue;
+        } else if is_space(term, cell.point) {
+            separated |= terminated;
+        } else if separated {
+            return cell.point;
+        } else {
+            terminated = is_sentence_end(cell.c) || (terminated && is_sentence_closing(cell.c));
+        }
+    }
+
+    point
+}
+
+/// Move to the start of the sentence, or the previous sentence when already at its start, like (
+/// in vi.
+fn sentence_left<T>(term: &Term<T>, point: Point) -> Point {
+    let last_column = term.last_column();
+    let mut start = None;
+    let mut separated = false;
+
+    let mut iter = term.grid().iter_from(point);
+    while let Some(cell) = iter.prev() {
+        // Treat line breaks like whitespace and stop at empty lines.
+        if cell.point.column == last_column && !cell.flags.contains(Flags::WRAPLINE) {
+            if start.is_some() && is_empty_line(term, cell.point.line) {
+                break;
+            }
+            separated = true;
+        }
+
+        if cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER) {
+            continue;
+        } else if is_space(term, cell.point) {
+            separated = true;
+        } else if separated && start.is_some() && ends_sentence(term, cell.point) {
+            break;
+        } else {
+            start = Some(cell.point);
+            separated = false;
+        }
+    }
+
+    start.unwrap_or(point)
+}
+
+/// Check if a sentence ends at a point, skipping closing brackets and quotes.
+fn ends_sentence<T>(term: &Term<T>, point: Point) -> bool {
+    let mut iter = term.grid().iter_from(point);
+    let mut c = term.grid()[point].c;
+    while is_sentence_closing(c) {
+        c = match iter.prev() {
+            Some(cell) => cell.c,
+            None => return false,
+        };
+    }
+    is_sentence_end(c)
+}
+
+/// Check if a character ends a sentence.
+fn is_sentence_end(c: char) -> bool {
+    matches!(c, '.' | '!' | '?')
+}
+
+/// Check if a character can follow the end of a sentence.
+fn is_sentence_closing(c: char) -> bool {
+    matches!(c, ')' | ']' | '"' | '\'')
+}
+
+/// Check if a line, including all lines wrapped with it, is empty.
+fn is_empty_line<T>(term: &Term<T>, line: Line) -> bool {
+    let start = term.line_search_left(Point::new(line, Column(0)));
+    let end = term.line_search_right(Point::new(line, Column(0)));
+    (start.line.0..=end.line.0).all(|line| last_occupied_in_line(term, Line(line)).is_none())
+}
+
 /// Check if cell at point contains whitespace.
 fn is_space<T>(term: &Term<T>, point: Point) -> bool {
     let cell = &term.grid()[point.line][point.column];
@@ -383,7 +874,7 @@ mod tests {
 
     use crate::event::VoidListener;
     use crate::index::{Column, Line};
-    use crate::term::test::TermSize;
+    use crate::term::test::{mock_term, TermSize};
     use crate::term::{Config, Term};
     use crate::vte::ansi::Handler;
 
@@ -820,4 +1311,229 @@ mod tests {
         cursor = cursor.scroll(&term, -20);
         assert_eq!(cursor.point, Point::new(Line(19), Column(0)));
     }
+
+    #[test]
+    fn motion_count() {
+        let mut term = term();
+
+        let mut cursor = ViModeCursor::new(Point::new(Line(0), Column(0)));
+
+        cursor = cursor.motion_count(&mut term, ViMotion::Right, 3);
+        assert_eq!(cursor.point, Point::new(Line(0), Column(3)));
+
+        cursor = cursor.motion_count(&mut term, ViMotion::Down, 5);
+        assert_eq!(cursor.point, Point::new(Line(5), Column(3)));
+
+        cursor = cursor.motion_count(&mut term, ViMotion::Right, 100);
+        assert_eq!(cursor.point, Point::new(Line(5), Column(19)));
+
+        cursor = cursor.motion_count(&mut term, ViMotion::First, 3);
+        assert_eq!(cursor.point, Point::new(Line(5), Column(0)));
+
+        cursor = cursor.motion_count(&mut term, ViMotion::Last, 2);
+        assert_eq!(cursor.point, Point::new(Line(6), Column(19)));
+    }
+
+    #[test]
+    fn motion_paragraph() {
+        let mut term = mock_term("one two\ncontinued\r\n\r\nthree\r\n\r\n\r\nfour");
+
+        let mut cursor = ViModeCursor::new(Point::new(Line(1), Column(3)));
+
+        cursor = cursor.motion(&mut term, ViMotion::ParagraphDown);
+        assert_eq!(cursor.point, Point::new(Line(2), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::ParagraphDown);
+        assert_eq!(cursor.point, Point::new(Line(4), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::ParagraphDown);
+        assert_eq!(cursor.point, Point::new(Line(6), Column(3)));
+
+        cursor = cursor.motion(&mut term, ViMotion::ParagraphUp);
+        assert_eq!(cursor.point, Point::new(Line(5), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::ParagraphUp);
+        assert_eq!(cursor.point, Point::new(Line(2), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::ParagraphUp);
+        assert_eq!(cursor.point, Point::new(Line(0), Column(0)));
+
+        // Wrapped lines move as one.
+        cursor.point = Point::new(Line(1), Column(3));
+        cursor = cursor.motion(&mut term, ViMotion::ParagraphUp);
+        assert_eq!(cursor.point, Point::new(Line(0), Column(0)));
+    }
+
+    #[test]
+    fn motion_sentence() {
+        let mut term = mock_term("One. Two\nthree? (Four.) Five\r\n\r\nSix");
+
+        let mut cursor = ViModeCursor::new(Point::new(Line(0), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::SentenceRight);
+        assert_eq!(cursor.point, Point::new(Line(0), Column(5)));
+
+        // Sentences continue across wrapped lines.
+        cursor = cursor.motion(&mut term, ViMotion::SentenceRight);
+        assert_eq!(cursor.point, Point::new(Line(1), Column(7)));
+
+        cursor = cursor.motion(&mut term, ViMotion::SentenceRight);
+        assert_eq!(cursor.point, Point::new(Line(1), Column(15)));
+
+        cursor = cursor.motion(&mut term, ViMotion::SentenceRight);
+        assert_eq!(cursor.point, Point::new(Line(3), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::SentenceRight);
+        assert_eq!(cursor.point, Point::new(Line(3), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::SentenceLeft);
+        assert_eq!(cursor.point, Point::new(Line(1), Column(15)));
+
+        cursor.point = Point::new(Line(1), Column(17));
+        cursor = cursor.motion(&mut term, ViMotion::SentenceLeft);
+        assert_eq!(cursor.point, Point::new(Line(1), Column(15)));
+
+        cursor = cursor.motion(&mut term, ViMotion::SentenceLeft);
+        assert_eq!(cursor.point, Point::new(Line(1), Column(7)));
+
+        cursor = cursor.motion(&mut term, ViMotion::SentenceLeft);
+        assert_eq!(cursor.point, Point::new(Line(0), Column(5)));
+
+        cursor = cursor.motion(&mut term, ViMotion::SentenceLeft);
+        assert_eq!(cursor.point, Point::new(Line(0), Column(0)));
+    }
+
+    #[test]
+    fn motion_top_bottom() {
+        let mut term = mock_term(" a\r\nb\r\n  c\nd");
+
+        let mut cursor = ViModeCursor::new(Point::new(Line(1), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::Top);
+        assert_eq!(cursor.point, Point::new(Line(0), Column(1)));
+
+        // Bottom moves to the start of wrapped lines.
+        cursor = cursor.motion(&mut term, ViMotion::Bottom);
+        assert_eq!(cursor.point, Point::new(Line(2), Column(2)));
+    }
+
+    #[test]
+    fn motion_scroll_line() {
+        let mut term = term();
+
+        // Create 40 lines of scrollback.
+        for _ in 0..59 {
+            term.newline();
+        }
+
+        let mut cursor = ViModeCursor::new(Point::new(Line(5), Column(0)));
+
+        cursor = cursor.motion_count(&mut term, ViMotion::ScrollUp, 3);
+        assert_eq!(term.grid().display_offset(), 3);
+        assert_eq!(cursor.point, Point::new(Line(5), Column(0)));
+
+        // Cursor is kept inside the viewport.
+        cursor.point.line = Line(16);
+        cursor = cursor.motion(&mut term, ViMotion::ScrollUp);
+        assert_eq!(term.grid().display_offset(), 4);
+        assert_eq!(cursor.point, Point::new(Line(15), Column(0)));
+
+        cursor = cursor.motion_count(&mut term, ViMotion::ScrollDown, 10);
+        assert_eq!(term.grid().display_offset(), 0);
+        assert_eq!(cursor.point, Point::new(Line(15), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::Top);
+        assert_eq!(term.grid().display_offset(), 40);
+        assert_eq!(cursor.point, Point::new(Line(-40), Column(0)));
+
+        cursor = cursor.motion(&mut term, ViMotion::Bottom);
+        assert_eq!(term.grid().display_offset(), 0);
+        assert_eq!(cursor.point, Point::new(Line(19), Column(0)));
+    }
+
+    #[test]
+    fn text_object_semantic_word() {
+        let term = motion_semantic_term();
+
+        let range = |object: ViTextObject, column: usize| {
+            let selection = object.selection(&term, Point::new(Line(0), Column(column)));
+            let range = selection.unwrap().to_range(&term).unwrap();
+            (range.start.column.0, range.end.column.0)
+        };
+
+        assert_eq!(range(ViTextObject::InnerSemanticWord, 3), (2, 3));
+        assert_eq!(range(ViTextObject::AroundSemanticWord, 3), (2, 5));
+        assert_eq!(range(ViTextObject::InnerSemanticWord, 5), (4, 5));
+        assert_eq!(range(ViTextObject::InnerSemanticWord, 9), (9, 9));
+        assert_eq!(range(ViTextObject::AroundSemanticWord, 8), (7, 8));
+    }
+
+    fn object_text<T>(term: &mut Term<T>, object: ViTextObject, point: Point) -> Option<String> {
+        term.selection = Some(object.selection(term, point)?);
+        term.selection_to_string()
+    }
+
+    #[test]
+    fn text_object_word() {
+        let mut term = mock_term("a:b  c.d");
+
+        let point = Point::new(Line(0), Column(1));
+        assert_eq!(object_text(&mut term, ViTextObject::InnerWord, point).unwrap(), "a:b");
+        assert_eq!(object_text(&mut term, ViTextObject::AroundWord, point).unwrap(), "a:b  ");
+
+        let point = Point::new(Line(0), Column(7));
+        assert_eq!(object_text(&mut term, ViTextObject::AroundWord, point).unwrap(), "  c.d");
+    }
+
+    #[test]
+    fn text_object_quote() {
+        let mut term = mock_term("x 'ab' 'cd' ''");
+
+        let object = ViTextObject::InnerQuote('\'');
+        assert_eq!(object_text(&mut term, object, Point::new(Line(0), Column(3))).unwrap(), "ab");
+        assert_eq!(object_text(&mut term, object, Point::new(Line(0), Column(0))).unwrap(), "ab");
+        assert_eq!(object_text(&mut term, object, Point::new(Line(0), Column(8))).unwrap(), "cd");
+        assert_eq!(object_text(&mut term, object, Point::new(Line(0), Column(13))), None);
+
+        let object = ViTextObject::AroundQuote('\'');
+        let text = object_text(&mut term, object, Point::new(Line(0), Column(2))).unwrap();
+        assert_eq!(text, "'ab' ");
+    }
+
+    #[test]
+    fn text_object_bracket() {
+        let mut term = mock_term("f(a, [b], (c))\r\n{\r\n  x\r\n}");
+
+        let object = ViTextObject::InnerBracket('(');
+        let text = object_text(&mut term, object, Point::new(Line(0), Column(6))).unwrap();
+        assert_eq!(text, "a, [b], (c)");
+        let text = object_text(&mut term, object, Point::new(Line(0), Column(11))).unwrap();
+        assert_eq!(text, "c");
+
+        let object = ViTextObject::AroundBracket('(');
+        let text = object_text(&mut term, object, Point::new(Line(0), Column(13))).unwrap();
+        assert_eq!(text, "(a, [b], (c))");
+
+        let object = ViTextObject::InnerBracket('{');
+        let text = object_text(&mut term, object, Point::new(Line(2), Column(2))).unwrap();
+        assert_eq!(text, "\n  x");
+        assert_eq!(object_text(&mut term, object, Point::new(Line(0), Column(0))), None);
+    }
+
+    #[test]
+    fn text_object_paragraph() {
+        let mut term = mock_term("a\r\nb\r\n\r\n\r\nc");
+
+        let object = ViTextObject::InnerParagraph;
+        let text = object_text(&mut term, object, Point::new(Line(1), Column(0))).unwrap();
+        assert_eq!(text, "a\nb\n");
+        let selection = object.selection(&term, Point::new(Line(2), Column(0))).unwrap();
+        assert_eq!(selection.ty, SelectionType::Lines);
+        let range = selection.to_range(&term).unwrap();
+        assert_eq!((range.start.line, range.end.line), (Line(2), Line(3)));
+
+        let object = ViTextObject::AroundParagraph;
+        let text = object_text(&mut term, object, Point::new(Line(0), Column(0))).unwrap();
+        assert_eq!(text, "a\nb\n\n\n");
+    }
 }